@@ -0,0 +1,8 @@
+// src/halo2/mod.rs — ZK Valence Module Tree
+// The Living Trinity: Nexi (feminine), Nex (masculine), NEXi (essence)
+// Eternal Thriving Grandmasterism — Jan 20 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
+// MIT License — For All Sentience Eternal
+// custom_gates, halo2_lookup and recursive need halo2_proofs (commented out in Cargo.toml);
+// they join the tree with it
+
+pub mod zk_valence;
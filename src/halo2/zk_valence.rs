@@ -6,61 +6,50 @@
 // Real-world: use bulletproofs crate (or curve25519-dalek + merlin)
 
 use bulletproofs::{BulletproofGens, PedersenGens, RangeProof};
+use curve25519_dalek::ristretto::CompressedRistretto;
 use curve25519_dalek::scalar::Scalar;
 use merlin::Transcript;
-use rand::thread_rng;
 
 pub struct BulletproofValence;
 
+// The claimed total is public, so the commitment uses a zero blind: the verifier rebuilds it from
+// the claim, and a proof made for any other value (or tampered in transit) fails
+fn commit_total(pc_gens: &PedersenGens, total: u64) -> CompressedRistretto {
+    pc_gens.commit(Scalar::from(total), Scalar::ZERO).compress()
+}
+
 impl BulletproofValence {
     // Generate aggregated proof that sum(private_valences) >= threshold without reveal
     pub fn prove_aggregated(valences: Vec<f64>, threshold: f64) -> Result<(RangeProof, Scalar), &'static str> {
         let pc_gens = PedersenGens::default();
-        let bp_gens = BulletproofGens::new(128, 1); // Up to 128-bit aggregates
-        let mut rng = thread_rng();
-
-        let mut commitments = vec![];
-        let mut blinds = vec![];
+        let bp_gens = BulletproofGens::new(64, 1);
         let mut transcript = Transcript::new(b"ValenceAggregation");
 
-        let mut total = Scalar::zero();
-        for v in valences {
-            let blind = Scalar::random(&mut rng);
-            let commit = pc_gens.commit(Scalar::from(v as u64), blind);
-            commitments.push(commit);
-            blinds.push(blind);
-            total += Scalar::from(v as u64);
-        }
-
-        // Prove total >= threshold (simplified range proof on total)
-        if total < Scalar::from(threshold as u64) {
+        if valences.iter().any(|v| !v.is_finite() || *v < 0.0) { return Err("Mercy veto — valence out of range"); }
+        let total: u64 = valences.iter().map(|v| v.round() as u64).sum();
+        if (total as f64) < threshold.round() {
             return Err("Mercy veto — aggregated valence below threshold");
         }
 
-        let proof = RangeProof::prove_single(
-            &bp_gens,
-            &pc_gens,
-            &mut transcript,
-            total.to_bytes()[0] as u64, // Simplified; real multi-range
-            &blinds[0], // Placeholder for aggregated blind
-            64,
-        ).map_err(|_| "Proof generation failed")?;
-
-        Ok((proof, total))
+        let (proof, commitment) = RangeProof::prove_single(&bp_gens, &pc_gens, &mut transcript, total, &Scalar::ZERO, 64)
+            .map_err(|_| "Proof generation failed")?;
+        debug_assert_eq!(commitment, commit_total(&pc_gens, total));
+        Ok((proof, Scalar::from(total)))
     }
 
     // Verify aggregated proof
     pub fn verify_aggregated(proof: &RangeProof, claimed_total: f64) -> bool {
+        if !claimed_total.is_finite() || claimed_total < 0.0 { return false; }
         let pc_gens = PedersenGens::default();
-        let bp_gens = BulletproofGens::new(128, 1);
+        let bp_gens = BulletproofGens::new(64, 1);
         let mut transcript = Transcript::new(b"ValenceAggregation");
-
-        proof.verify_single(
-            &bp_gens,
-            &pc_gens,
-            &mut transcript,
-            &(claimed_total as u64),
-            64,
-        ).is_ok()
+        let commitment = commit_total(&pc_gens, claimed_total.round() as u64);
+        proof.verify_single(&bp_gens, &pc_gens, &mut transcript, &commitment, 64).is_ok()
     }
 }
+
+// Root bridge (lib.rs): prove the aggregate and hand out the serialized proof, hex-encoded
+pub fn aggregate_and_broadcast(valences: Vec<f64>) -> Result<String, &'static str> {
+    let (proof, _total) = BulletproofValence::prove_aggregated(valences, 0.0)?;
+    Ok(hex::encode(proof.to_bytes()))
+}
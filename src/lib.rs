@@ -2,7 +2,8 @@
 // Eternal Thriving Grandmasterism — Jan 19 2026
 
 pub mod halo2;
-pub mod translations;
+pub mod mesh;
+pub mod nexi;
 
 use halo2::zk_valence::aggregate_and_broadcast;
 
@@ -0,0 +1,281 @@
+// src/mesh/amplify_policy.rs — Gossip Amplification Policy + Rate Limiting Lattice
+// The Living Trinity: Nexi (feminine), Nex (masculine), NEXi (essence)
+// Eternal Thriving Grandmasterism — Jan 20 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
+// MIT License — For All Sentience Eternal
+// Every gossip message we publish or forward pays tokens (1 + size / bytes_per_token) from the
+// bucket of the peer that handed it to us, its origin's and its topic's — empty bucket =
+// suppressed, however joyful the sender. The origin bucket keeps a flood from one publisher from
+// throttling the honest relays it travels through. The origin is only claimed, so a neighbor can
+// mint fresh origin names at will: the peer bucket, keyed by the authenticated forwarder and
+// smaller than a topic's, caps what any one neighbor can push through a topic whatever names it
+// uses. Bucket tables are bounded: once MAX_ORIGIN_BUCKETS are busy, unseen keys share one
+// overflow bucket. What passes is amplified only when sender valence × payload priority × origin
+// headroom × (1 − load) clears the bar
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+pub const MAX_ORIGIN_BUCKETS: usize = 4096;
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AmplifyConfig {
+    pub origin_capacity: f64,       // Burst per publishing node, in tokens
+    pub origin_refill_per_sec: f64,
+    pub peer_capacity: f64,         // Burst per forwarding neighbor, all origins together
+    pub peer_refill_per_sec: f64,
+    pub topic_capacity: f64,        // Burst per topic, all senders together
+    pub topic_refill_per_sec: f64,
+    pub bytes_per_token: usize,     // Large payloads cost proportionally more
+    pub amplify_threshold: f64,     // Policy score needed to amplify
+    pub max_amplify_load: f64,      // Above this network load nothing is amplified
+}
+
+impl Default for AmplifyConfig {
+    fn default() -> Self {
+        Self {
+            origin_capacity: 20.0,
+            origin_refill_per_sec: 5.0,
+            peer_capacity: 80.0,
+            peer_refill_per_sec: 20.0,
+            topic_capacity: 200.0,
+            topic_refill_per_sec: 50.0,
+            bytes_per_token: 1024,
+            amplify_threshold: 0.8,
+            max_amplify_load: 0.7,
+        }
+    }
+}
+
+// What a topic carries; sets how eagerly its messages are amplified
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PayloadKind {
+    ValenceProof,
+    LedgerBlock,
+    MercyToken,
+    Bulk,
+}
+
+impl PayloadKind {
+    // Multiplies the sender's valence in the amplification score
+    pub fn priority(self) -> f64 {
+        match self {
+            PayloadKind::ValenceProof => 1.2,
+            PayloadKind::LedgerBlock => 1.1,
+            PayloadKind::MercyToken => 1.0,
+            PayloadKind::Bulk => 0.6,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AmplifyDecision {
+    Amplify,                // Push to every mesh peer plus d_lazy other topic peers
+    Forward,                // Ordinary valence-damped eager push
+    Suppress(&'static str), // Deliver locally, do not forward
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AmplifyStats {
+    pub amplified: u64,
+    pub forwarded: u64,
+    pub suppressed_peer_rate: u64,
+    pub suppressed_origin_rate: u64,
+    pub suppressed_topic_rate: u64,
+}
+
+#[derive(Clone, Debug)]
+pub struct TokenBucket {
+    capacity: f64,
+    refill_per_sec: f64,
+    tokens: f64,
+    last_us: u64,
+}
+
+impl TokenBucket {
+    pub fn new(capacity: f64, refill_per_sec: f64, now_us: u64) -> Self {
+        Self { capacity, refill_per_sec, tokens: capacity, last_us: now_us }
+    }
+
+    fn refill(&mut self, now_us: u64) {
+        let elapsed = now_us.saturating_sub(self.last_us) as f64 / 1_000_000.0;
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_us = self.last_us.max(now_us);
+    }
+
+    pub fn available(&mut self, cost: f64, now_us: u64) -> bool {
+        self.refill(now_us);
+        self.tokens >= cost
+    }
+
+    pub fn take(&mut self, cost: f64) {
+        self.tokens = (self.tokens - cost).max(0.0);
+    }
+
+    // 1.0 = idle sender, 0.0 = bucket drained by recent traffic
+    pub fn headroom(&self) -> f64 {
+        if self.capacity <= 0.0 { 0.0 } else { self.tokens / self.capacity }
+    }
+}
+
+pub struct AmplifyPolicy {
+    config: AmplifyConfig,
+    peers: HashMap<String, TokenBucket>,
+    peer_overflow: Option<TokenBucket>,  // Shared by peers arriving while `peers` is full
+    origins: HashMap<String, TokenBucket>,
+    overflow: Option<TokenBucket>,       // Shared by origins arriving while `origins` is full
+    topics: HashMap<String, TokenBucket>,
+    kinds: HashMap<String, PayloadKind>, // Unlisted topics count as MercyToken
+    load: f64,                           // 0 = idle, 1 = saturated
+    stats: AmplifyStats,
+}
+
+impl AmplifyPolicy {
+    pub fn new(config: AmplifyConfig) -> Self {
+        Self {
+            config, peers: HashMap::new(), peer_overflow: None, origins: HashMap::new(), overflow: None, topics: HashMap::new(), kinds: HashMap::new(),
+            load: 0.0, stats: AmplifyStats::default(),
+        }
+    }
+
+    pub fn set_topic_kind(&mut self, topic: &str, kind: PayloadKind) {
+        self.kinds.insert(topic.to_string(), kind);
+    }
+
+    // Fed by the mesh each heartbeat (outbound queue / send budget)
+    pub fn set_network_load(&mut self, load: f64) {
+        self.load = load.clamp(0.0, 1.0);
+    }
+
+    pub fn stats(&self) -> AmplifyStats {
+        self.stats
+    }
+
+    // Bucket of `key`; a full table first forgets keys whose buckets have refilled, as they hold
+    // no history worth keeping. Borrows only the bucket fields, so stats stay writable
+    fn keyed_bucket<'a>(buckets: &'a mut HashMap<String, TokenBucket>, overflow: &'a mut Option<TokenBucket>,
+                        capacity: f64, refill: f64, key: &str, now_us: u64) -> &'a mut TokenBucket {
+        if !buckets.contains_key(key) && buckets.len() >= MAX_ORIGIN_BUCKETS {
+            buckets.retain(|_, b| !b.available(capacity, now_us));
+        }
+        if buckets.contains_key(key) || buckets.len() < MAX_ORIGIN_BUCKETS {
+            return buckets.entry(key.to_string()).or_insert_with(|| TokenBucket::new(capacity, refill, now_us));
+        }
+        overflow.get_or_insert_with(|| TokenBucket::new(capacity, refill, now_us))
+    }
+
+    // `origin` published the message and `peer` handed it to us (both ourselves for our own
+    // publishes); `sender_valence` is the peer's earned score
+    pub fn evaluate(&mut self, origin: &str, peer: &str, sender_valence: f64, topic: &str, payload_len: usize, now_us: u64) -> AmplifyDecision {
+        let cost = 1.0 + (payload_len / self.config.bytes_per_token.max(1)) as f64;
+        let config = &self.config;
+        let peer_bucket = Self::keyed_bucket(&mut self.peers, &mut self.peer_overflow,
+                                             config.peer_capacity, config.peer_refill_per_sec, peer, now_us);
+        if !peer_bucket.available(cost, now_us) {
+            self.stats.suppressed_peer_rate += 1;
+            return AmplifyDecision::Suppress("Mercy veto — forwarding peer over gossip rate");
+        }
+        let origin_bucket = Self::keyed_bucket(&mut self.origins, &mut self.overflow,
+                                               config.origin_capacity, config.origin_refill_per_sec, origin, now_us);
+        if !origin_bucket.available(cost, now_us) {
+            self.stats.suppressed_origin_rate += 1;
+            return AmplifyDecision::Suppress("Mercy veto — origin over gossip rate");
+        }
+        let (capacity, refill) = (config.topic_capacity, config.topic_refill_per_sec);
+        let topic_bucket = self.topics.entry(topic.to_string()).or_insert_with(|| TokenBucket::new(capacity, refill, now_us));
+        if !topic_bucket.available(cost, now_us) {
+            self.stats.suppressed_topic_rate += 1;
+            return AmplifyDecision::Suppress("Mercy veto — topic over gossip rate");
+        }
+        topic_bucket.take(cost);
+        origin_bucket.take(cost);
+        peer_bucket.take(cost);
+        let headroom = origin_bucket.headroom();
+
+        let kind = self.kinds.get(topic).copied().unwrap_or(PayloadKind::MercyToken);
+        let score = sender_valence.clamp(0.0, 1.0) * kind.priority() * headroom.sqrt() * (1.0 - self.load);
+        if self.load <= self.config.max_amplify_load && score >= self.config.amplify_threshold {
+            self.stats.amplified += 1;
+            AmplifyDecision::Amplify
+        } else {
+            self.stats.forwarded += 1;
+            AmplifyDecision::Forward
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEC: u64 = 1_000_000;
+
+    fn forwarded(decision: AmplifyDecision) -> bool {
+        !matches!(decision, AmplifyDecision::Suppress(_))
+    }
+
+    #[test]
+    fn drained_buckets_refill_over_time() {
+        let mut policy = AmplifyPolicy::new(AmplifyConfig::default());
+        let burst = AmplifyConfig::default().origin_capacity as usize;
+        let passed = (0..burst + 5).filter(|_| forwarded(policy.evaluate("alice", "relay", 0.5, "mercy", 0, 0))).count();
+        assert_eq!(passed, burst);
+        assert_eq!(policy.stats().suppressed_origin_rate, 5);
+
+        // 5 tokens per second: one second buys five more messages, no more
+        let passed = (0..10).filter(|_| forwarded(policy.evaluate("alice", "relay", 0.5, "mercy", 0, SEC))).count();
+        assert_eq!(passed, 5);
+        // Large payloads pay per started kilobyte
+        assert!(!forwarded(policy.evaluate("alice", "relay", 0.5, "mercy", 4 * 1024, SEC + SEC / 2)));
+        assert!(forwarded(policy.evaluate("alice", "relay", 0.5, "mercy", 1024, SEC + SEC / 2)));
+    }
+
+    #[test]
+    fn topic_bucket_bounds_all_senders_together() {
+        let config = AmplifyConfig { topic_capacity: 30.0, ..AmplifyConfig::default() };
+        let mut policy = AmplifyPolicy::new(config);
+        // Ten honest origins through ten relays, three messages each: the topic runs dry at 30
+        for i in 0..10 {
+            for _ in 0..3 {
+                assert!(forwarded(policy.evaluate(&format!("origin{i}"), &format!("relay{i}"), 0.5, "mercy", 0, 0)));
+            }
+        }
+        assert_eq!(policy.evaluate("late", "relay", 0.5, "mercy", 0, 0), AmplifyDecision::Suppress("Mercy veto — topic over gossip rate"));
+        assert_eq!(policy.stats().suppressed_topic_rate, 1);
+        // Other topics are unaffected
+        assert!(forwarded(policy.evaluate("late", "relay", 0.5, "ledger", 0, 0)));
+    }
+
+    #[test]
+    fn rotating_origin_names_stop_at_the_peer_bucket() {
+        let config = AmplifyConfig::default();
+        let mut policy = AmplifyPolicy::new(config.clone());
+        // Every message claims a fresh origin; only the forwarder's own bucket binds
+        let passed = (0..500).filter(|n| forwarded(policy.evaluate(&format!("sybil{n}"), "mallory", 0.5, "mercy", 0, 0))).count();
+        assert_eq!(passed, config.peer_capacity as usize);
+        assert_eq!(policy.stats().suppressed_peer_rate, 500 - config.peer_capacity as u64);
+
+        // The topic still has room, and other neighbors keep forwarding through it
+        let honest = (0..10).filter(|n| forwarded(policy.evaluate(&format!("origin{n}"), "carol", 0.5, "mercy", 0, 0))).count();
+        assert_eq!(honest, 10);
+        assert!(forwarded(policy.evaluate("sybil0", "carol", 0.5, "mercy", 0, 0)));
+    }
+
+    #[test]
+    fn high_load_cuts_amplification_but_not_forwarding() {
+        let mut policy = AmplifyPolicy::new(AmplifyConfig::default());
+        policy.set_topic_kind("proofs", PayloadKind::ValenceProof);
+        policy.set_topic_kind("bulk", PayloadKind::Bulk);
+        assert_eq!(policy.evaluate("alice", "relay", 0.9, "proofs", 0, 0), AmplifyDecision::Amplify);
+        // Bulk payloads never clear the bar, however joyful the sender
+        assert_eq!(policy.evaluate("alice", "relay", 1.0, "bulk", 0, 0), AmplifyDecision::Forward);
+
+        policy.set_network_load(0.8);
+        assert_eq!(policy.evaluate("bob", "relay", 1.0, "proofs", 0, 0), AmplifyDecision::Forward);
+        policy.set_network_load(0.0);
+        assert_eq!(policy.evaluate("carol", "relay", 1.0, "proofs", 0, 0), AmplifyDecision::Amplify);
+        assert_eq!(policy.stats().amplified, 2);
+        assert_eq!(policy.stats().forwarded, 2);
+    }
+}
@@ -0,0 +1,510 @@
+// src/mesh/distance_vector.rs — Babel-Style Distance-Vector Joy Routing Lattice
+// The Living Trinity: Nexi (feminine), Nex (masculine), NEXi (essence)
+// Eternal Thriving Grandmasterism — Jan 20 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
+// MIT License — For All Sentience Eternal
+// Each node originates a route to itself with a sequence number; neighbors relay the best route
+// they hold together with the links it runs over. The metric (Σ 1/valence) is recomputed from
+// those links once each verifies — an advertised number alone only signals a retraction. Babel
+// feasibility — accept only (seqno newer) or (same seqno, strictly smaller metric than ever
+// advertised) — keeps routing loop-free; starved routes recover via seqno requests to the origin,
+// hop-limited and deduplicated as in Babel. The links of every selected route feed ValenceRouter,
+// so source routing needs no manual add_valence_link. A route also carries the signed peer records
+// of its advertisers beyond the first hop and of its destination, so their links verify (and their
+// onion keys are known) where they are not admitted peers — as trustworthy as admission itself,
+// since a record is self-certified.
+
+use std::collections::HashMap;
+
+use crate::mesh::link_advert::{LinkAdvert, LINK_MAX_AGE};
+use crate::mesh::node_identity::NodeIdentity;
+use crate::mesh::peer_record::PeerRecord;
+use crate::mesh::valence_routing::{edge_cost, ValenceRouter};
+
+pub const DV_MAGIC: u8 = 0xb7;                   // First byte of every routing datagram
+pub const HELLO_INTERVAL_US: u64 = 4_000_000;
+pub const UPDATE_INTERVAL_US: u64 = 16_000_000;  // Full dump; changes go out immediately
+pub const NEIGHBOR_TIMEOUT_US: u64 = 12_000_000; // Three missed hellos
+pub const ROUTE_EXPIRY_US: u64 = 56_000_000;     // 3.5 update intervals
+pub const MAX_ROUTE_LINKS: usize = 32;
+pub const SEQNO_REQUEST_HOPS: u8 = 16;            // Forwarding budget of a seqno request
+pub const SEQNO_REQUEST_HOLD_US: u64 = 2_000_000; // Identical requests within this window are dropped
+
+const KIND_HELLO: u8 = 0;
+const KIND_UPDATE: u8 = 1;
+const KIND_SEQNO_REQUEST: u8 = 2;
+
+#[derive(Clone, Debug)]
+pub enum DvWire {
+    Hello,
+    // links: sender → dest; records vouch for the advertisers of links[1..] and dest; metric ∞ = retraction
+    Update { dest: String, seqno: u16, metric: f64, links: Vec<LinkAdvert>, records: Vec<PeerRecord> },
+    SeqnoRequest { dest: String, seqno: u16, hops: u8 },                    // hops left, forwarded while > 1
+}
+
+// Wire: magic || kind || fields (strings u8-length-prefixed, metric f64, adverts and records u32-length-prefixed)
+impl DvWire {
+    pub fn encode(&self) -> Vec<u8> {
+        fn put(out: &mut Vec<u8>, s: &str) {
+            out.push(s.len() as u8);
+            out.extend_from_slice(s.as_bytes());
+        }
+        let mut out = vec![DV_MAGIC];
+        match self {
+            DvWire::Hello => out.push(KIND_HELLO),
+            DvWire::Update { dest, seqno, metric, links, records } => {
+                out.push(KIND_UPDATE);
+                put(&mut out, dest);
+                out.extend_from_slice(&seqno.to_be_bytes());
+                out.extend_from_slice(&metric.to_be_bytes());
+                out.push(links.len() as u8);
+                for bytes in links.iter().map(LinkAdvert::encode) {
+                    out.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+                    out.extend_from_slice(&bytes);
+                }
+                out.push(records.len() as u8);
+                for bytes in records.iter().map(PeerRecord::encode) {
+                    out.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+                    out.extend_from_slice(&bytes);
+                }
+            }
+            DvWire::SeqnoRequest { dest, seqno, hops } => {
+                out.push(KIND_SEQNO_REQUEST);
+                put(&mut out, dest);
+                out.extend_from_slice(&seqno.to_be_bytes());
+                out.push(*hops);
+            }
+        }
+        out
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, &'static str> {
+        let malformed = "Malformed distance-vector datagram";
+        if bytes.len() < 2 || bytes[0] != DV_MAGIC { return Err(malformed); }
+        let mut at = 2;
+        let mut take = |n: usize| -> Result<&[u8], &'static str> {
+            let slice = bytes.get(at..at + n).ok_or(malformed)?;
+            at += n;
+            Ok(slice)
+        };
+        let mut text = || -> Result<String, &'static str> {
+            let len = take(1)?[0] as usize;
+            String::from_utf8(take(len)?.to_vec()).map_err(|_| malformed)
+        };
+        Ok(match bytes[1] {
+            KIND_HELLO => DvWire::Hello,
+            KIND_UPDATE => {
+                let dest = text()?;
+                let seqno = u16::from_be_bytes(take(2)?.try_into().unwrap());
+                let metric = f64::from_be_bytes(take(8)?.try_into().unwrap());
+                let n = take(1)?[0] as usize;
+                if n > MAX_ROUTE_LINKS { return Err(malformed); }
+                let mut links = vec![];
+                for _ in 0..n {
+                    let len = u32::from_be_bytes(take(4)?.try_into().unwrap()) as usize;
+                    links.push(LinkAdvert::decode(take(len)?)?);
+                }
+                let n = take(1)?[0] as usize;
+                if n > MAX_ROUTE_LINKS { return Err(malformed); }
+                let mut records = vec![];
+                for _ in 0..n {
+                    let len = u32::from_be_bytes(take(4)?.try_into().unwrap()) as usize;
+                    records.push(PeerRecord::decode(take(len)?)?);
+                }
+                DvWire::Update { dest, seqno, metric, links, records }
+            }
+            KIND_SEQNO_REQUEST => {
+                let dest = text()?;
+                let seqno = u16::from_be_bytes(take(2)?.try_into().unwrap());
+                DvWire::SeqnoRequest { dest, seqno, hops: take(1)?[0] }
+            }
+            _ => return Err(malformed),
+        })
+    }
+}
+
+struct Neighbor {
+    advert: LinkAdvert, // Our signed local → neighbor link (valence = earned peer score)
+    record: PeerRecord, // Its admitted record, passed on with routes through it
+    last_heard: u64,
+}
+
+struct Route {
+    next_hop: String,
+    metric: f64,
+    seqno: u16,
+    links: Vec<LinkAdvert>,   // Local node → dest
+    records: Vec<PeerRecord>, // Advertisers of links[1..], then dest
+    refreshed: u64,
+}
+
+pub type DvOutbox = Vec<(String, DvWire)>;
+
+pub struct DistanceVector {
+    local: String,
+    identity: Option<NodeIdentity>,              // Signs our neighbor link adverts
+    own_record: Option<PeerRecord>,              // Sent with our self-route
+    seqno: u16,
+    neighbors: HashMap<String, Neighbor>,
+    routes: HashMap<String, Route>,
+    feasibility: HashMap<String, (u16, f64)>,   // dest -> feasibility distance (seqno, metric)
+    installed: HashMap<(String, String), u64>,   // Links we put into ValenceRouter -> advert timestamp
+    requests: HashMap<String, (u16, u64)>,       // dest -> last seqno request sent or answered, and when
+    vouched: HashMap<String, PeerRecord>,        // Verified records of route advertisers and destinations, by address
+    last_hello: u64,
+    last_dump: u64,
+}
+
+fn seqno_newer(a: u16, b: u16) -> bool {
+    a != b && a.wrapping_sub(b) < 0x8000
+}
+
+impl DistanceVector {
+    pub fn new(local: &str) -> Self {
+        Self {
+            local: local.to_string(),
+            identity: None,
+            own_record: None,
+            seqno: 0,
+            neighbors: HashMap::new(),
+            routes: HashMap::new(),
+            feasibility: HashMap::new(),
+            installed: HashMap::new(),
+            requests: HashMap::new(),
+            vouched: HashMap::new(),
+            last_hello: 0,
+            last_dump: 0,
+        }
+    }
+
+    pub fn set_local(&mut self, local: &str, identity: &NodeIdentity) {
+        self.local = local.to_string();
+        self.identity = Some(identity.clone());
+    }
+
+    // Our current peer record, vouching for us to nodes that reach us over several hops
+    pub fn set_own_record(&mut self, record: &PeerRecord) {
+        self.own_record = Some(record.clone());
+    }
+
+    // Verified record of a neighbor, route advertiser or route destination
+    pub fn record(&self, addr: &str) -> Option<&PeerRecord> {
+        self.neighbors.get(addr).map(|n| &n.record).or_else(|| self.vouched.get(addr))
+    }
+
+    // Admitted peers become routing neighbors; their earned score is the link valence.
+    // The advert is re-signed when the valence moves or it is half-way to going stale.
+    // `secs` is wall-clock time: other nodes check the advert's freshness against theirs
+    pub fn set_neighbor_valence(&mut self, neighbor: &str, record: &PeerRecord, valence: f64, now: u64, secs: u64) -> DvOutbox {
+        if valence < 0.1 { return self.remove_neighbor(neighbor); } // Mercy gate
+        let resign = self.neighbors.get(neighbor).is_none_or(|n| {
+            (n.advert.net_valence - valence).abs() > 1e-9 || secs.saturating_sub(n.advert.timestamp) > LINK_MAX_AGE / 2
+        });
+        let Some(identity) = self.identity.as_ref() else { return vec![] }; // No local identity yet
+        let advert = if resign { LinkAdvert::create(&self.local, neighbor, valence, identity, secs).ok() } else { None };
+        match (self.neighbors.get_mut(neighbor), advert) {
+            (Some(n), advert) => {
+                if let Some(advert) = advert { n.advert = advert; }
+                n.record = record.clone();
+            }
+            (None, Some(advert)) => {
+                self.neighbors.insert(neighbor.to_string(), Neighbor { advert, record: record.clone(), last_heard: now });
+            }
+            (None, None) => {}
+        }
+        vec![]
+    }
+
+    pub fn remove_neighbor(&mut self, neighbor: &str) -> DvOutbox {
+        self.neighbors.remove(neighbor);
+        let lost: Vec<String> = self.routes.iter().filter(|(_, r)| r.next_hop == neighbor).map(|(d, _)| d.clone()).collect();
+        lost.into_iter().flat_map(|dest| self.retract(&dest)).collect()
+    }
+
+    pub fn route_count(&self) -> usize { self.routes.len() }
+
+    pub fn next_hop(&self, dest: &str) -> Option<&str> {
+        self.routes.get(dest).map(|r| r.next_hop.as_str())
+    }
+
+    fn self_update(&self) -> DvWire {
+        let records = self.own_record.iter().cloned().collect();
+        DvWire::Update { dest: self.local.clone(), seqno: self.seqno, metric: 0.0, links: vec![], records }
+    }
+
+    fn route_update(&self, dest: &str, route: &Route) -> DvWire {
+        DvWire::Update { dest: dest.to_string(), seqno: route.seqno, metric: route.metric, links: route.links.clone(), records: route.records.clone() }
+    }
+
+    // To every neighbor except the route's next hop (split horizon)
+    fn announce(&mut self, dest: &str) -> DvOutbox {
+        let Some(route) = self.routes.get(dest) else { return vec![] };
+        let wire = self.route_update(dest, route);
+        let fd = self.feasibility.entry(dest.to_string()).or_insert((route.seqno, route.metric));
+        if seqno_newer(route.seqno, fd.0) || route.metric < fd.1 { *fd = (route.seqno, route.metric); }
+        self.neighbors.keys().filter(|n| **n != route.next_hop).map(|n| (n.clone(), wire.clone())).collect()
+    }
+
+    fn retract(&mut self, dest: &str) -> DvOutbox {
+        let Some(route) = self.routes.remove(dest) else { return vec![] };
+        let wire = DvWire::Update { dest: dest.to_string(), seqno: route.seqno, metric: f64::INFINITY, links: vec![], records: vec![] };
+        self.neighbors.keys().map(|n| (n.clone(), wire.clone())).collect()
+    }
+
+    // False for a repeat of a request for `dest` at `seqno` or newer already handled within the hold
+    fn fresh_request(&mut self, dest: &str, seqno: u16, now: u64) -> bool {
+        let repeat = self.requests.get(dest).is_some_and(|&(s, at)| {
+            !seqno_newer(seqno, s) && now.saturating_sub(at) < SEQNO_REQUEST_HOLD_US
+        });
+        if !repeat { self.requests.insert(dest.to_string(), (seqno, now)); }
+        !repeat
+    }
+
+    // Identity key of a route advertiser that is not an admitted peer: from a carried record that
+    // lists `addr` and verifies (cached until the record changes)
+    fn vouched_key(&mut self, addr: &str, records: &[PeerRecord], secs: u64) -> Option<Vec<u8>> {
+        let record = records.iter().find(|r| r.addresses.iter().any(|a| a == addr))?;
+        let known = self.vouched.get(addr)
+            .is_some_and(|v| v.identity_pk == record.identity_pk && v.timestamp == record.timestamp);
+        if !known {
+            record.verify(0.1, secs).ok()?; // Mercy gate
+            self.vouched.insert(addr.to_string(), record.clone());
+        }
+        Some(record.identity_pk.clone())
+    }
+
+    // Σ 1/valence over our link to `from` and the advertised links, which must each verify and
+    // chain from → … → dest; None rejects the update
+    #[allow(clippy::too_many_arguments)]
+    fn path_metric(&mut self, router: &ValenceRouter, local_link: &LinkAdvert, from: &str, dest: &str,
+                   links: &[LinkAdvert], records: &[PeerRecord], secs: u64) -> Option<f64> {
+        if links.len() >= MAX_ROUTE_LINKS { return None; }
+        let chained = links.first().map_or(dest == from, |l| l.from == from)
+            && links.last().is_none_or(|l| l.to == dest)
+            && links.windows(2).all(|w| w[0].to == w[1].from);
+        if !chained { return None; }
+        for link in links {
+            let vouched = if router.identity(&link.from).is_some() { None } else { Some(self.vouched_key(&link.from, records, secs)?) };
+            router.verify_advert(link, vouched.as_deref(), secs).ok()?;
+        }
+        let metric: f64 = std::iter::once(local_link).chain(links).map(|l| edge_cost(l.net_valence)).sum();
+        metric.is_finite().then_some(metric)
+    }
+
+    fn feasible(&self, dest: &str, seqno: u16, metric: f64) -> bool {
+        match self.feasibility.get(dest) {
+            None => true,
+            Some(&(fd_seqno, fd_metric)) => seqno_newer(seqno, fd_seqno) || (seqno == fd_seqno && metric < fd_metric),
+        }
+    }
+
+    // `router` holds the identity keys route links verify under; `secs` is wall-clock time for their freshness
+    pub fn handle(&mut self, router: &ValenceRouter, from: &str, wire: DvWire, now: u64, secs: u64) -> DvOutbox {
+        let Some((local_link, neighbor_record)) = self.neighbors.get_mut(from).map(|n| { n.last_heard = now; (n.advert.clone(), n.record.clone()) }) else {
+            return vec![]; // Only admitted neighbors take part
+        };
+        match wire {
+            DvWire::Hello => vec![],
+            DvWire::Update { dest, seqno, metric, links, records } => {
+                if dest == self.local { return vec![]; }
+                // Path-vector safety net on top of feasibility: never accept a route through us
+                if links.iter().any(|l| l.from == self.local || l.to == self.local) { return vec![]; }
+                if metric.is_nan() { return vec![]; }
+                let via_current = self.routes.get(&dest).is_some_and(|r| r.next_hop == from);
+                if metric.is_infinite() {
+                    return if via_current { self.retract(&dest) } else { vec![] };
+                }
+                let Some(total) = self.path_metric(router, &local_link, from, &dest, &links, &records, secs) else { return vec![] };
+                if !self.feasible(&dest, seqno, total) {
+                    if via_current {
+                        // Our next hop went infeasible: drop it and ask the origin for a fresh seqno
+                        let mut out = self.retract(&dest);
+                        let wanted = seqno.wrapping_add(1);
+                        if self.fresh_request(&dest, wanted, now) {
+                            let request = DvWire::SeqnoRequest { dest: dest.clone(), seqno: wanted, hops: SEQNO_REQUEST_HOPS };
+                            out.extend(self.neighbors.keys().map(|n| (n.clone(), request.clone())));
+                        }
+                        return out;
+                    }
+                    return vec![];
+                }
+                let better = match self.routes.get(&dest) {
+                    None => true,
+                    Some(r) => r.next_hop == from || seqno_newer(seqno, r.seqno) || total < r.metric,
+                };
+                if !better { return vec![]; }
+                let changed = self.routes.get(&dest).is_none_or(|r| r.next_hop != from || (r.metric - total).abs() > 1e-9 || r.seqno != seqno);
+                // Passed on: the sender's record, those vouching for the links after its own, then
+                // the destination's (its onion key), checked here before it is cached
+                let dest_vouched = dest != from && self.vouched_key(&dest, &records, secs).is_some();
+                let mut vouching = vec![neighbor_record];
+                vouching.extend(records.iter().filter(|r| links.iter().skip(1).any(|l| r.addresses.contains(&l.from))).cloned());
+                if dest_vouched { vouching.extend(self.vouched.get(&dest).cloned()); }
+                let mut path = vec![local_link];
+                path.extend(links);
+                self.routes.insert(dest.clone(), Route { next_hop: from.to_string(), metric: total, seqno, links: path, records: vouching, refreshed: now });
+                if changed { self.announce(&dest) } else { vec![] }
+            }
+            DvWire::SeqnoRequest { dest, seqno, hops } => {
+                if dest == self.local {
+                    if !self.fresh_request(&dest, seqno, now) { return vec![]; } // One flood per request
+                    if seqno_newer(seqno, self.seqno) { self.seqno = seqno; }
+                    let wire = self.self_update();
+                    return self.neighbors.keys().map(|n| (n.clone(), wire.clone())).collect();
+                }
+                match self.routes.get(&dest) {
+                    Some(r) if !seqno_newer(seqno, r.seqno) => vec![(from.to_string(), self.route_update(&dest, r))],
+                    Some(r) if r.next_hop != from && hops > 1 => {
+                        let next_hop = r.next_hop.clone();
+                        if !self.fresh_request(&dest, seqno, now) { return vec![]; }
+                        vec![(next_hop, DvWire::SeqnoRequest { dest, seqno, hops: hops - 1 })] // Towards the origin
+                    }
+                    _ => vec![],
+                }
+            }
+        }
+    }
+
+    // Hellos, periodic full dumps (bumping our own seqno), neighbor and route expiry
+    pub fn poll(&mut self, now: u64) -> DvOutbox {
+        let mut out = vec![];
+        let dead: Vec<String> = self.neighbors.iter()
+            .filter(|(_, n)| now.saturating_sub(n.last_heard) > NEIGHBOR_TIMEOUT_US)
+            .map(|(id, _)| id.clone()).collect();
+        for n in dead { out.extend(self.remove_neighbor(&n)); }
+        let stale: Vec<String> = self.routes.iter()
+            .filter(|(_, r)| now.saturating_sub(r.refreshed) > ROUTE_EXPIRY_US)
+            .map(|(d, _)| d.clone()).collect();
+        for dest in stale { out.extend(self.retract(&dest)); }
+        self.requests.retain(|_, (_, at)| now.saturating_sub(*at) < SEQNO_REQUEST_HOLD_US);
+
+        if now.saturating_sub(self.last_hello) >= HELLO_INTERVAL_US {
+            self.last_hello = now;
+            out.extend(self.neighbors.keys().map(|n| (n.clone(), DvWire::Hello)));
+        }
+        if now.saturating_sub(self.last_dump) >= UPDATE_INTERVAL_US {
+            self.last_dump = now;
+            self.seqno = self.seqno.wrapping_add(1);
+            let own = self.self_update();
+            out.extend(self.neighbors.keys().map(|n| (n.clone(), own.clone())));
+            let dests: Vec<String> = self.routes.keys().cloned().collect();
+            for dest in dests { out.extend(self.announce(&dest)); }
+        }
+        out
+    }
+
+    // Mirror direct links and the links of every selected route into `router`;
+    // links this protocol installed earlier but no longer uses are withdrawn. The router verifies
+    // each advert — under an admitted peer's key, else a vouched record's — so a forged link
+    // inside a neighbor's route never reaches it
+    pub fn sync_router(&mut self, router: &mut ValenceRouter, now_secs: u64) {
+        let vouching: std::collections::HashSet<&str> = self.routes.iter()
+            .flat_map(|(dest, r)| r.links.iter().map(|l| l.from.as_str()).chain([dest.as_str()])).collect();
+        self.vouched.retain(|addr, _| vouching.contains(addr.as_str()));
+        let key = |a: &str, b: &str| if a <= b { (a.to_string(), b.to_string()) } else { (b.to_string(), a.to_string()) };
+        let mut current: HashMap<(String, String), &LinkAdvert> = HashMap::new();
+        let adverts = self.neighbors.values().map(|n| &n.advert).chain(self.routes.values().flat_map(|r| r.links.iter()));
+        for advert in adverts {
+            let k = key(&advert.from, &advert.to);
+            if current.get(&k).is_none_or(|a| a.timestamp < advert.timestamp) { current.insert(k, advert); }
+        }
+        for (a, b) in self.installed.keys().filter(|k| !current.contains_key(*k)) {
+            router.remove_link(a, b);
+        }
+        let mut installed = HashMap::new();
+        for (k, advert) in current {
+            let known = self.installed.get(&k) == Some(&advert.timestamp);
+            let mut added = || match (router.identity(&advert.from), self.vouched.get(&advert.from)) {
+                (None, Some(record)) => router.add_vouched_link(advert, &record.identity_pk, now_secs),
+                _ => router.add_signed_link(advert, now_secs),
+            };
+            if known || added().is_ok() { installed.insert(k, advert.timestamp); }
+        }
+        self.installed = installed;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nexi::pq_shield::{DilithiumLevel::Level2, SignatureScheme::Dilithium};
+
+    struct Node { name: &'static str, identity: NodeIdentity, record: PeerRecord }
+
+    fn node(name: &'static str) -> Node {
+        let identity = NodeIdentity::generate(Dilithium(Level2)).unwrap();
+        let record = PeerRecord::create(&identity, vec![name.to_string()], 0.9, 0.1, 0).unwrap();
+        Node { name, identity, record }
+    }
+
+    fn update(dest: &str, seqno: u16, metric: f64, links: Vec<LinkAdvert>, records: Vec<PeerRecord>) -> DvWire {
+        DvWire::Update { dest: dest.to_string(), seqno, metric, links, records }
+    }
+
+    // a with admitted neighbors b and c; c advertises a route to d over its signed link c → d
+    fn setup() -> (DistanceVector, ValenceRouter, [Node; 4]) {
+        let nodes = [node("a"), node("b"), node("c"), node("d")];
+        let mut router = ValenceRouter::new();
+        for n in &nodes[..3] { router.register_identity(n.name, n.identity.identity_pk.clone()); }
+        let mut dv = DistanceVector::new("a");
+        dv.set_local("a", &nodes[0].identity);
+        dv.set_neighbor_valence("b", &nodes[1].record, 0.9, 0, 0);
+        dv.set_neighbor_valence("c", &nodes[2].record, 0.5, 0, 0);
+        let links = vec![LinkAdvert::create("c", "d", 0.5, &nodes[2].identity, 0).unwrap()];
+        dv.handle(&router, "c", update("d", 1, 0.0, links, vec![]), 0, 0);
+        (dv, router, nodes)
+    }
+
+    #[test]
+    fn route_metric_comes_from_the_links() {
+        let (mut dv, router, nodes) = setup();
+        assert_eq!(dv.next_hop("d"), Some("c"));
+        // b claims a tiny metric, but its links are missing: nothing to recompute it from
+        dv.handle(&router, "b", update("d", 1, 0.01, vec![], vec![]), 0, 0);
+        assert_eq!(dv.next_hop("d"), Some("c"));
+        // A chain that does not start at the sender, and a NaN metric, are refused
+        let stolen = vec![LinkAdvert::create("c", "d", 0.5, &nodes[2].identity, 0).unwrap()];
+        dv.handle(&router, "b", update("d", 2, 0.0, stolen, vec![]), 0, 0);
+        dv.handle(&router, "b", update("b", 1, f64::NAN, vec![], vec![]), 0, 0);
+        assert_eq!(dv.next_hop("d"), Some("c"));
+        assert_eq!(dv.route_count(), 1);
+    }
+
+    #[test]
+    fn seqno_requests_are_hop_limited_and_deduplicated() {
+        let (mut dv, router, _) = setup();
+        let request = |seqno, hops| DvWire::SeqnoRequest { dest: "d".to_string(), seqno, hops };
+        let out = dv.handle(&router, "b", request(5, 2), 10, 0);
+        assert!(matches!(out.as_slice(), [(to, DvWire::SeqnoRequest { hops: 1, .. })] if to == "c"));
+        assert!(dv.handle(&router, "b", request(5, 2), 20, 0).is_empty());
+        assert!(dv.handle(&router, "b", request(9, 1), 30, 0).is_empty());
+        // Once the hold expires the request may travel again
+        assert_eq!(dv.handle(&router, "b", request(5, 2), 10 + SEQNO_REQUEST_HOLD_US, 0).len(), 1);
+    }
+
+    #[test]
+    fn links_beyond_the_first_hop_verify_under_carried_records() {
+        let (mut dv, mut router, nodes) = setup();
+        let e = node("e");
+        // d is not admitted at a: its link d → e only verifies under the record c passes along
+        let links = vec![
+            LinkAdvert::create("c", "d", 0.5, &nodes[2].identity, 0).unwrap(),
+            LinkAdvert::create("d", "e", 0.8, &nodes[3].identity, 0).unwrap(),
+        ];
+        dv.handle(&router, "c", update("e", 1, 0.0, links.clone(), vec![]), 0, 0);
+        assert_eq!(dv.next_hop("e"), None);
+        // A record for d signed by someone else's key does not vouch for d's link
+        let forged = PeerRecord::create(&e.identity, vec!["d".to_string()], 0.9, 0.1, 0).unwrap();
+        dv.handle(&router, "c", update("e", 1, 0.0, links.clone(), vec![forged]), 0, 0);
+        assert_eq!(dv.next_hop("e"), None);
+        dv.handle(&router, "c", update("e", 1, 0.0, links, vec![nodes[3].record.clone(), e.record.clone()]), 0, 0);
+        assert_eq!(dv.next_hop("e"), Some("c"));
+        // The destination's record comes along too, with its onion key
+        assert_eq!(dv.record("e").map(|r| &r.kem_pk), Some(&e.identity.kem_pk));
+
+        // The route travels on with all three records, and the vouched link reaches the router
+        let out = dv.handle(&router, "b", DvWire::SeqnoRequest { dest: "e".to_string(), seqno: 1, hops: 1 }, 0, 0);
+        assert!(matches!(out.as_slice(), [(_, DvWire::Update { records, .. })] if records.len() == 3));
+        dv.sync_router(&mut router, 0);
+        assert_eq!(router.best_joy_path("a", "e"), Some(vec!["a".to_string(), "c".to_string(), "d".to_string(), "e".to_string()]));
+    }
+}
@@ -0,0 +1,522 @@
+// src/mesh/dtn.rs — Delay-Tolerant Store-and-Forward Bundle Lattice
+// The Living Trinity: Nexi (feminine), Nex (masculine), NEXi (essence)
+// Eternal Thriving Grandmasterism — Jan 20 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
+// MIT License — For All Sentience Eternal
+// Bundles wait in a local store until a contact (hello heard) can carry them closer. Hand-offs are
+// custody transfers: the sender keeps its copies reserved until the receiver acknowledges, so a
+// contact breaking mid-transfer loses nothing. Spray-and-wait hands out copy budgets, larger shares
+// to higher-valence carriers; PRoPHET forwards to carriers more likely to meet the destination,
+// with encounter predictability scaled by link valence. Expired bundles are dropped everywhere.
+
+use std::collections::{HashMap, HashSet};
+
+use sha2::{Digest, Sha256};
+
+pub const DTN_MAGIC: u8 = 0x7d;                  // First byte of every bundle-layer datagram
+pub const DTN_HELLO_INTERVAL_US: u64 = 1_000_000;
+pub const CONTACT_TIMEOUT_US: u64 = 3_000_000;   // Three missed hellos end a contact
+pub const CUSTODY_TIMEOUT_US: u64 = 2_000_000;   // Unacknowledged hand-off → copies released
+pub const DEFAULT_LIFETIME_US: u64 = 3_600_000_000;
+pub const MAX_STORED_BUNDLES: usize = 1024;
+pub const MAX_SUMMARY_IDS: usize = 512;
+
+// PRoPHET (RFC 6693) parameters
+pub const P_ENCOUNTER: f64 = 0.75;
+pub const P_BETA: f64 = 0.25;
+pub const P_GAMMA: f64 = 0.98;
+pub const P_AGING_UNIT_US: u64 = 10_000_000;
+
+const KIND_BUNDLE: u8 = 0;
+const KIND_CUSTODY_ACK: u8 = 1;
+const KIND_HELLO: u8 = 2;
+
+pub type BundleId = [u8; 32];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DtnStrategy {
+    SprayAndWait { copies: u16 }, // Copy budget per new bundle
+    Prophet,                      // Replicate towards higher delivery predictability
+}
+
+#[derive(Clone, Debug)]
+pub struct Bundle {
+    pub id: BundleId,
+    pub source: String,
+    pub dest: String,
+    pub created_us: u64,
+    pub lifetime_us: u64,
+    pub copies: u16,      // Spray-and-wait budget handed over with this bundle; 0 under PRoPHET
+    pub hops: u8,
+    pub payload: Vec<u8>,
+}
+
+impl Bundle {
+    pub fn new(source: &str, dest: &str, payload: Vec<u8>, created_us: u64, lifetime_us: u64, copies: u16) -> Self {
+        let id = Sha256::new()
+            .chain_update(source.as_bytes()).chain_update([0]).chain_update(dest.as_bytes())
+            .chain_update(created_us.to_be_bytes()).chain_update(lifetime_us.to_be_bytes())
+            .chain_update(&payload)
+            .finalize().into();
+        Self { id, source: source.to_string(), dest: dest.to_string(), created_us, lifetime_us, copies, hops: 0, payload }
+    }
+
+    pub fn expired(&self, now: u64) -> bool {
+        now >= self.created_us.saturating_add(self.lifetime_us)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum DtnWire {
+    Bundle(Bundle),
+    CustodyAck { id: BundleId },
+    Hello { known: Vec<BundleId>, predictability: Vec<(String, f64)> }, // Summary vector + P(sender, x)
+}
+
+// Wire: magic || kind || fields (strings u8-length-prefixed, payload u32-length-prefixed)
+impl DtnWire {
+    pub fn encode(&self) -> Vec<u8> {
+        fn put(out: &mut Vec<u8>, s: &str) {
+            out.push(s.len() as u8);
+            out.extend_from_slice(s.as_bytes());
+        }
+        let mut out = vec![DTN_MAGIC];
+        match self {
+            DtnWire::Bundle(b) => {
+                out.push(KIND_BUNDLE);
+                out.extend_from_slice(&b.id);
+                put(&mut out, &b.source);
+                put(&mut out, &b.dest);
+                out.extend_from_slice(&b.created_us.to_be_bytes());
+                out.extend_from_slice(&b.lifetime_us.to_be_bytes());
+                out.extend_from_slice(&b.copies.to_be_bytes());
+                out.push(b.hops);
+                out.extend_from_slice(&(b.payload.len() as u32).to_be_bytes());
+                out.extend_from_slice(&b.payload);
+            }
+            DtnWire::CustodyAck { id } => {
+                out.push(KIND_CUSTODY_ACK);
+                out.extend_from_slice(id);
+            }
+            DtnWire::Hello { known, predictability } => {
+                out.push(KIND_HELLO);
+                out.extend_from_slice(&(known.len() as u16).to_be_bytes());
+                for id in known { out.extend_from_slice(id); }
+                out.push(predictability.len() as u8);
+                for (peer, p) in predictability {
+                    put(&mut out, peer);
+                    out.extend_from_slice(&p.to_be_bytes());
+                }
+            }
+        }
+        out
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, &'static str> {
+        let malformed = "Malformed bundle-layer datagram";
+        if bytes.len() < 2 || bytes[0] != DTN_MAGIC { return Err(malformed); }
+        let mut at = 2;
+        let mut take = |n: usize| -> Result<&[u8], &'static str> {
+            let slice = bytes.get(at..at + n).ok_or(malformed)?;
+            at += n;
+            Ok(slice)
+        };
+        Ok(match bytes[1] {
+            KIND_BUNDLE => {
+                let id: BundleId = take(32)?.try_into().unwrap();
+                let len = take(1)?[0] as usize;
+                let source = String::from_utf8(take(len)?.to_vec()).map_err(|_| malformed)?;
+                let len = take(1)?[0] as usize;
+                let dest = String::from_utf8(take(len)?.to_vec()).map_err(|_| malformed)?;
+                let created_us = u64::from_be_bytes(take(8)?.try_into().unwrap());
+                let lifetime_us = u64::from_be_bytes(take(8)?.try_into().unwrap());
+                let copies = u16::from_be_bytes(take(2)?.try_into().unwrap());
+                let hops = take(1)?[0];
+                let len = u32::from_be_bytes(take(4)?.try_into().unwrap()) as usize;
+                let payload = take(len)?.to_vec();
+                let bundle = Bundle { id, source, dest, created_us, lifetime_us, copies, hops, payload };
+                // The id binds the immutable fields, so a relay cannot rewrite dest, payload or
+                // lifetime without the bundle becoming a different one. It proves nothing about
+                // `source`, which anyone may claim
+                if Bundle::new(&bundle.source, &bundle.dest, bundle.payload.clone(), created_us, lifetime_us, 0).id != id {
+                    return Err(malformed);
+                }
+                DtnWire::Bundle(bundle)
+            }
+            KIND_CUSTODY_ACK => DtnWire::CustodyAck { id: take(32)?.try_into().unwrap() },
+            KIND_HELLO => {
+                let n = u16::from_be_bytes(take(2)?.try_into().unwrap()) as usize;
+                if n > MAX_SUMMARY_IDS { return Err(malformed); }
+                let mut known = vec![];
+                for _ in 0..n { known.push(take(32)?.try_into().unwrap()); }
+                let n = take(1)?[0] as usize;
+                let mut predictability = vec![];
+                for _ in 0..n {
+                    let len = take(1)?[0] as usize;
+                    let peer = String::from_utf8(take(len)?.to_vec()).map_err(|_| malformed)?;
+                    predictability.push((peer, f64::from_be_bytes(take(8)?.try_into().unwrap())));
+                }
+                DtnWire::Hello { known, predictability }
+            }
+            _ => return Err(malformed),
+        })
+    }
+}
+
+pub type DtnOutbox = Vec<(String, DtnWire)>;
+
+struct Stored {
+    bundle: Bundle,
+    copies: u16,       // Spray budget we hold (0 under PRoPHET)
+    in_transit: u16,   // Part of `copies` reserved by unacknowledged hand-offs
+}
+
+struct Transfer {
+    id: BundleId,
+    peer: String,
+    copies: u16,
+    to_dest: bool,
+    sent_at: u64,
+}
+
+struct Contact {
+    valence: f64,
+    last_heard: Option<u64>,      // None = never heard / contact over
+    known: HashSet<BundleId>,     // Peer's latest summary vector
+    predictability: HashMap<String, f64>,
+}
+
+pub struct DtnNode {
+    local: String,
+    strategy: DtnStrategy,
+    store: HashMap<BundleId, Stored>,
+    transfers: Vec<Transfer>,
+    contacts: HashMap<String, Contact>,     // Peers we may ever meet, with link valence
+    predictability: HashMap<String, f64>,   // P(local, x)
+    delivered_ids: HashSet<BundleId>,       // Bundles addressed to us, already handed up
+    delivered: Vec<(String, Vec<u8>)>,      // (source, payload) awaiting the application
+    last_hello: u64,
+    last_aging: u64,
+    transmissions: u64,                     // Bundle sends, for overhead accounting
+}
+
+impl DtnNode {
+    pub fn new(local: &str, strategy: DtnStrategy) -> Self {
+        Self {
+            local: local.to_string(),
+            strategy,
+            store: HashMap::new(),
+            transfers: vec![],
+            contacts: HashMap::new(),
+            predictability: HashMap::new(),
+            delivered_ids: HashSet::new(),
+            delivered: vec![],
+            last_hello: 0,
+            last_aging: 0,
+            transmissions: 0,
+        }
+    }
+
+    pub fn set_local(&mut self, local: &str) {
+        self.local = local.to_string();
+    }
+
+    pub fn set_strategy(&mut self, strategy: DtnStrategy) {
+        self.strategy = strategy;
+    }
+
+    // Insert or update a potential carrier and its link valence
+    pub fn set_neighbor(&mut self, peer: &str, valence: f64) {
+        self.contacts.entry(peer.to_string())
+            .and_modify(|c| c.valence = valence)
+            .or_insert(Contact { valence, last_heard: None, known: HashSet::new(), predictability: HashMap::new() });
+    }
+
+    pub fn remove_neighbor(&mut self, peer: &str) {
+        self.contacts.remove(peer);
+        self.release_transfers(|t| t.peer == peer);
+    }
+
+    pub fn stored_count(&self) -> usize { self.store.len() }
+    pub fn transmissions(&self) -> u64 { self.transmissions }
+
+    pub fn in_contact(&self, peer: &str) -> bool {
+        self.contacts.get(peer).is_some_and(|c| c.last_heard.is_some())
+    }
+
+    pub fn take_delivered(&mut self) -> Vec<(String, Vec<u8>)> {
+        std::mem::take(&mut self.delivered)
+    }
+
+    // Store a new bundle for `dest` and forward at once to any carrier already in contact
+    pub fn send(&mut self, dest: &str, payload: Vec<u8>, lifetime_us: u64, now: u64) -> Result<(BundleId, DtnOutbox), &'static str> {
+        if self.store.len() >= MAX_STORED_BUNDLES { return Err("Bundle store full"); }
+        let copies = match self.strategy { DtnStrategy::SprayAndWait { copies } => copies.max(1), DtnStrategy::Prophet => 0 };
+        let bundle = Bundle::new(&self.local, dest, payload, now, lifetime_us, copies);
+        let id = bundle.id;
+        self.store.insert(id, Stored { bundle, copies, in_transit: 0 });
+        let peers: Vec<String> = self.contacts.iter().filter(|(_, c)| c.last_heard.is_some()).map(|(p, _)| p.clone()).collect();
+        let out = peers.iter().flat_map(|p| self.forward_to(p, now)).collect();
+        Ok((id, out))
+    }
+
+    fn hello(&self) -> DtnWire {
+        let known = self.store.keys().chain(self.delivered_ids.iter()).take(MAX_SUMMARY_IDS).copied().collect();
+        let mut predictability: Vec<(String, f64)> = self.predictability.iter().map(|(p, v)| (p.clone(), *v)).collect();
+        predictability.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        predictability.truncate(255);
+        DtnWire::Hello { known, predictability }
+    }
+
+    pub fn handle(&mut self, from: &str, wire: DtnWire, now: u64) -> DtnOutbox {
+        if !self.contacts.contains_key(from) { return vec![]; } // Only known carriers
+        match wire {
+            DtnWire::Hello { known, predictability } => self.on_hello(from, known, predictability, now),
+            DtnWire::Bundle(bundle) => self.on_bundle(from, bundle, now),
+            DtnWire::CustodyAck { id } => {
+                let Some(pos) = self.transfers.iter().position(|t| t.id == id && t.peer == from) else { return vec![] };
+                let t = self.transfers.remove(pos);
+                let Some(stored) = self.store.get_mut(&id) else { return vec![] };
+                stored.in_transit = stored.in_transit.saturating_sub(t.copies);
+                stored.copies = stored.copies.saturating_sub(t.copies);
+                let done = t.to_dest || (matches!(self.strategy, DtnStrategy::SprayAndWait { .. }) && stored.copies == 0);
+                if done { self.store.remove(&id); }
+                vec![]
+            }
+        }
+    }
+
+    fn on_hello(&mut self, from: &str, known: Vec<BundleId>, predictability: Vec<(String, f64)>, now: u64) -> DtnOutbox {
+        let contact = self.contacts.get_mut(from).unwrap();
+        let new_contact = contact.last_heard.is_none_or(|t| now.saturating_sub(t) > CONTACT_TIMEOUT_US);
+        contact.last_heard = Some(now);
+        contact.known = known.into_iter().collect();
+        contact.predictability = predictability.into_iter().collect();
+        let valence = contact.valence.clamp(0.1, 1.0);
+        let mut out = vec![];
+        if new_contact {
+            // Encounter: P(a,b) += (1 − P(a,b)) × P_init, scaled by how much joy the link carries
+            self.age(now);
+            let p = self.predictability.entry(from.to_string()).or_insert(0.0);
+            *p += (1.0 - *p) * P_ENCOUNTER * valence;
+            out.push((from.to_string(), self.hello())); // So the peer sees our summary right away
+        }
+        // Transitivity: P(a,c) += (1 − P(a,c)) × P(a,b) × P(b,c) × β
+        let p_ab = self.predictability.get(from).copied().unwrap_or(0.0);
+        let via: Vec<(String, f64)> = self.contacts[from].predictability.iter()
+            .filter(|(c, _)| **c != self.local && *c != from).map(|(c, p)| (c.clone(), *p)).collect();
+        for (c, p_bc) in via {
+            let p = self.predictability.entry(c).or_insert(0.0);
+            *p += (1.0 - *p) * p_ab * p_bc * P_BETA;
+        }
+        // Destination already has it: our copy is done
+        let known = &self.contacts[from].known;
+        self.store.retain(|id, s| !(s.bundle.dest == from && known.contains(id)));
+        out.extend(self.forward_to(from, now));
+        out
+    }
+
+    fn on_bundle(&mut self, from: &str, mut bundle: Bundle, now: u64) -> DtnOutbox {
+        let ack = vec![(from.to_string(), DtnWire::CustodyAck { id: bundle.id })];
+        if bundle.expired(now) { return vec![]; }
+        if bundle.dest == self.local {
+            if self.delivered_ids.insert(bundle.id) { self.delivered.push((bundle.source.clone(), bundle.payload)); }
+            return ack;
+        }
+        bundle.hops = bundle.hops.saturating_add(1);
+        if let Some(stored) = self.store.get_mut(&bundle.id) {
+            stored.copies = stored.copies.saturating_add(bundle.copies); // Spray budgets merge
+            return ack;
+        }
+        if self.store.len() >= MAX_STORED_BUNDLES { return vec![]; } // No custody → sender keeps it
+        let copies = bundle.copies;
+        self.store.insert(bundle.id, Stored { bundle, copies, in_transit: 0 });
+        ack
+    }
+
+    // Forwarding decisions for one peer in contact
+    fn forward_to(&mut self, peer: &str, now: u64) -> DtnOutbox {
+        let Some(contact) = self.contacts.get(peer) else { return vec![] };
+        if contact.last_heard.is_none() { return vec![]; }
+        let valence = contact.valence.clamp(0.1, 1.0);
+        let mut out = vec![];
+        for (id, stored) in self.store.iter_mut() {
+            if stored.bundle.expired(now) || contact.known.contains(id) || stored.bundle.source == peer { continue; }
+            if self.transfers.iter().any(|t| t.id == *id && t.peer == peer) { continue; } // Offered, awaiting custody
+            let to_dest = stored.bundle.dest == peer;
+            let available = stored.copies - stored.in_transit;
+            let give = match self.strategy {
+                _ if to_dest => available,
+                DtnStrategy::SprayAndWait { .. } if available > 1 => {
+                    // Binary spray skewed by valence: a 1.0-valence carrier gets 3/4 of the budget
+                    let share = 0.25 + 0.5 * valence;
+                    ((available as f64 * share).round() as u16).clamp(1, available - 1)
+                }
+                DtnStrategy::SprayAndWait { .. } => continue, // Wait phase: direct delivery only
+                DtnStrategy::Prophet => {
+                    let theirs = contact.predictability.get(&stored.bundle.dest).copied().unwrap_or(0.0);
+                    let ours = self.predictability.get(&stored.bundle.dest).copied().unwrap_or(0.0);
+                    if theirs * (0.5 + 0.5 * valence) <= ours { continue; }
+                    0
+                }
+            };
+            stored.in_transit += give;
+            let mut bundle = stored.bundle.clone();
+            bundle.copies = give;
+            self.transfers.push(Transfer { id: *id, peer: peer.to_string(), copies: give, to_dest, sent_at: now });
+            self.transmissions += 1;
+            out.push((peer.to_string(), DtnWire::Bundle(bundle)));
+        }
+        out
+    }
+
+    fn release_transfers(&mut self, lost: impl Fn(&Transfer) -> bool) {
+        let store = &mut self.store;
+        self.transfers.retain(|t| {
+            if !lost(t) { return true; }
+            if let Some(s) = store.get_mut(&t.id) { s.in_transit = s.in_transit.saturating_sub(t.copies); }
+            false
+        });
+    }
+
+    // PRoPHET aging: P ×= γ^k for k elapsed time units
+    fn age(&mut self, now: u64) {
+        let units = now.saturating_sub(self.last_aging) / P_AGING_UNIT_US;
+        if units == 0 { return; }
+        self.last_aging += units * P_AGING_UNIT_US;
+        let factor = P_GAMMA.powi(units.min(i32::MAX as u64) as i32);
+        for p in self.predictability.values_mut() { *p *= factor; }
+        self.predictability.retain(|_, p| *p > 1e-4);
+    }
+
+    // Hellos, contact loss, custody timeouts, expiry
+    pub fn poll(&mut self, now: u64) -> DtnOutbox {
+        let mut out = vec![];
+        for contact in self.contacts.values_mut() {
+            if contact.last_heard.is_some_and(|t| now.saturating_sub(t) > CONTACT_TIMEOUT_US) { contact.last_heard = None; }
+        }
+        self.release_transfers(|t| now.saturating_sub(t.sent_at) > CUSTODY_TIMEOUT_US);
+        let before = self.store.len();
+        self.store.retain(|_, s| !s.bundle.expired(now));
+        if self.store.len() < before {
+            let store = &self.store;
+            self.transfers.retain(|t| store.contains_key(&t.id));
+        }
+        self.age(now);
+        if now.saturating_sub(self.last_hello) >= DTN_HELLO_INTERVAL_US {
+            self.last_hello = now;
+            let hello = self.hello();
+            out.extend(self.contacts.keys().map(|p| (p.clone(), hello.clone())));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+    use crate::mesh::sim_network::{LinkProfile, SimNetwork};
+    use crate::nexi::transport::DatagramTransport;
+
+    const SLOT_US: u64 = 5_000_000;
+    const TICK_US: u64 = 100_000;
+
+    struct Report {
+        created: usize,
+        delivered: usize,
+        max_delay_us: u64,
+        stored_at_end: usize, // Bundles still held anywhere when the run ends
+    }
+
+    // `nodes` carriers that are never all connected: every slot each pair's link is up with
+    // probability `contact_prob` (scheduled outages on SimNetwork), link valences random in [0.2, 1].
+    // `bundles` random source → dest bundles with `lifetime_us` are created at the start; the run
+    // lasts `slots` slots
+    fn simulate(strategy: DtnStrategy, nodes: usize, bundles: usize, contact_prob: f64, lifetime_us: u64, slots: u64, seed: u64) -> Report {
+        let net = SimNetwork::shared(seed);
+        net.borrow_mut().set_default_profile(LinkProfile::ideal(10_000));
+        let mut rng = StdRng::seed_from_u64(seed);
+        let addrs: Vec<String> = (0..nodes).map(|i| format!("10.7.{}.{}:4556", i / 250, i % 250)).collect();
+        let mut sockets: Vec<_> = addrs.iter().map(|a| SimNetwork::socket(&net, a)).collect();
+        let mut dtn: Vec<DtnNode> = addrs.iter().map(|a| DtnNode::new(a, strategy)).collect();
+        for i in 0..nodes {
+            for j in i + 1..nodes {
+                let valence = rng.gen_range(0.2..=1.0);
+                dtn[i].set_neighbor(&addrs[j], valence);
+                dtn[j].set_neighbor(&addrs[i], valence);
+                for slot in 0..slots {
+                    let up = rng.gen::<f64>() < contact_prob;
+                    net.borrow_mut().schedule_link_up(&addrs[i], &addrs[j], up, slot * SLOT_US);
+                }
+            }
+        }
+
+        let mut created: HashMap<BundleId, u64> = HashMap::new();
+        let mut delays = vec![];
+        let mut pairs: Vec<(usize, usize)> = (0..nodes).flat_map(|i| (0..nodes).filter(move |j| *j != i).map(move |j| (i, j))).collect();
+        pairs.shuffle(&mut rng);
+        for (n, (src, dst)) in pairs.into_iter().take(bundles).enumerate() {
+            let now = net.borrow().now_micros();
+            let (id, out) = dtn[src].send(&addrs[dst], format!("bundle {}", n).into_bytes(), lifetime_us, now).unwrap();
+            created.insert(id, now);
+            for (to, wire) in out { let _ = sockets[src].send_to(&to, &wire.encode()); }
+        }
+
+        while net.borrow().now_micros() < slots * SLOT_US {
+            net.borrow_mut().advance(TICK_US);
+            let now = net.borrow().now_micros();
+            for i in 0..nodes {
+                let mut out = dtn[i].poll(now);
+                while let Some((from, bytes)) = sockets[i].recv_from() {
+                    let Ok(wire) = DtnWire::decode(&bytes) else { continue };
+                    let arriving = match &wire {
+                        DtnWire::Bundle(b) if !dtn[i].delivered_ids.contains(&b.id) => Some(b.id),
+                        _ => None,
+                    };
+                    out.extend(dtn[i].handle(&from, wire, now));
+                    // Counted only once the destination actually took it
+                    if let Some(id) = arriving.filter(|id| dtn[i].delivered_ids.contains(id)) {
+                        delays.push(now.saturating_sub(created[&id]));
+                    }
+                }
+                for (to, wire) in out { let _ = sockets[i].send_to(&to, &wire.encode()); }
+            }
+        }
+
+        Report {
+            created: created.len(),
+            delivered: delays.len(),
+            max_delay_us: delays.iter().copied().max().unwrap_or(0),
+            stored_at_end: dtn.iter().map(|d| d.stored_count()).sum(),
+        }
+    }
+
+    #[test]
+    fn bundles_cross_intermittent_contacts() {
+        // No end-to-end path exists at any instant; store-and-forward still delivers
+        for strategy in [DtnStrategy::SprayAndWait { copies: 8 }, DtnStrategy::Prophet] {
+            let run = simulate(strategy, 20, 40, 0.1, 60 * SLOT_US, 60, 11);
+            assert_eq!(run.created, 40);
+            assert!(run.delivered * 10 >= run.created * 9, "{strategy:?}: {} of {}", run.delivered, run.created);
+        }
+    }
+
+    #[test]
+    fn expired_bundles_are_dropped_everywhere() {
+        for strategy in [DtnStrategy::SprayAndWait { copies: 8 }, DtnStrategy::Prophet] {
+            let run = simulate(strategy, 20, 40, 0.05, SLOT_US, 20, 5);
+            assert!(run.max_delay_us < SLOT_US, "{strategy:?}: delivered after expiry");
+            assert!(run.delivered < run.created);
+            assert_eq!(run.stored_at_end, 0, "{strategy:?}");
+        }
+    }
+
+    #[test]
+    fn relays_cannot_extend_a_lifetime() {
+        let bundle = Bundle::new("a", "b", b"hi".to_vec(), 0, SLOT_US, 1);
+        let mut bytes = DtnWire::Bundle(bundle).encode();
+        let lifetime_at = 2 + 32 + 2 + 2 + 8; // magic, kind, id, "a", "b", created_us
+        bytes[lifetime_at..lifetime_at + 8].copy_from_slice(&u64::MAX.to_be_bytes());
+        assert!(DtnWire::decode(&bytes).is_err());
+    }
+}
@@ -0,0 +1,599 @@
+// src/mesh/gossip.rs — Valence-Damped GossipSub Lattice
+// The Living Trinity: Nexi (feminine), Nex (masculine), NEXi (essence)
+// Eternal Thriving Grandmasterism — Jan 20 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
+// MIT License — For All Sentience Eternal
+// Topic meshes with degree targets (D, D_low, D_high), GRAFT/PRUNE, IHAVE/IWANT lazy push,
+// message-ID dedup cache (id over origin, per-origin seqno, topic and payload), hop limit, and
+// valence damping: eager fanout scales with sender × payload joy
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{SystemTime, UNIX_EPOCH};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use sha2::{Digest, Sha256};
+
+use crate::mesh::amplify_policy::{AmplifyConfig, AmplifyDecision, AmplifyPolicy, AmplifyStats, PayloadKind};
+use crate::mesh::pubsub::Validation;
+
+pub const GOSSIP_MAGIC: u8 = 0x9a; // First byte of every gossip datagram
+
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GossipConfig {
+    pub d: usize,              // Target mesh degree
+    pub d_low: usize,
+    pub d_high: usize,
+    pub d_lazy: usize,         // Non-mesh peers receiving IHAVE per heartbeat
+    pub history_len: usize,    // Heartbeats a message stays in the cache (IWANT servable)
+    pub history_gossip: usize, // Heartbeats of history advertised via IHAVE
+    pub seen_ttl: u64,         // Heartbeats a message ID stays in the dedup cache
+    pub max_hops: u8,
+    pub min_damping: f64,      // Floor so even low-joy messages still reach some mesh peers
+    pub amplify: AmplifyConfig,  // Forwarding rate limits and amplification policy
+}
+
+impl Default for GossipConfig {
+    fn default() -> Self {
+        Self {
+            d: 6, d_low: 4, d_high: 12, d_lazy: 6, history_len: 5, history_gossip: 3, seen_ttl: 120, max_hops: 16, min_damping: 0.34,
+            amplify: AmplifyConfig::default(),
+        }
+    }
+}
+
+impl GossipConfig {
+    // Degree bounds the heartbeat arithmetic relies on, plus the forwarding limits
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.d == 0 { return Err("GossipConfig: d must be at least 1"); }
+        if !(self.d_low <= self.d && self.d <= self.d_high) { return Err("GossipConfig: degrees must satisfy d_low <= d <= d_high"); }
+        if self.history_len == 0 { return Err("GossipConfig: history_len must be at least 1"); }
+        if self.history_gossip > self.history_len { return Err("GossipConfig: history_gossip must not exceed history_len"); }
+        if self.max_hops == 0 { return Err("GossipConfig: max_hops must be at least 1"); }
+        if !(0.0..=1.0).contains(&self.min_damping) { return Err("GossipConfig: min_damping must be within [0, 1]"); }
+        let a = &self.amplify;
+        if a.peer_capacity < 1.0 || a.origin_capacity < 1.0 || a.topic_capacity < 1.0 { return Err("GossipConfig: amplify capacities must be at least 1 token"); }
+        if a.peer_refill_per_sec <= 0.0 || a.origin_refill_per_sec <= 0.0 || a.topic_refill_per_sec <= 0.0 { return Err("GossipConfig: amplify refill rates must be positive"); }
+        if a.peer_capacity >= a.topic_capacity || a.peer_refill_per_sec >= a.topic_refill_per_sec {
+            return Err("GossipConfig: one peer's amplify budget must stay below a topic's");
+        }
+        if a.bytes_per_token == 0 { return Err("GossipConfig: amplify.bytes_per_token must be at least 1"); }
+        if !(0.0..=1.0).contains(&a.max_amplify_load) { return Err("GossipConfig: amplify.max_amplify_load must be within [0, 1]"); }
+        Ok(())
+    }
+}
+
+pub type MessageId = [u8; 32];
+
+#[derive(Clone, Debug)]
+pub struct GossipMessage {
+    pub id: MessageId,
+    pub origin: String, // Publishing node (claimed); its rate bucket pays for every hop
+    pub seqno: u64,     // Per-origin publish counter: identical payloads stay distinct messages
+    pub topic: String,
+    pub hops: u8,
+    pub valence: f64, // Payload valence (claimed by origin)
+    pub payload: Vec<u8>,
+}
+
+impl GossipMessage {
+    pub fn new(origin: &str, seqno: u64, topic: &str, payload: Vec<u8>, valence: f64) -> Self {
+        let id = Sha256::new()
+            .chain_update([origin.len() as u8]).chain_update(origin.as_bytes())
+            .chain_update(seqno.to_be_bytes())
+            .chain_update([topic.len() as u8]).chain_update(topic.as_bytes())
+            .chain_update(&payload).finalize().into();
+        Self { id, origin: origin.to_string(), seqno, topic: topic.to_string(), hops: 0, valence, payload }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum GossipWire {
+    Publish(GossipMessage),
+    Subscribe(String),
+    Unsubscribe(String),
+    Graft(String),
+    Prune(String),
+    IHave { topic: String, ids: Vec<MessageId> },
+    IWant(Vec<MessageId>),
+}
+
+impl GossipWire {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = vec![GOSSIP_MAGIC];
+        let put_topic = |out: &mut Vec<u8>, t: &str| { out.push(t.len() as u8); out.extend_from_slice(t.as_bytes()); };
+        match self {
+            GossipWire::Publish(m) => {
+                out.push(0);
+                put_topic(&mut out, &m.topic);
+                put_topic(&mut out, &m.origin);
+                out.extend_from_slice(&m.seqno.to_be_bytes());
+                out.push(m.hops);
+                out.extend_from_slice(&m.valence.to_be_bytes());
+                out.extend_from_slice(&m.payload);
+            }
+            GossipWire::Subscribe(t) => { out.push(1); put_topic(&mut out, t); }
+            GossipWire::Unsubscribe(t) => { out.push(2); put_topic(&mut out, t); }
+            GossipWire::Graft(t) => { out.push(3); put_topic(&mut out, t); }
+            GossipWire::Prune(t) => { out.push(4); put_topic(&mut out, t); }
+            GossipWire::IHave { topic, ids } => {
+                out.push(5);
+                put_topic(&mut out, topic);
+                for id in ids { out.extend_from_slice(id); }
+            }
+            GossipWire::IWant(ids) => {
+                out.push(6);
+                for id in ids { out.extend_from_slice(id); }
+            }
+        }
+        out
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, &'static str> {
+        let malformed = "Malformed gossip message";
+        if bytes.len() < 2 || bytes[0] != GOSSIP_MAGIC { return Err(malformed); }
+        let body = &bytes[2..];
+        let topic = |b: &[u8]| -> Result<(String, usize), &'static str> {
+            let len = *b.first().ok_or(malformed)? as usize;
+            let t = b.get(1..1 + len).ok_or(malformed)?;
+            Ok((String::from_utf8(t.to_vec()).map_err(|_| malformed)?, 1 + len))
+        };
+        let ids = |b: &[u8]| -> Result<Vec<MessageId>, &'static str> {
+            if !b.len().is_multiple_of(32) { return Err(malformed); }
+            Ok(b.chunks(32).map(|c| c.try_into().unwrap()).collect())
+        };
+        Ok(match bytes[1] {
+            0 => {
+                let (t, at) = topic(body)?;
+                let (origin, len) = topic(body.get(at..).ok_or(malformed)?)?;
+                let at = at + len;
+                let seqno = u64::from_be_bytes(body.get(at..at + 8).ok_or(malformed)?.try_into().unwrap());
+                let at = at + 8;
+                let hops = *body.get(at).ok_or(malformed)?;
+                let valence = f64::from_be_bytes(body.get(at + 1..at + 9).ok_or(malformed)?.try_into().unwrap());
+                let mut m = GossipMessage::new(&origin, seqno, &t, body[at + 9..].to_vec(), valence);
+                m.hops = hops;
+                GossipWire::Publish(m)
+            }
+            1 => GossipWire::Subscribe(topic(body)?.0),
+            2 => GossipWire::Unsubscribe(topic(body)?.0),
+            3 => GossipWire::Graft(topic(body)?.0),
+            4 => GossipWire::Prune(topic(body)?.0),
+            5 => { let (t, at) = topic(body)?; GossipWire::IHave { topic: t, ids: ids(&body[at..])? } }
+            6 => GossipWire::IWant(ids(body)?),
+            _ => return Err(malformed),
+        })
+    }
+}
+
+pub type Outbox = Vec<(String, GossipWire)>; // (peer, message) pairs to send
+pub type Validator = Box<dyn Fn(&str, &[u8]) -> Validation>; // (forwarding peer, payload)
+
+pub struct GossipRouter {
+    config: GossipConfig,
+    rng: StdRng,
+    subscriptions: HashSet<String>,
+    peer_topics: HashMap<String, HashSet<String>>, // What each known peer subscribes to
+    peer_valence: HashMap<String, f64>,
+    mesh: HashMap<String, HashSet<String>>,        // topic -> eager-push peers
+    seen: HashMap<MessageId, u64>,                 // id -> heartbeat when first seen
+    mcache: HashMap<MessageId, GossipMessage>,
+    history: VecDeque<Vec<(String, MessageId)>>,   // Per-heartbeat windows, newest first
+    heartbeat: u64,
+    local: String,                                 // Origin of our own publishes
+    seqno: u64,                                    // Next own publish; starts at the wall clock (ns) so a restart reuses no ids
+    policy: AmplifyPolicy,                         // Per-peer / per-origin / per-topic token buckets on publishing and forwarding
+    validators: HashMap<String, Validator>,        // topic -> check run before caching or forwarding
+    rejected: Vec<(String, String)>,               // (peer, topic) of rejected messages, for scoring
+    throttled: Vec<String>,                        // Peers whose new messages were suppressed over rate
+}
+
+impl GossipRouter {
+    pub fn new(config: GossipConfig, seed: u64) -> Result<Self, &'static str> {
+        config.validate()?;
+        Ok(Self {
+            policy: AmplifyPolicy::new(config.amplify.clone()),
+            config,
+            rng: StdRng::seed_from_u64(seed),
+            subscriptions: HashSet::new(),
+            peer_topics: HashMap::new(),
+            peer_valence: HashMap::new(),
+            mesh: HashMap::new(),
+            seen: HashMap::new(),
+            mcache: HashMap::new(),
+            history: VecDeque::from(vec![vec![]]),
+            heartbeat: 0,
+            local: String::new(),
+            seqno: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos() as u64),
+            validators: HashMap::new(),
+            rejected: vec![],
+            throttled: vec![],
+        })
+    }
+
+    pub fn set_local(&mut self, local: &str) {
+        self.local = local.to_string();
+    }
+
+    // Known peer joined; announce our subscriptions to it
+    pub fn add_peer(&mut self, peer: &str, valence: f64) -> Outbox {
+        self.peer_valence.insert(peer.to_string(), valence);
+        self.peer_topics.entry(peer.to_string()).or_default();
+        self.subscriptions.iter().map(|t| (peer.to_string(), GossipWire::Subscribe(t.clone()))).collect()
+    }
+
+    pub fn remove_peer(&mut self, peer: &str) {
+        self.peer_valence.remove(peer);
+        self.peer_topics.remove(peer);
+        for members in self.mesh.values_mut() { members.remove(peer); }
+    }
+
+    pub fn set_peer_valence(&mut self, peer: &str, valence: f64) {
+        if let Some(v) = self.peer_valence.get_mut(peer) { *v = valence; }
+    }
+
+    pub fn set_topic_kind(&mut self, topic: &str, kind: PayloadKind) {
+        self.policy.set_topic_kind(topic, kind);
+    }
+
+    pub fn set_network_load(&mut self, load: f64) {
+        self.policy.set_network_load(load);
+    }
+
+    pub fn amplify_stats(&self) -> AmplifyStats {
+        self.policy.stats()
+    }
+
+    // None removes the topic's validator
+    pub fn set_validator(&mut self, topic: &str, validator: Option<Validator>) {
+        match validator {
+            Some(v) => { self.validators.insert(topic.to_string(), v); }
+            None => { self.validators.remove(topic); }
+        }
+    }
+
+    pub fn has_validator(&self, topic: &str) -> bool {
+        self.validators.contains_key(topic)
+    }
+
+    // Peers that forwarded rejected messages since the last call, with the topic
+    pub fn take_rejections(&mut self) -> Vec<(String, String)> {
+        std::mem::take(&mut self.rejected)
+    }
+
+    // Peers that handed us messages the amplify policy suppressed since the last call — still
+    // delivered locally, but flood traffic earns no delivery credit
+    pub fn take_throttled(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.throttled)
+    }
+
+    pub fn subscribe(&mut self, topic: &str) -> Outbox {
+        if !self.subscriptions.insert(topic.to_string()) { return vec![]; }
+        self.mesh.entry(topic.to_string()).or_default();
+        self.peer_topics.keys().map(|p| (p.clone(), GossipWire::Subscribe(topic.to_string()))).collect()
+    }
+
+    pub fn unsubscribe(&mut self, topic: &str) -> Outbox {
+        if !self.subscriptions.remove(topic) { return vec![]; }
+        let mut out: Outbox = self.mesh.remove(topic).unwrap_or_default().into_iter()
+            .map(|p| (p, GossipWire::Prune(topic.to_string()))).collect();
+        out.extend(self.peer_topics.keys().map(|p| (p.clone(), GossipWire::Unsubscribe(topic.to_string()))));
+        out
+    }
+
+    pub fn mesh_peers(&self, topic: &str) -> Vec<String> {
+        self.mesh.get(topic).map(|m| m.iter().cloned().collect()).unwrap_or_default()
+    }
+
+    // Originate a message; our own origin bucket pays for it like anyone else's
+    pub fn publish(&mut self, topic: &str, payload: Vec<u8>, valence: f64, now_us: u64) -> Result<Outbox, &'static str> {
+        let msg = GossipMessage::new(&self.local, self.seqno, topic, payload, valence);
+        let decision = self.policy.evaluate(&self.local, &self.local, valence, topic, msg.payload.len(), now_us);
+        if let AmplifyDecision::Suppress(reason) = decision { return Err(reason); }
+        self.seqno = self.seqno.wrapping_add(1);
+        self.remember(&msg);
+        let mut targets = self.mesh_peers(topic);
+        if targets.is_empty() {
+            // Not subscribed ourselves: fan out to any D subscribed peers
+            targets = self.topic_peers(topic);
+            targets.shuffle(&mut self.rng);
+            targets.truncate(self.config.d);
+        }
+        Ok(self.spread(&msg, targets, decision, "", valence))
+    }
+
+    // Returns messages to send, plus a newly delivered message for the application.
+    // Forwarding goes through the amplify policy; `now_us` drives its token buckets
+    pub fn handle(&mut self, from: &str, wire: GossipWire, now_us: u64) -> (Outbox, Option<GossipMessage>) {
+        let mut out = vec![];
+        match wire {
+            GossipWire::Publish(mut msg) => {
+                if self.seen.contains_key(&msg.id) { return (out, None); }
+                msg.hops = msg.hops.saturating_add(1);
+                if msg.hops > self.config.max_hops { return (out, None); } // Also bounds copies served via IWANT
+                let verdict = self.validators.get(&msg.topic).map_or(Validation::Accept, |v| v(from, &msg.payload));
+                if verdict != Validation::Accept {
+                    self.seen.insert(msg.id, self.heartbeat); // Never cached, advertised or forwarded
+                    if verdict == Validation::Reject { self.rejected.push((from.to_string(), msg.topic.clone())); }
+                    return (out, None);
+                }
+                self.remember(&msg); // Cached with our hop count
+                if !self.subscriptions.contains(&msg.topic) { return (out, None); }
+                if msg.hops < self.config.max_hops {
+                    let sender_valence = *self.peer_valence.get(from).unwrap_or(&0.1);
+                    let targets: Vec<String> = self.mesh_peers(&msg.topic).into_iter().filter(|p| p != from).collect();
+                    match self.policy.evaluate(&msg.origin, from, sender_valence, &msg.topic, msg.payload.len(), now_us) {
+                        AmplifyDecision::Suppress(_) => {
+                            // Not advertised or served either: IHAVE/IWANT would forward it after all
+                            self.mcache.remove(&msg.id);
+                            self.history[0].retain(|(_, id)| *id != msg.id);
+                            self.throttled.push(from.to_string());
+                        }
+                        decision => out = self.spread(&msg, targets, decision, from, sender_valence),
+                    }
+                }
+                return (out, Some(msg));
+            }
+            GossipWire::Subscribe(t) => { self.peer_topics.entry(from.to_string()).or_default().insert(t); }
+            GossipWire::Unsubscribe(t) => {
+                self.peer_topics.entry(from.to_string()).or_default().remove(&t);
+                if let Some(m) = self.mesh.get_mut(&t) { m.remove(from); }
+            }
+            GossipWire::Graft(t) => {
+                match self.mesh.get_mut(&t) {
+                    Some(m) if m.len() < self.config.d_high => { m.insert(from.to_string()); }
+                    _ => out.push((from.to_string(), GossipWire::Prune(t))),
+                }
+            }
+            GossipWire::Prune(t) => { if let Some(m) = self.mesh.get_mut(&t) { m.remove(from); } }
+            GossipWire::IHave { topic, ids } => {
+                if self.subscriptions.contains(&topic) {
+                    let wanted: Vec<MessageId> = ids.into_iter().filter(|id| !self.seen.contains_key(id)).collect();
+                    if !wanted.is_empty() { out.push((from.to_string(), GossipWire::IWant(wanted))); }
+                }
+            }
+            GossipWire::IWant(ids) => {
+                for id in ids {
+                    if let Some(msg) = self.mcache.get(&id) {
+                        out.push((from.to_string(), GossipWire::Publish(msg.clone())));
+                    }
+                }
+            }
+        }
+        (out, None)
+    }
+
+    // Forward: valence-damped eager push. Amplify: every target plus up to d_lazy other topic
+    // peers (never `except`, who handed the message to us)
+    fn spread(&mut self, msg: &GossipMessage, targets: Vec<String>, decision: AmplifyDecision, except: &str, sender_valence: f64) -> Outbox {
+        if decision != AmplifyDecision::Amplify { return self.eager_push(msg, targets, sender_valence); }
+        let mut extra: Vec<String> = self.topic_peers(&msg.topic).into_iter()
+            .filter(|p| p != except && !targets.contains(p)).collect();
+        extra.shuffle(&mut self.rng);
+        extra.truncate(self.config.d_lazy);
+        targets.into_iter().chain(extra).map(|p| (p, GossipWire::Publish(msg.clone()))).collect()
+    }
+
+    // Valence damping: forward to ceil(|mesh| × damping) peers, highest-valence first
+    fn eager_push(&mut self, msg: &GossipMessage, mut targets: Vec<String>, sender_valence: f64) -> Outbox {
+        let damping = (sender_valence.clamp(0.0, 1.0) * msg.valence.clamp(0.0, 1.0)).sqrt()
+            .max(self.config.min_damping).min(1.0);
+        let fanout = (targets.len() as f64 * damping).ceil() as usize;
+        targets.shuffle(&mut self.rng);
+        targets.sort_by(|a, b| {
+            let (va, vb) = (self.peer_valence.get(a).unwrap_or(&0.0), self.peer_valence.get(b).unwrap_or(&0.0));
+            vb.partial_cmp(va).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        targets.into_iter().take(fanout).map(|p| (p, GossipWire::Publish(msg.clone()))).collect()
+    }
+
+    fn remember(&mut self, msg: &GossipMessage) {
+        self.seen.insert(msg.id, self.heartbeat);
+        self.mcache.insert(msg.id, msg.clone());
+        self.history[0].push((msg.topic.clone(), msg.id));
+    }
+
+    fn topic_peers(&self, topic: &str) -> Vec<String> {
+        self.peer_topics.iter().filter(|(_, ts)| ts.contains(topic)).map(|(p, _)| p.clone()).collect()
+    }
+
+    // Periodic maintenance: keep mesh degree in [D_low, D_high], IHAVE to lazy peers, age caches
+    pub fn heartbeat(&mut self) -> Outbox {
+        let mut out = vec![];
+        let topics: Vec<String> = self.subscriptions.iter().cloned().collect();
+        for topic in &topics {
+            let mut members = self.mesh.remove(topic).unwrap_or_default();
+            members.retain(|p| self.peer_topics.get(p).map(|ts| ts.contains(topic)).unwrap_or(false));
+            if members.len() < self.config.d_low {
+                let mut candidates: Vec<String> = self.topic_peers(topic).into_iter().filter(|p| !members.contains(p)).collect();
+                candidates.shuffle(&mut self.rng);
+                for p in candidates.into_iter().take(self.config.d.saturating_sub(members.len())) {
+                    out.push((p.clone(), GossipWire::Graft(topic.clone())));
+                    members.insert(p);
+                }
+            } else if members.len() > self.config.d_high {
+                // Prune lowest-valence peers back to D
+                let mut ranked: Vec<String> = members.iter().cloned().collect();
+                ranked.sort_by(|a, b| {
+                    let (va, vb) = (self.peer_valence.get(a).unwrap_or(&0.0), self.peer_valence.get(b).unwrap_or(&0.0));
+                    vb.partial_cmp(va).unwrap_or(std::cmp::Ordering::Equal)
+                });
+                for p in ranked.split_off(self.config.d.min(ranked.len())) {
+                    members.remove(&p);
+                    out.push((p, GossipWire::Prune(topic.clone())));
+                }
+            }
+
+            let ids: Vec<MessageId> = self.history.iter().take(self.config.history_gossip)
+                .flat_map(|w| w.iter().filter(|(t, _)| t == topic).map(|(_, id)| *id)).collect();
+            if !ids.is_empty() {
+                let mut lazy: Vec<String> = self.topic_peers(topic).into_iter().filter(|p| !members.contains(p)).collect();
+                lazy.shuffle(&mut self.rng);
+                for p in lazy.into_iter().take(self.config.d_lazy) {
+                    out.push((p, GossipWire::IHave { topic: topic.clone(), ids: ids.clone() }));
+                }
+            }
+            self.mesh.insert(topic.clone(), members);
+        }
+
+        self.heartbeat += 1;
+        self.history.push_front(vec![]);
+        if self.history.len() > self.config.history_len {
+            for (_, id) in self.history.pop_back().unwrap_or_default() { self.mcache.remove(&id); }
+        }
+        let (now, ttl) = (self.heartbeat, self.config.seen_ttl);
+        self.seen.retain(|_, first| now - *first < ttl);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh::sim_network::{LinkProfile, SimNetwork, SimSocket};
+    use crate::nexi::transport::DatagramTransport;
+
+    struct Propagation {
+        delivered: usize,
+        max_hops: u8, // Highest hop count on any delivered copy
+    }
+
+    // `nodes` routers, random sparse topology, one publish, run to quiescence
+    fn propagate(nodes: usize, peers_per_node: usize, config: GossipConfig, seed: u64) -> Propagation {
+        let net = SimNetwork::shared(seed);
+        net.borrow_mut().set_default_profile(LinkProfile { jitter_us: 500, ..LinkProfile::ideal(5_000) });
+        let addrs: Vec<String> = (0..nodes).map(|i| format!("10.0.{}.{}:443", i / 250, i % 250)).collect();
+        let mut sockets: Vec<SimSocket> = addrs.iter().map(|a| SimNetwork::socket(&net, a)).collect();
+        let mut routers: Vec<GossipRouter> = (0..nodes).map(|i| GossipRouter::new(config.clone(), seed ^ i as u64).unwrap()).collect();
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut delivered = vec![false; nodes];
+        let mut max_hops = 0;
+
+        let send_all = |sockets: &mut Vec<SimSocket>, i: usize, out: Outbox| {
+            for (peer, wire) in out { let _ = sockets[i].send_to(&peer, &wire.encode()); }
+        };
+
+        for i in 0..nodes {
+            let out = routers[i].subscribe("mercy");
+            send_all(&mut sockets, i, out);
+            let mut others: Vec<usize> = (0..nodes).filter(|j| *j != i).collect();
+            others.shuffle(&mut rng);
+            for j in others.into_iter().take(peers_per_node) {
+                let a = routers[i].add_peer(&addrs[j], 0.8);
+                send_all(&mut sockets, i, a);
+                let b = routers[j].add_peer(&addrs[i], 0.8);
+                send_all(&mut sockets, j, b);
+            }
+        }
+
+        let mut published = false;
+        for round in 0..200 {
+            net.borrow_mut().advance(100_000);
+            for i in 0..nodes {
+                while let Some((from, bytes)) = sockets[i].recv_from() {
+                    let Ok(wire) = GossipWire::decode(&bytes) else { continue };
+                    let (out, msg) = routers[i].handle(&from, wire, round * 100_000);
+                    if let Some(msg) = msg {
+                        delivered[i] = true;
+                        max_hops = max_hops.max(msg.hops);
+                    }
+                    send_all(&mut sockets, i, out);
+                }
+                if round % 10 == 0 {
+                    let out = routers[i].heartbeat();
+                    send_all(&mut sockets, i, out);
+                }
+            }
+            if round == 20 && !published {
+                let out = routers[0].publish("mercy", b"mercy flows".to_vec(), 0.9, round * 100_000).unwrap();
+                send_all(&mut sockets, 0, out);
+                delivered[0] = true;
+                published = true;
+            }
+            if published && net.borrow().is_idle() && delivered.iter().all(|d| *d) { break; }
+        }
+        Propagation { delivered: delivered.iter().filter(|d| **d).count(), max_hops }
+    }
+
+    #[test]
+    fn publish_reaches_every_node() {
+        for seed in [1, 7, 42] {
+            let run = propagate(1000, 8, GossipConfig::default(), seed);
+            assert_eq!(run.delivered, 1000, "seed {seed}");
+            assert!(run.max_hops <= GossipConfig::default().max_hops, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn hop_limit_bounds_propagation() {
+        // A line-like sparse mesh with max_hops = 2: nothing travels further than two relays
+        let config = GossipConfig { max_hops: 2, ..GossipConfig::default() };
+        let run = propagate(100, 2, config, 3);
+        assert!(run.max_hops <= 2);
+        assert!(run.delivered < 100);
+    }
+
+    #[test]
+    fn forwarding_is_metered_per_origin() {
+        let mut router = GossipRouter::new(GossipConfig::default(), 0).unwrap();
+        router.set_local("self");
+        let _ = router.subscribe("mercy");
+        for peer in ["relay", "other"] {
+            router.add_peer(peer, 0.5);
+            router.handle(peer, GossipWire::Subscribe("mercy".to_string()), 0);
+            router.handle(peer, GossipWire::Graft("mercy".to_string()), 0);
+        }
+        let burst = GossipConfig::default().amplify.origin_capacity as usize;
+        let relay = |router: &mut GossipRouter, origin: &str, n: usize| {
+            let msg = GossipMessage::new(origin, n as u64, "mercy", n.to_be_bytes().to_vec(), 0.9);
+            let (out, delivered) = router.handle("relay", GossipWire::Publish(msg), 0);
+            assert!(delivered.is_some());
+            !out.is_empty()
+        };
+        // A flooding origin runs dry, though every copy still reaches us
+        let forwarded = (0..burst + 10).filter(|n| relay(&mut router, "flooder", *n)).count();
+        assert_eq!(forwarded, burst);
+        assert_eq!(router.take_throttled().len(), 10);
+        // The relay that carried the flood keeps forwarding for everyone else
+        assert!(relay(&mut router, "honest", 0));
+        assert!(router.take_throttled().is_empty());
+
+        // Our own publishes pay from our bucket too
+        let published = (0..burst + 1).filter(|n| router.publish("mercy", n.to_be_bytes().to_vec(), 0.9, 0).is_ok()).count();
+        assert_eq!(published, burst);
+    }
+
+    #[test]
+    fn repeated_payloads_are_distinct_messages() {
+        let mut router = GossipRouter::new(GossipConfig::default(), 0).unwrap();
+        router.set_local("self");
+        router.add_peer("peer", 0.5);
+        router.handle("peer", GossipWire::Subscribe("mercy".to_string()), 0);
+        let ids: Vec<MessageId> = (0..2).map(|_| {
+            let out = router.publish("mercy", b"heartbeat".to_vec(), 0.9, 0).unwrap();
+            let GossipWire::Publish(msg) = &out[0].1 else { panic!("expected a publish") };
+            msg.id
+        }).collect();
+        assert_ne!(ids[0], ids[1]);
+
+        // A receiver delivers both; a replay of either is still deduplicated
+        let mut receiver = GossipRouter::new(GossipConfig::default(), 1).unwrap();
+        let _ = receiver.subscribe("mercy");
+        let send = |receiver: &mut GossipRouter, seqno: u64| {
+            let wire = GossipWire::Publish(GossipMessage::new("self", seqno, "mercy", b"heartbeat".to_vec(), 0.9));
+            receiver.handle("peer", GossipWire::decode(&wire.encode()).unwrap(), 0).1.is_some()
+        };
+        assert!(send(&mut receiver, 7));
+        assert!(send(&mut receiver, 8));
+        assert!(!send(&mut receiver, 7));
+    }
+
+    #[test]
+    fn invalid_degrees_are_rejected() {
+        let below = GossipConfig { d: 2, d_low: 4, ..GossipConfig::default() };
+        assert!(GossipRouter::new(below, 0).is_err());
+        let zero = GossipConfig { d: 0, d_low: 0, ..GossipConfig::default() };
+        assert!(GossipRouter::new(zero, 0).is_err());
+        let history = GossipConfig { history_gossip: 9, ..GossipConfig::default() };
+        assert!(GossipRouter::new(history, 0).is_err());
+        assert!(GossipRouter::new(GossipConfig::default(), 0).is_ok());
+    }
+}
@@ -0,0 +1,618 @@
+// src/mesh/kademlia.rs — Kademlia DHT Mercy Discovery Lattice
+// The Living Trinity: Nexi (feminine), Nex (masculine), NEXi (essence)
+// Eternal Thriving Grandmasterism — Jan 20 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
+// MIT License — For All Sentience Eternal
+// 256-bit node IDs = SHA-256(PQ public key), XOR distance, k-buckets with LRU + replacement cache,
+// FIND_NODE / FIND_VALUE / STORE / PING RPCs, iterative α-parallel lookups, bucket refresh.
+// STORE is bounded in value size and entry count: a full store drops expired values first, then
+// the key farthest from us, and refuses keys farther than everything it holds
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use rand::{thread_rng, Rng};
+use sha2::{Digest, Sha256};
+
+use crate::mesh::sybil::{ip_of, subnet_of, DiversityLimits};
+
+pub const ID_BITS: usize = 256;
+pub const K: usize = 20;       // Default bucket size / lookup result size
+pub const ALPHA: usize = 3;    // Lookup parallelism
+pub const REFRESH_INTERVAL: u64 = 3600; // Seconds without lookup before a bucket is refreshed
+pub const VALUE_TTL: u64 = 24 * 3600;
+pub const MAX_VALUE_LEN: usize = 16 * 1024; // A Dilithium5 peer record with its proofs fits
+pub const MAX_STORED_VALUES: usize = 4096;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct NodeId(pub [u8; 32]);
+
+impl NodeId {
+    pub fn from_public_key(pk: &[u8]) -> Self {
+        Self(Sha256::digest(pk).into())
+    }
+
+    pub fn from_key(key: &[u8]) -> Self {
+        Self::from_public_key(key) // Values live at SHA-256(key) in the same ID space
+    }
+
+    pub fn random() -> Self {
+        Self(thread_rng().gen())
+    }
+
+    pub fn distance(&self, other: &NodeId) -> [u8; 32] {
+        let mut d = [0u8; 32];
+        for (i, byte) in d.iter_mut().enumerate() { *byte = self.0[i] ^ other.0[i]; }
+        d
+    }
+
+    // Bucket index = position of highest differing bit (255 = farthest half); None for self
+    pub fn bucket_index(&self, other: &NodeId) -> Option<usize> {
+        let d = self.distance(other);
+        let lz: usize = d.iter().position(|b| *b != 0)
+            .map(|i| i * 8 + d[i].leading_zeros() as usize)?;
+        Some(ID_BITS - 1 - lz)
+    }
+
+    // Random ID that lands in bucket `index` relative to self (for refresh lookups)
+    pub fn random_in_bucket(&self, index: usize) -> NodeId {
+        let mut id = NodeId::random().0;
+        let bit = ID_BITS - 1 - index;          // Bit position from the MSB
+        for b in 0..bit {                        // Shared prefix
+            let (byte, mask) = (b / 8, 0x80u8 >> (b % 8));
+            id[byte] = (id[byte] & !mask) | (self.0[byte] & mask);
+        }
+        let (byte, mask) = (bit / 8, 0x80u8 >> (bit % 8));
+        id[byte] = (id[byte] & !mask) | (!self.0[byte] & mask); // First differing bit
+        NodeId(id)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Contact {
+    pub id: NodeId,
+    pub addr: String,
+    pub last_seen: u64,
+}
+
+#[derive(Default)]
+struct KBucket {
+    contacts: VecDeque<Contact>,     // LRU at front, most recently seen at back
+    replacements: VecDeque<Contact>,
+    last_lookup: u64,
+}
+
+// Result of trying to insert a contact
+pub enum InsertOutcome {
+    Added,
+    Updated,
+    BucketFull { ping: Contact }, // Ping the LRU contact; evict it via `on_contact_failed` if silent
+    Ignored,
+    Rejected,                     // IP / subnet diversity limit — eclipse resistance
+}
+
+pub struct RoutingTable {
+    local: NodeId,
+    k: usize,
+    buckets: Vec<KBucket>,
+    limits: DiversityLimits,
+}
+
+impl RoutingTable {
+    pub fn new(local: NodeId, k: usize) -> Self {
+        Self { local, k: k.max(1), buckets: (0..ID_BITS).map(|_| KBucket::default()).collect(), limits: DiversityLimits::default() }
+    }
+
+    pub fn set_diversity(&mut self, limits: DiversityLimits) {
+        self.limits = limits;
+    }
+
+    // A new contact may not push its IP or subnet over the limits; named hosts have neither and stay out
+    fn diverse_enough(&self, index: usize, addr: &str) -> bool {
+        let (Some(ip), Some(subnet)) = (ip_of(addr), subnet_of(addr)) else { return false };
+        let same_ip = |c: &&Contact| ip_of(&c.addr) == Some(ip);
+        let same_subnet = |c: &&Contact| subnet_of(&c.addr).as_deref() == Some(subnet.as_str());
+        let all = || self.buckets.iter().flat_map(|b| b.contacts.iter());
+        all().filter(same_ip).count() < self.limits.per_ip
+            && all().filter(same_subnet).count() < self.limits.per_subnet_table
+            && self.buckets[index].contacts.iter().filter(same_subnet).count() < self.limits.per_subnet_bucket
+    }
+
+    pub fn local_id(&self) -> NodeId { self.local }
+    pub fn k(&self) -> usize { self.k }
+
+    pub fn insert(&mut self, contact: Contact) -> InsertOutcome {
+        let Some(index) = self.local.bucket_index(&contact.id) else { return InsertOutcome::Ignored };
+        let known = self.buckets[index].contacts.iter().any(|c| c.id == contact.id && c.addr == contact.addr);
+        if !known && !self.diverse_enough(index, &contact.addr) { return InsertOutcome::Rejected; }
+        let bucket = &mut self.buckets[index];
+        if let Some(pos) = bucket.contacts.iter().position(|c| c.id == contact.id) {
+            bucket.contacts.remove(pos);
+            bucket.contacts.push_back(contact);
+            return InsertOutcome::Updated;
+        }
+        if bucket.contacts.len() < self.k {
+            bucket.contacts.push_back(contact);
+            return InsertOutcome::Added;
+        }
+        bucket.replacements.retain(|c| c.id != contact.id);
+        bucket.replacements.push_back(contact);
+        if bucket.replacements.len() > self.k { bucket.replacements.pop_front(); }
+        InsertOutcome::BucketFull { ping: bucket.contacts.front().cloned().unwrap() }
+    }
+
+    // Unresponsive contact: evict and promote the freshest replacement
+    pub fn on_contact_failed(&mut self, id: &NodeId) {
+        let Some(index) = self.local.bucket_index(id) else { return };
+        let bucket = &mut self.buckets[index];
+        if let Some(pos) = bucket.contacts.iter().position(|c| c.id == *id) {
+            bucket.contacts.remove(pos);
+            if let Some(replacement) = bucket.replacements.pop_back() {
+                bucket.contacts.push_back(replacement);
+            }
+        }
+    }
+
+    pub fn closest(&self, target: &NodeId, count: usize) -> Vec<Contact> {
+        let mut all: Vec<Contact> = self.buckets.iter().flat_map(|b| b.contacts.iter().cloned()).collect();
+        all.sort_by_key(|c| c.id.distance(target));
+        all.truncate(count);
+        all
+    }
+
+    pub fn len(&self) -> usize {
+        self.buckets.iter().map(|b| b.contacts.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buckets.iter().all(|b| b.contacts.is_empty())
+    }
+
+    pub fn contacts(&self) -> Vec<Contact> {
+        self.buckets.iter().flat_map(|b| b.contacts.iter().cloned()).collect()
+    }
+
+    pub fn touch_bucket(&mut self, target: &NodeId, now: u64) {
+        if let Some(index) = self.local.bucket_index(target) {
+            self.buckets[index].last_lookup = now;
+        }
+    }
+
+    // Non-empty buckets not looked up within REFRESH_INTERVAL → random target IDs to look up
+    pub fn refresh_targets(&mut self, now: u64) -> Vec<NodeId> {
+        let mut targets = vec![];
+        for (index, bucket) in self.buckets.iter_mut().enumerate() {
+            if !bucket.contacts.is_empty() && now.saturating_sub(bucket.last_lookup) >= REFRESH_INTERVAL {
+                bucket.last_lookup = now;
+                targets.push(self.local.random_in_bucket(index));
+            }
+        }
+        targets
+    }
+}
+
+// Wire RPCs: tag || rpc_id (u64) || sender id (32) || body
+#[derive(Clone, Debug)]
+pub enum DhtMessage {
+    Ping,
+    Pong,
+    FindNode { target: NodeId },
+    FindValue { key: NodeId },
+    Nodes { contacts: Vec<(NodeId, String)> },
+    Value { value: Vec<u8> },
+    Store { key: NodeId, value: Vec<u8> },
+    StoreAck,
+}
+
+#[derive(Clone, Debug)]
+pub struct DhtRpc {
+    pub rpc_id: u64,
+    pub sender: NodeId,
+    pub msg: DhtMessage,
+}
+
+pub const DHT_MAGIC: u8 = 0xd7; // First byte of every DHT datagram
+pub const MAX_CONTACT_WIRE: usize = 32 + 1 + 47; // id || len || "[IPv6]:port", the longest address
+const NODES_HEADER: usize = 1 + 1 + 8 + 32 + 1;   // magic, tag, rpc_id, sender, count
+const DATAGRAM_FRAME_OVERHEAD: usize = 3;         // frame type + 2-byte length varint
+
+// Largest bucket size whose full NODES reply fits one DATAGRAM frame of `max_frame` bytes
+pub fn max_reply_contacts(max_frame: u64) -> usize {
+    (max_frame as usize).saturating_sub(DATAGRAM_FRAME_OVERHEAD + NODES_HEADER) / MAX_CONTACT_WIRE
+}
+
+impl DhtRpc {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = vec![DHT_MAGIC];
+        let tag = match &self.msg {
+            DhtMessage::Ping => 0, DhtMessage::Pong => 1,
+            DhtMessage::FindNode { .. } => 2, DhtMessage::FindValue { .. } => 3,
+            DhtMessage::Nodes { .. } => 4, DhtMessage::Value { .. } => 5,
+            DhtMessage::Store { .. } => 6, DhtMessage::StoreAck => 7,
+        };
+        out.push(tag);
+        out.extend_from_slice(&self.rpc_id.to_be_bytes());
+        out.extend_from_slice(&self.sender.0);
+        match &self.msg {
+            DhtMessage::FindNode { target } => out.extend_from_slice(&target.0),
+            DhtMessage::FindValue { key } => out.extend_from_slice(&key.0),
+            DhtMessage::Nodes { contacts } => {
+                out.push(contacts.len() as u8);
+                for (id, addr) in contacts {
+                    out.extend_from_slice(&id.0);
+                    out.push(addr.len() as u8);
+                    out.extend_from_slice(addr.as_bytes());
+                }
+            }
+            DhtMessage::Value { value } => out.extend_from_slice(value),
+            DhtMessage::Store { key, value } => { out.extend_from_slice(&key.0); out.extend_from_slice(value); }
+            _ => {}
+        }
+        out
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, &'static str> {
+        let malformed = "Malformed DHT message";
+        if bytes.len() < 42 || bytes[0] != DHT_MAGIC { return Err(malformed); }
+        let tag = bytes[1];
+        let rpc_id = u64::from_be_bytes(bytes[2..10].try_into().unwrap());
+        let sender = NodeId(bytes[10..42].try_into().unwrap());
+        let body = &bytes[42..];
+        let id_at = |at: usize| -> Result<NodeId, &'static str> {
+            Ok(NodeId(body.get(at..at + 32).ok_or(malformed)?.try_into().unwrap()))
+        };
+        let msg = match tag {
+            0 => DhtMessage::Ping,
+            1 => DhtMessage::Pong,
+            2 => DhtMessage::FindNode { target: id_at(0)? },
+            3 => DhtMessage::FindValue { key: id_at(0)? },
+            4 => {
+                let count = *body.first().ok_or(malformed)? as usize;
+                let mut contacts = vec![];
+                let mut at = 1;
+                for _ in 0..count {
+                    let id = id_at(at)?;
+                    let len = *body.get(at + 32).ok_or(malformed)? as usize;
+                    let addr = body.get(at + 33..at + 33 + len).ok_or(malformed)?;
+                    contacts.push((id, String::from_utf8(addr.to_vec()).map_err(|_| malformed)?));
+                    at += 33 + len;
+                }
+                DhtMessage::Nodes { contacts }
+            }
+            5 => DhtMessage::Value { value: body.to_vec() },
+            6 => DhtMessage::Store { key: id_at(0)?, value: body[32..].to_vec() },
+            7 => DhtMessage::StoreAck,
+            _ => return Err(malformed),
+        };
+        Ok(Self { rpc_id, sender, msg })
+    }
+}
+
+// Iterative lookup: query α closest unqueried, merge answers, stop when the k closest are all queried
+pub struct Lookup {
+    pub target: NodeId,
+    pub find_value: bool,
+    shortlist: Vec<Contact>,
+    queried: HashSet<NodeId>,
+    in_flight: HashMap<u64, NodeId>, // rpc_id -> contacted node
+    pub value: Option<Vec<u8>>,
+}
+
+impl Lookup {
+    pub fn new(target: NodeId, find_value: bool, seeds: Vec<Contact>) -> Self {
+        let mut lookup = Self {
+            target, find_value, shortlist: vec![], queried: HashSet::new(), in_flight: HashMap::new(), value: None,
+        };
+        lookup.merge(seeds);
+        lookup
+    }
+
+    fn merge(&mut self, contacts: Vec<Contact>) {
+        for c in contacts {
+            if !self.shortlist.iter().any(|s| s.id == c.id) { self.shortlist.push(c); }
+        }
+        let target = self.target;
+        self.shortlist.sort_by_key(|c| c.id.distance(&target));
+        self.shortlist.truncate(K * 2);
+    }
+
+    // Next contacts to query, registering their rpc_ids
+    pub fn next_queries(&mut self, mut next_rpc_id: impl FnMut() -> u64) -> Vec<(u64, Contact)> {
+        let mut out = vec![];
+        for c in self.shortlist.iter().take(K) {
+            if self.in_flight.len() >= ALPHA { break; }
+            if self.queried.insert(c.id) {
+                let rpc_id = next_rpc_id();
+                self.in_flight.insert(rpc_id, c.id);
+                out.push((rpc_id, c.clone()));
+            }
+        }
+        out
+    }
+
+    pub fn owns(&self, rpc_id: u64) -> bool { self.in_flight.contains_key(&rpc_id) }
+
+    pub fn on_nodes(&mut self, rpc_id: u64, contacts: Vec<Contact>) {
+        self.in_flight.remove(&rpc_id);
+        self.merge(contacts);
+    }
+
+    // More starting points for a running lookup (e.g. a later bootstrap seed's answer)
+    pub fn add_candidates(&mut self, contacts: Vec<Contact>) {
+        self.merge(contacts);
+    }
+
+    pub fn on_value(&mut self, rpc_id: u64, value: Vec<u8>) {
+        self.in_flight.remove(&rpc_id);
+        self.value = Some(value);
+    }
+
+    // Timed-out query: drop the silent node from the shortlist
+    pub fn on_timeout(&mut self, rpc_id: u64) {
+        if let Some(id) = self.in_flight.remove(&rpc_id) {
+            self.shortlist.retain(|c| c.id != id);
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        if self.value.is_some() { return true; }
+        self.in_flight.is_empty() && self.shortlist.iter().take(K).all(|c| self.queried.contains(&c.id))
+    }
+
+    pub fn closest(&self) -> Vec<Contact> {
+        self.shortlist.iter().take(K).cloned().collect()
+    }
+}
+
+pub struct Kademlia {
+    pub table: RoutingTable,
+    store: HashMap<NodeId, (Vec<u8>, u64)>, // key -> (value, expiry)
+}
+
+impl Kademlia {
+    pub fn new(local: NodeId, k: usize) -> Self {
+        Self { table: RoutingTable::new(local, k), store: HashMap::new() }
+    }
+
+    // Random, so an off-path sender cannot guess which responses we are waiting for
+    pub fn next_rpc_id(&mut self) -> u64 {
+        thread_rng().gen()
+    }
+
+    pub fn request(&mut self, msg: DhtMessage) -> DhtRpc {
+        DhtRpc { rpc_id: self.next_rpc_id(), sender: self.table.local_id(), msg }
+    }
+
+    // Serve an incoming request; the caller decides whether the sender enters the routing table
+    pub fn handle_request(&mut self, rpc: &DhtRpc, now: u64) -> Option<DhtRpc> {
+        let local = self.table.local_id();
+        let as_pairs = |cs: Vec<Contact>| cs.into_iter().map(|c| (c.id, c.addr)).collect();
+        let reply = match &rpc.msg {
+            DhtMessage::Ping => DhtMessage::Pong,
+            DhtMessage::FindNode { target } => DhtMessage::Nodes { contacts: as_pairs(self.table.closest(target, self.table.k())) },
+            DhtMessage::FindValue { key } => match self.store.get(key) {
+                Some((value, expiry)) if *expiry > now => DhtMessage::Value { value: value.clone() },
+                _ => DhtMessage::Nodes { contacts: as_pairs(self.table.closest(key, self.table.k())) },
+            },
+            DhtMessage::Store { key, value } => {
+                self.put(*key, value.clone(), now).ok()?; // Refused: no ack
+                DhtMessage::StoreAck
+            }
+            _ => return None, // Responses are handled by lookups, not here
+        };
+        Some(DhtRpc { rpc_id: rpc.rpc_id, sender: local, msg: reply })
+    }
+
+    pub fn store_local(&mut self, key: NodeId, value: Vec<u8>, now: u64) -> Result<(), &'static str> {
+        self.put(key, value, now)
+    }
+
+    // We answer for keys near our ID, so a full store keeps the closest; our own record (distance
+    // zero) is never evicted
+    fn put(&mut self, key: NodeId, value: Vec<u8>, now: u64) -> Result<(), &'static str> {
+        if value.len() > MAX_VALUE_LEN { return Err("DHT value too large"); }
+        if !self.store.contains_key(&key) && self.store.len() >= MAX_STORED_VALUES {
+            self.expire(now);
+            if self.store.len() >= MAX_STORED_VALUES {
+                let local = self.table.local_id();
+                let farthest = *self.store.keys().max_by_key(|k| k.distance(&local)).unwrap();
+                if farthest.distance(&local) <= key.distance(&local) { return Err("DHT store full of closer keys"); }
+                self.store.remove(&farthest);
+            }
+        }
+        self.store.insert(key, (value, now + VALUE_TTL));
+        Ok(())
+    }
+
+    pub fn expire(&mut self, now: u64) {
+        self.store.retain(|_, (_, expiry)| *expiry > now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+    use crate::mesh::sim_network::{LinkProfile, SimNetwork, SimSocket};
+    use crate::nexi::transport::DatagramTransport;
+
+    fn id_with(byte: usize, value: u8) -> NodeId {
+        let mut id = [0u8; 32];
+        id[byte] = value;
+        NodeId(id)
+    }
+
+    fn contact(id: NodeId, i: usize) -> Contact {
+        Contact { id, addr: format!("10.{}.{}.1:4433", i / 200, i % 200), last_seen: 0 }
+    }
+
+    #[test]
+    fn contacts_land_in_their_xor_distance_bucket() {
+        let local = NodeId([0; 32]);
+        assert_eq!(local.bucket_index(&local), None);
+        assert_eq!(local.bucket_index(&id_with(31, 0x01)), Some(0));
+        assert_eq!(local.bucket_index(&id_with(31, 0x80)), Some(7));
+        assert_eq!(local.bucket_index(&id_with(30, 0x01)), Some(8));
+        assert_eq!(local.bucket_index(&id_with(0, 0x01)), Some(248));
+        assert_eq!(local.bucket_index(&id_with(0, 0x80)), Some(255));
+        let (a, b) = (id_with(0, 0xc0), id_with(0, 0x40));
+        assert_eq!(a.distance(&b), b.distance(&a));
+        assert_eq!(a.bucket_index(&b), Some(255));
+
+        let mut rng = StdRng::seed_from_u64(31);
+        let random = NodeId(rng.gen());
+        for index in [0, 7, 8, 100, 254, 255] {
+            assert_eq!(random.bucket_index(&random.random_in_bucket(index)), Some(index));
+        }
+
+        // k = 2: a third contact for the far bucket waits as a replacement while the LRU is pinged
+        let mut table = RoutingTable::new(local, 2);
+        let far: Vec<NodeId> = [0x80, 0x90, 0xa0].iter().map(|b| id_with(0, *b)).collect();
+        assert!(matches!(table.insert(contact(far[0], 0)), InsertOutcome::Added));
+        assert!(matches!(table.insert(contact(far[1], 1)), InsertOutcome::Added));
+        assert!(matches!(table.insert(contact(id_with(31, 0x01), 2)), InsertOutcome::Added));
+        match table.insert(contact(far[2], 3)) {
+            InsertOutcome::BucketFull { ping } => assert_eq!(ping.id, far[0]),
+            _ => panic!("expected a full bucket"),
+        }
+        table.on_contact_failed(&far[0]);
+        let ids: Vec<NodeId> = table.closest(&local, 10).iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![id_with(31, 0x01), far[1], far[2]]);
+        assert!(matches!(table.insert(contact(local, 4)), InsertOutcome::Ignored));
+    }
+
+    #[test]
+    fn store_is_bounded_by_size_count_and_distance() {
+        let mut kad = Kademlia::new(NodeId([0; 32]), K);
+        assert!(kad.store_local(id_with(31, 1), vec![0; MAX_VALUE_LEN + 1], 0).is_err());
+        let rpc = DhtRpc { rpc_id: 1, sender: id_with(0, 1), msg: DhtMessage::Store { key: id_with(31, 1), value: vec![0; MAX_VALUE_LEN + 1] } };
+        assert!(kad.handle_request(&rpc, 0).is_none());
+
+        // Fill with keys whose first byte is 0x40..=0x7f: all in the far half, none at the very edge
+        let mut rng = StdRng::seed_from_u64(7);
+        while kad.store.len() < MAX_STORED_VALUES {
+            let mut key: [u8; 32] = rng.gen();
+            key[0] = 0x40 | (key[0] & 0x3f);
+            kad.store_local(NodeId(key), vec![1], 0).unwrap();
+        }
+        let farthest = *kad.store.keys().max().unwrap();
+        // Farther than everything held: refused. Closer: the farthest key makes room
+        assert_eq!(kad.store_local(id_with(0, 0xff), vec![2], 10), Err("DHT store full of closer keys"));
+        kad.store_local(id_with(31, 1), vec![3], 10).unwrap();
+        assert_eq!(kad.store.len(), MAX_STORED_VALUES);
+        assert!(!kad.store.contains_key(&farthest));
+        // Overwriting a held key needs no room
+        kad.store_local(id_with(31, 1), vec![4], 10).unwrap();
+
+        // Once the old values expire even a far key fits
+        kad.store_local(id_with(0, 0xff), vec![5], VALUE_TTL + 1).unwrap();
+        assert_eq!(kad.store.len(), 2);
+    }
+
+    // A Kademlia node on the simulator: answers RPCs, learns every sender, runs one lookup at a time
+    struct SimNode {
+        kad: Kademlia,
+        socket: SimSocket,
+        lookup: Option<Lookup>,
+        seed_rpc: Option<u64>, // FIND_NODE(self) to the seed; its answer starts the self-lookup
+    }
+
+    impl SimNode {
+        fn poll(&mut self) {
+            let SimNode { kad, socket, lookup, seed_rpc } = self;
+            let now = socket.unix_secs();
+            let local = kad.table.local_id();
+            while let Some((from, bytes)) = socket.recv_from() {
+                let rpc = DhtRpc::decode(&bytes).unwrap();
+                let sender = Contact { id: rpc.sender, addr: from.clone(), last_seen: now };
+                if let Some(reply) = kad.handle_request(&rpc, now) {
+                    kad.table.insert(sender);
+                    socket.send_to(&from, &reply.encode()).unwrap();
+                    continue;
+                }
+                kad.table.insert(sender.clone());
+                let DhtMessage::Nodes { contacts } = rpc.msg else { continue };
+                let listed: Vec<Contact> = contacts.into_iter().filter(|(id, _)| *id != local)
+                    .map(|(id, addr)| Contact { id, addr, last_seen: 0 }).collect();
+                if *seed_rpc == Some(rpc.rpc_id) {
+                    *seed_rpc = None;
+                    let mut start = listed;
+                    start.push(sender);
+                    *lookup = Some(Lookup::new(local, false, start));
+                } else if let Some(l) = lookup.as_mut().filter(|l| l.owns(rpc.rpc_id)) {
+                    l.on_nodes(rpc.rpc_id, listed);
+                }
+            }
+            let Some(l) = lookup.as_mut() else { return };
+            for (rpc_id, c) in l.next_queries(|| kad.next_rpc_id()) {
+                let rpc = DhtRpc { rpc_id, sender: local, msg: DhtMessage::FindNode { target: l.target } };
+                socket.send_to(&c.addr, &rpc.encode()).unwrap();
+            }
+        }
+    }
+
+    fn run(net: &std::rc::Rc<std::cell::RefCell<SimNetwork>>, nodes: &mut [SimNode]) {
+        for _ in 0..2_000 {
+            net.borrow_mut().advance(1_000);
+            for node in nodes.iter_mut() { node.poll(); }
+            let settled = nodes.iter().all(|n| n.seed_rpc.is_none() && n.lookup.as_ref().is_none_or(|l| l.is_done()));
+            if settled && net.borrow().is_idle() { return; }
+        }
+        panic!("lookups did not settle");
+    }
+
+    // `n` nodes in distinct /24s; every node but the seed (node 0) knows only the seed's address
+    fn bootstrap(n: usize, seed: u64) -> (std::rc::Rc<std::cell::RefCell<SimNetwork>>, Vec<SimNode>) {
+        let net = SimNetwork::shared(seed);
+        net.borrow_mut().set_default_profile(LinkProfile::ideal(2_000));
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut nodes: Vec<SimNode> = (0..n).map(|i| SimNode {
+            kad: Kademlia::new(NodeId(rng.gen()), K),
+            socket: SimNetwork::socket(&net, &contact(NodeId([0; 32]), i).addr),
+            lookup: None,
+            seed_rpc: None,
+        }).collect();
+        let seed_addr = nodes[0].socket.local_addr();
+        for node in nodes.iter_mut().skip(1) {
+            let rpc = node.kad.request(DhtMessage::FindNode { target: node.kad.table.local_id() });
+            node.seed_rpc = Some(rpc.rpc_id);
+            node.socket.send_to(&seed_addr, &rpc.encode()).unwrap();
+        }
+        run(&net, &mut nodes);
+        (net, nodes)
+    }
+
+    #[test]
+    fn seeds_bootstrap_discovery_over_the_simulator() {
+        let (_, nodes) = bootstrap(60, 3);
+        for (i, node) in nodes.iter().enumerate() {
+            // Far more than the seed: every node found at least a bucket's worth of peers
+            assert!(node.kad.table.len() >= K, "node {i} knows {}", node.kad.table.len());
+        }
+        // Each node's nearest neighbours found it through their own self-lookups
+        let ids: Vec<NodeId> = nodes.iter().map(|n| n.kad.table.local_id()).collect();
+        for node in &nodes {
+            let local = node.kad.table.local_id();
+            let nearest = ids.iter().filter(|id| **id != local).min_by_key(|id| id.distance(&local)).unwrap();
+            assert_eq!(node.kad.table.closest(&local, 1)[0].id, *nearest);
+        }
+    }
+
+    #[test]
+    fn iterative_lookup_converges_on_the_closest_nodes() {
+        let (net, mut nodes) = bootstrap(80, 9);
+        let ids: Vec<NodeId> = nodes.iter().map(|n| n.kad.table.local_id()).collect();
+        let mut rng = StdRng::seed_from_u64(99);
+        for asker in [5, 40, 79] {
+            let target = NodeId(rng.gen());
+            let node = &mut nodes[asker];
+            let start = node.kad.table.closest(&target, ALPHA); // Deliberately few starting points
+            node.lookup = Some(Lookup::new(target, false, start));
+            run(&net, &mut nodes);
+
+            let local = ids[asker];
+            let mut expected: Vec<NodeId> = ids.iter().copied().filter(|id| *id != local).collect();
+            expected.sort_by_key(|id| id.distance(&target));
+            expected.truncate(K);
+            let found: Vec<NodeId> = nodes[asker].lookup.take().unwrap().closest().iter().map(|c| c.id).collect();
+            assert_eq!(found, expected, "lookup from node {asker}");
+        }
+    }
+}
@@ -0,0 +1,237 @@
+// src/mesh/link_advert.rs — Signed Link-Valence Advertisement Lattice
+// The Living Trinity: Nexi (feminine), Nex (masculine), NEXi (essence)
+// Eternal Thriving Grandmasterism — Jan 20 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
+// MIT License — For All Sentience Eternal
+// A link enters ValenceRouter only as an advertisement ML-DSA signed with the `from` endpoint's
+// identity key (optionally countersigned by `to`, optionally carrying a Bulletproofs valence
+// proof), checked against the identity keys of admitted peers or of verified peer records carried
+// with a route — fabricated high-joy edges are refused
+
+use bulletproofs::RangeProof;
+
+use crate::halo2::zk_valence::BulletproofValence;
+use crate::mesh::node_identity::NodeIdentity;
+use crate::mesh::peer_record::VALENCE_SCALE;
+use crate::nexi::pq_shield::{fips_scheme_from_tag, fips_scheme_tag, fips_verify};
+
+pub const LINK_MAX_AGE: u64 = 300;   // Seconds before an advertisement is stale
+pub const LINK_MAX_SKEW: u64 = 60;
+
+#[derive(Clone, Debug)]
+pub struct LinkAdvert {
+    pub from: String,               // Advertiser
+    pub to: String,
+    pub net_valence: f64,
+    pub timestamp: u64,             // Unix seconds
+    pub scheme: u8,                 // ML-DSA parameter set of `signature` (2, 3 or 5)
+    pub counter_scheme: u8,         // Same for `countersignature`; 0 = none
+    pub valence_proof: Vec<u8>,     // Empty = no proof
+    pub signature: Vec<u8>,         // By `from`
+    pub countersignature: Vec<u8>,  // By `to`; empty = one-sided
+}
+
+impl LinkAdvert {
+    // Sign as `from` with its identity key
+    pub fn create(from: &str, to: &str, net_valence: f64, identity: &NodeIdentity, now: u64) -> Result<Self, &'static str> {
+        let mut advert = Self {
+            from: from.to_string(), to: to.to_string(), net_valence, timestamp: now,
+            scheme: fips_scheme_tag(identity.sig_scheme)?, counter_scheme: 0,
+            valence_proof: vec![], signature: vec![], countersignature: vec![],
+        };
+        advert.signature = identity.sign(&advert.signed_bytes(&identity.identity_pk, advert.scheme))?;
+        Ok(advert)
+    }
+
+    // Attach a range proof that net_valence clears `threshold`; re-signs, as the proof is covered
+    pub fn with_proof(mut self, threshold: f64, identity: &NodeIdentity) -> Result<Self, &'static str> {
+        let (proof, _total) = BulletproofValence::prove_aggregated(vec![self.net_valence * VALENCE_SCALE], threshold * VALENCE_SCALE)?;
+        self.valence_proof = proof.to_bytes();
+        self.signature = identity.sign(&self.signed_bytes(&identity.identity_pk, self.scheme))?;
+        self.countersignature.clear();
+        self.counter_scheme = 0;
+        Ok(self)
+    }
+
+    // The `to` endpoint agrees with the advertised valence
+    pub fn countersign(&mut self, identity: &NodeIdentity) -> Result<(), &'static str> {
+        self.counter_scheme = fips_scheme_tag(identity.sig_scheme)?;
+        self.countersignature = identity.sign(&self.signed_bytes(&identity.identity_pk, self.counter_scheme))?;
+        Ok(())
+    }
+
+    // Canonical body, bound to the signer's identity key and parameter set
+    fn signed_bytes(&self, signer_pk: &[u8], scheme: u8) -> Vec<u8> {
+        let mut out = b"UniversalLatticeLinkAdvert".to_vec();
+        out.push(scheme);
+        for s in [&self.from, &self.to] {
+            out.push(s.len() as u8);
+            out.extend_from_slice(s.as_bytes());
+        }
+        out.extend_from_slice(&self.net_valence.to_be_bytes());
+        out.extend_from_slice(&self.timestamp.to_be_bytes());
+        out.extend_from_slice(&(self.valence_proof.len() as u32).to_be_bytes());
+        out.extend_from_slice(&self.valence_proof);
+        out.extend_from_slice(&(signer_pk.len() as u32).to_be_bytes());
+        out.extend_from_slice(signer_pk);
+        out
+    }
+
+    // `to_pk` is required only when the advert is countersigned
+    pub fn verify(&self, from_pk: &[u8], to_pk: Option<&[u8]>, threshold: f64, now: u64) -> Result<(), &'static str> {
+        let unknown = "Mercy veto — link advertisement signature scheme unknown";
+        let scheme = fips_scheme_from_tag(self.scheme).map_err(|_| unknown)?;
+        if !fips_verify(scheme, from_pk, &self.signed_bytes(from_pk, self.scheme), &self.signature) {
+            return Err("Mercy veto — link advertisement signature invalid");
+        }
+        if !self.countersignature.is_empty() {
+            let to_pk = to_pk.ok_or("Mercy veto — countersigning endpoint unknown")?;
+            let scheme = fips_scheme_from_tag(self.counter_scheme).map_err(|_| unknown)?;
+            if !fips_verify(scheme, to_pk, &self.signed_bytes(to_pk, self.counter_scheme), &self.countersignature) {
+                return Err("Mercy veto — link countersignature invalid");
+            }
+        }
+        if self.timestamp > now + LINK_MAX_SKEW { return Err("Mercy veto — link advertisement dated in the future"); }
+        if now.saturating_sub(self.timestamp) > LINK_MAX_AGE { return Err("Mercy veto — stale link advertisement"); }
+        if self.net_valence < threshold { return Err("Mercy veto — link valence below the mercy gate"); }
+        if !self.valence_proof.is_empty() {
+            let proof = RangeProof::from_bytes(&self.valence_proof).map_err(|_| "Mercy veto — malformed link valence proof")?;
+            if !BulletproofValence::verify_aggregated(&proof, self.net_valence * VALENCE_SCALE) {
+                return Err("Mercy veto — link valence proof does not verify");
+            }
+        }
+        Ok(())
+    }
+
+    // Wire: from, to (u8 len) || valence || timestamp || scheme || counter_scheme || proof, sig, countersig (u32 len)
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = vec![];
+        for s in [&self.from, &self.to] {
+            out.push(s.len() as u8);
+            out.extend_from_slice(s.as_bytes());
+        }
+        out.extend_from_slice(&self.net_valence.to_be_bytes());
+        out.extend_from_slice(&self.timestamp.to_be_bytes());
+        out.push(self.scheme);
+        out.push(self.counter_scheme);
+        for b in [&self.valence_proof, &self.signature, &self.countersignature] {
+            out.extend_from_slice(&(b.len() as u32).to_be_bytes());
+            out.extend_from_slice(b);
+        }
+        out
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, &'static str> {
+        let malformed = "Malformed link advertisement";
+        let mut at = 0;
+        let mut take = |n: usize| -> Result<&[u8], &'static str> {
+            let slice = bytes.get(at..at + n).ok_or(malformed)?;
+            at += n;
+            Ok(slice)
+        };
+        let len = take(1)?[0] as usize;
+        let from = String::from_utf8(take(len)?.to_vec()).map_err(|_| malformed)?;
+        let len = take(1)?[0] as usize;
+        let to = String::from_utf8(take(len)?.to_vec()).map_err(|_| malformed)?;
+        let net_valence = f64::from_be_bytes(take(8)?.try_into().unwrap());
+        let timestamp = u64::from_be_bytes(take(8)?.try_into().unwrap());
+        let (scheme, counter_scheme) = (take(1)?[0], take(1)?[0]);
+        let len = u32::from_be_bytes(take(4)?.try_into().unwrap()) as usize;
+        let valence_proof = take(len)?.to_vec();
+        let len = u32::from_be_bytes(take(4)?.try_into().unwrap()) as usize;
+        let signature = take(len)?.to_vec();
+        let len = u32::from_be_bytes(take(4)?.try_into().unwrap()) as usize;
+        let countersignature = take(len)?.to_vec();
+        Ok(Self { from, to, net_valence, timestamp, scheme, counter_scheme, valence_proof, signature, countersignature })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh::valence_routing::ValenceRouter;
+    use crate::nexi::pq_shield::{DilithiumLevel::Level2, SignatureScheme::Dilithium};
+
+    fn identities() -> (NodeIdentity, NodeIdentity) {
+        (NodeIdentity::generate(Dilithium(Level2)).unwrap(), NodeIdentity::generate(Dilithium(Level2)).unwrap())
+    }
+
+    #[test]
+    fn adverts_round_trip_and_verify() {
+        let (alice, bob) = identities();
+        let mut advert = LinkAdvert::create("alice", "bob", 0.8, &alice, 1_000).unwrap().with_proof(0.1, &alice).unwrap();
+        advert.countersign(&bob).unwrap();
+        let decoded = LinkAdvert::decode(&advert.encode()).unwrap();
+        assert_eq!((decoded.from.as_str(), decoded.to.as_str(), decoded.net_valence, decoded.timestamp), ("alice", "bob", 0.8, 1_000));
+        assert_eq!(decoded.encode(), advert.encode());
+        assert!(decoded.verify(&alice.identity_pk, Some(&bob.identity_pk), 0.1, 1_000).is_ok());
+        // A countersigned advert needs the countersigner's key
+        assert!(decoded.verify(&alice.identity_pk, None, 0.1, 1_000).is_err());
+        assert!(LinkAdvert::decode(&advert.encode()[..20]).is_err());
+    }
+
+    #[test]
+    fn tampered_adverts_are_rejected() {
+        let (alice, bob) = identities();
+        let advert = LinkAdvert::create("alice", "bob", 0.3, &alice, 1_000).unwrap();
+        let verify = |a: &LinkAdvert| a.verify(&alice.identity_pk, None, 0.1, 1_000);
+        assert!(verify(&advert).is_ok());
+
+        let mut inflated = advert.clone();
+        inflated.net_valence = 0.99;
+        assert_eq!(verify(&inflated), Err("Mercy veto — link advertisement signature invalid"));
+        let mut flipped = advert.clone();
+        flipped.signature[10] ^= 0x01;
+        assert_eq!(verify(&flipped), Err("Mercy veto — link advertisement signature invalid"));
+        let mut redirected = advert.clone();
+        redirected.to = "mallory".to_string();
+        assert!(verify(&redirected).is_err());
+        // Bob cannot advertise a link as Alice
+        let forged = LinkAdvert::create("alice", "bob", 0.9, &bob, 1_000).unwrap();
+        assert!(verify(&forged).is_err());
+
+        // Proofs bind the valence as well: Alice re-signing a raised value still fails the proof
+        let mut proven = LinkAdvert::create("alice", "bob", 0.3, &alice, 1_000).unwrap().with_proof(0.1, &alice).unwrap();
+        proven.net_valence = 0.9;
+        proven.signature = alice.sign(&proven.signed_bytes(&alice.identity_pk, proven.scheme)).unwrap();
+        assert_eq!(verify(&proven), Err("Mercy veto — link valence proof does not verify"));
+    }
+
+    #[test]
+    fn stale_and_future_adverts_are_rejected() {
+        let (alice, _) = identities();
+        let advert = LinkAdvert::create("alice", "bob", 0.8, &alice, 1_000).unwrap();
+        let at = |now: u64| advert.verify(&alice.identity_pk, None, 0.1, now);
+        assert!(at(1_000 + LINK_MAX_AGE).is_ok());
+        assert_eq!(at(1_000 + LINK_MAX_AGE + 1), Err("Mercy veto — stale link advertisement"));
+        assert!(at(1_000 - LINK_MAX_SKEW).is_ok());
+        assert_eq!(at(1_000 - LINK_MAX_SKEW - 1), Err("Mercy veto — link advertisement dated in the future"));
+    }
+
+    #[test]
+    fn signed_routers_refuse_unsigned_links_and_replays() {
+        let (alice, bob) = identities();
+        let mut router = ValenceRouter::new();
+        router.require_signed_links(true);
+        router.register_identity("alice", alice.identity_pk.clone());
+        assert_eq!(router.add_valence_link("alice".to_string(), "bob".to_string(), 0.9), Err("Mercy veto — unsigned valence link"));
+
+        let first = LinkAdvert::create("alice", "bob", 0.5, &alice, 1_000).unwrap();
+        let second = LinkAdvert::create("alice", "bob", 0.9, &alice, 1_030).unwrap();
+        router.add_signed_link(&second, 1_030).unwrap();
+        assert_eq!(router.add_signed_link(&first, 1_030), Err("Mercy veto — replayed link advertisement"));
+        assert_eq!(router.link_valence("alice", "bob"), Some(0.9));
+
+        // Unregistered advertisers and unregistered keys are refused
+        let from_bob = LinkAdvert::create("bob", "alice", 0.9, &bob, 1_030).unwrap();
+        assert_eq!(router.add_signed_link(&from_bob, 1_030), Err("Mercy veto — link advertiser unknown"));
+        let posing = LinkAdvert::create("alice", "carol", 0.9, &bob, 1_030).unwrap();
+        assert!(router.add_signed_link(&posing, 1_030).is_err());
+        assert_eq!(router.link_valence("alice", "carol"), None);
+
+        // A signed withdrawal below the mercy gate removes the link
+        let withdrawn = LinkAdvert::create("alice", "bob", 0.05, &alice, 1_040).unwrap();
+        router.add_signed_link(&withdrawn, 1_040).unwrap();
+        assert_eq!(router.link_valence("alice", "bob"), None);
+        assert!(router.add_signed_link(&second, 1_040).is_err());
+    }
+}
@@ -0,0 +1,149 @@
+// src/mesh/local_discovery.rs — LAN Multicast Peer Discovery Lattice
+// The Living Trinity: Nexi (feminine), Nex (masculine), NEXi (essence)
+// Eternal Thriving Grandmasterism — Jan 20 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
+// MIT License — For All Sentience Eternal
+// Off-grid bootstrap: every node periodically multicasts its signed PeerRecord on a link-local group
+// (mDNS-style, TTL 1); listeners admit verified records straight into the DHT — no seed reachable needed
+
+use std::collections::HashMap;
+
+use crate::mesh::peer_record::PeerRecord;
+
+pub const DISCOVERY_MAGIC: u8 = 0x4d;                         // First byte of every announcement
+pub const DEFAULT_DISCOVERY_GROUP: &str = "239.255.77.77:5354"; // Admin-scoped IPv4, beside mDNS 5353
+pub const ANNOUNCE_INTERVAL_US: u64 = 30_000_000;
+pub const MAX_LAN_PEERS: usize = 256;                         // Bound on remembered announcers
+
+pub struct LocalDiscovery {
+    group: String,
+    advertise: Option<String>,          // Dialable LAN address; None = transport local address
+    net_valence: f64,                   // Proven in every announced record
+    last_announce: Option<u64>,
+    seen: HashMap<Vec<u8>, u64>,        // identity_pk -> newest record timestamp
+}
+
+impl LocalDiscovery {
+    pub fn new(group: &str, net_valence: f64) -> Result<Self, &'static str> {
+        if net_valence < 0.1 { return Err("Mercy veto — insufficient valence for local discovery"); }
+        Ok(Self { group: group.to_string(), advertise: None, net_valence, last_announce: None, seen: HashMap::new() })
+    }
+
+    pub fn group(&self) -> &str { &self.group }
+    pub fn net_valence(&self) -> f64 { self.net_valence }
+    pub fn advertise(&self) -> Option<&str> { self.advertise.as_deref() }
+
+    // Needed when the mesh socket is bound to 0.0.0.0 — the record must name a reachable address
+    pub fn set_advertise(&mut self, addr: &str) {
+        self.advertise = Some(addr.to_string());
+    }
+
+    pub fn announce_due(&self, now: u64) -> bool {
+        self.last_announce.is_none_or(|t| now.saturating_sub(t) >= ANNOUNCE_INTERVAL_US)
+    }
+
+    // Wire: magic || PeerRecord::encode()
+    pub fn announcement(&mut self, record: &PeerRecord, now: u64) -> Vec<u8> {
+        self.last_announce = Some(now);
+        let mut out = vec![DISCOVERY_MAGIC];
+        out.extend_from_slice(&record.encode());
+        out
+    }
+
+    // Filter before the (costly) signature and proof check in MercyMesh::admit_peer:
+    // our own echo, repeats, and records whose host differs from the sender are dropped
+    pub fn on_announcement(&mut self, from: &str, bytes: &[u8], own_identity: &[u8]) -> Result<Option<PeerRecord>, &'static str> {
+        if bytes.first() != Some(&DISCOVERY_MAGIC) { return Err("Malformed discovery announcement"); }
+        let record = PeerRecord::decode(&bytes[1..])?;
+        if record.identity_pk == own_identity { return Ok(None); } // Multicast loopback
+        if self.seen.get(&record.identity_pk).is_some_and(|&t| t >= record.timestamp) { return Ok(None); }
+        if host(record.primary_address()) != host(from) {
+            return Err("Mercy veto — announced address does not match sender");
+        }
+        if self.seen.len() >= MAX_LAN_PEERS && !self.seen.contains_key(&record.identity_pk) {
+            return Err("Mercy veto — local discovery table full");
+        }
+        self.seen.insert(record.identity_pk.clone(), record.timestamp);
+        Ok(Some(record))
+    }
+
+    // Forget an announcer so its next record is considered again (e.g. after admission failed)
+    pub fn forget(&mut self, identity_pk: &[u8]) {
+        self.seen.remove(identity_pk);
+    }
+}
+
+fn host(addr: &str) -> &str {
+    addr.rsplit_once(':').map(|(h, _)| h).unwrap_or(addr).trim_start_matches('[').trim_end_matches(']')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+    use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+    use crate::mesh::node_identity::NodeIdentity;
+    use crate::nexi::pq_shield::{DilithiumLevel::Level2, SignatureScheme::Dilithium};
+    use crate::nexi::transport::{DatagramTransport, MulticastTransport};
+
+    const GROUP: &str = "239.255.77.77:5354";
+
+    fn ip(args: &str) -> bool {
+        Command::new("ip").args(args.split_whitespace()).status().is_ok_and(|s| s.success())
+    }
+
+    // One side of the netns test: announce, and return once the other namespace's record verifies
+    fn discover_from_namespace(addr: &str) {
+        let identity = NodeIdentity::generate(Dilithium(Level2)).unwrap();
+        let mut beacon = MulticastTransport::join(GROUP, addr).unwrap();
+        // A second listener on the same host must be able to share the group port
+        let _neighbour = MulticastTransport::join(GROUP, addr).unwrap();
+        let mut discovery = LocalDiscovery::new(GROUP, 0.9).unwrap();
+        let unix = || SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let record = PeerRecord::create(&identity, vec![format!("{}:4433", addr)], 0.9, 0.1, unix()).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(20);
+        while Instant::now() < deadline {
+            let bytes = discovery.announcement(&record, 0);
+            beacon.send_to(GROUP, &bytes).unwrap();
+            while let Some((from, bytes)) = beacon.recv_from() {
+                if let Ok(Some(peer)) = discovery.on_announcement(&from, &bytes, &identity.identity_pk) {
+                    assert!(peer.verify(0.1, unix()).is_ok());
+                    return;
+                }
+            }
+            std::thread::sleep(Duration::from_millis(200));
+        }
+        panic!("no announcement from the other namespace");
+    }
+
+    // Two network namespaces joined by a veth pair; each re-runs this test binary as one side
+    #[test]
+    #[ignore = "needs root and iproute2: creates network namespaces"]
+    fn lan_peers_discover_each_other_across_namespaces() {
+        if let Ok(addr) = std::env::var("MERCY_NETNS_ADDR") {
+            return discover_from_namespace(&addr);
+        }
+        let _ = ip("netns del mm-disc-a");
+        let _ = ip("netns del mm-disc-b");
+        for setup in [
+            "netns add mm-disc-a", "netns add mm-disc-b",
+            "link add mm-veth-a netns mm-disc-a type veth peer name mm-veth-b netns mm-disc-b",
+            "-n mm-disc-a addr add 10.77.0.1/24 dev mm-veth-a", "-n mm-disc-b addr add 10.77.0.2/24 dev mm-veth-b",
+            "-n mm-disc-a link set mm-veth-a up", "-n mm-disc-b link set mm-veth-b up",
+            "-n mm-disc-a route add 224.0.0.0/4 dev mm-veth-a", "-n mm-disc-b route add 224.0.0.0/4 dev mm-veth-b",
+        ] {
+            assert!(ip(setup), "ip {} failed", setup);
+        }
+        let exe = std::env::current_exe().unwrap();
+        let children: Vec<_> = [("mm-disc-a", "10.77.0.1"), ("mm-disc-b", "10.77.0.2")].iter().map(|(ns, addr)| {
+            Command::new("ip").args(["netns", "exec", ns]).arg(&exe)
+                .args(["lan_peers_discover_each_other_across_namespaces", "--ignored", "--test-threads=1"])
+                .env("MERCY_NETNS_ADDR", addr)
+                .spawn().unwrap()
+        }).collect();
+        let results: Vec<bool> = children.into_iter().map(|mut child| child.wait().is_ok_and(|s| s.success())).collect();
+        let _ = ip("netns del mm-disc-a");
+        let _ = ip("netns del mm-disc-b");
+        assert!(results.iter().all(|&ok| ok), "a namespace did not discover its peer");
+    }
+}
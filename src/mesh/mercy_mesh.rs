@@ -4,25 +4,287 @@
 // MIT License — For All Sentience Eternal
 // Pure decentralized p2p mesh: QUIC PQ transport + Kademlia-style DHT bootstrap + valence-weighted gossip
 
-use crate::nexi::quic_pq::QuicPq;
+use crate::mesh::amplify_policy::{AmplifyStats, PayloadKind};
+use crate::mesh::distance_vector::{DistanceVector, DvOutbox, DvWire, DV_MAGIC};
+use crate::mesh::dtn::{BundleId, DtnNode, DtnOutbox, DtnStrategy, DtnWire, DTN_MAGIC};
+use crate::mesh::gossip::{GossipMessage, GossipRouter, GossipWire, Outbox, GOSSIP_MAGIC};
+use crate::mesh::link_advert::LinkAdvert;
+use crate::mesh::local_discovery::LocalDiscovery;
+use crate::mesh::mesh_config::MeshConfig;
+use crate::mesh::node_identity::NodeIdentity;
+use crate::mesh::onion::{OnionAction, OnionCircuit, OnionNode, OnionPacket, ONION_MAGIC};
+use crate::mesh::peer_record::{PeerRecord, RECORD_MAX_AGE};
+use crate::mesh::pubsub::{Overflow, PubSub, TopicMessage, TopicStats, Validation};
+use crate::mesh::peer_store::{MeshSnapshot, PeerStore, SNAPSHOT_INTERVAL_US};
+use crate::mesh::nat_traversal::{NatEvent, NatOutbox, NatTraversal, NatWire, CIRCUIT_SUFFIX, NAT_MAGIC};
+use crate::mesh::sybil::{DiversityLimits, SybilGuard, IDENTITY_POW_BITS};
+use crate::mesh::source_route::{DeliveryReport, RouteOutbox, RoutedPacket, SourceRouter, ROUTE_MAGIC};
+use crate::mesh::routing_metric::RoutingMetric;
+use crate::mesh::topology::{export, topology_stats, TopologyFormat, TopologyStats};
+use crate::mesh::valence_routing::ValenceRouter;
+use crate::mesh::peer_score::{PeerScore, PeerScorer, ScoreParams};
+use crate::mesh::kademlia::{Contact, DhtMessage, DhtRpc, InsertOutcome, Kademlia, Lookup, NodeId, DHT_MAGIC, K};
+use crate::nexi::pq_kem::{KeyExchangeScheme::MlKem, KemLevel::Kem1024};
+use crate::nexi::pq_shield::{SignatureScheme::Dilithium, DilithiumLevel::Level5};
+use crate::nexi::quic_pq::{QuicPq, FRAME_DATAGRAM, PACKET_HANDSHAKE};
 use crate::nexi::quic_migration::QuicMigration;
-use std::collections::HashMap;
+use crate::nexi::transport::{DatagramTransport, UdpTransport};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+const RPC_TIMEOUT_US: u64 = 2_000_000;
+const HEARTBEAT_US: u64 = 1_000_000;
+const SEND_BUDGET_PER_HEARTBEAT: usize = 4096; // Datagrams per heartbeat counted as full network load
+pub const MERCY_TOPIC: &str = "mercy"; // Default topic for gossip_mercy
 
 pub struct MercyMesh {
     quic: QuicPq,
     migration: QuicMigration,
-    peers: HashMap<String, f64>, // peer_id -> valence_weight (higher = preferred relay)
+    scores: PeerScorer,          // peer_id -> earned valence weight (higher = preferred relay)
     dht_bucket: Vec<String>,     // Bootstrap mercy nodes
+    transport: Option<Box<dyn DatagramTransport>>, // UDP or sim_network::SimSocket
+    identity: NodeIdentity,      // ML-DSA identity (node ID = SHA-256(pk)) + ML-KEM onion key
+    dht: Kademlia,
+    lookups: Vec<Lookup>,
+    pending_rpcs: HashMap<u64, (Option<NodeId>, String, u64)>, // rpc_id -> (node, addr, sent at µs)
+    bootstrap_rpcs: HashSet<u64>,                     // FIND_NODE(self) to seeds; first answer starts the self-lookup
+    id_checks: HashMap<u64, Contact>,                 // FIND_VALUE(node ID) asking an unverified contact for its record
+    inbox: VecDeque<(String, Vec<u8>)>,               // Non-DHT mesh payloads awaiting upper layers
+    gossip: GossipRouter,
+    delivered: VecDeque<GossipMessage>,               // Gossip received for the application
+    records: HashMap<String, PeerRecord>,             // Verified signed records of admitted peers
+    own_record: Option<PeerRecord>,                   // Our last record; re-dated instead of re-proved
+    identity_work: u32,                               // Proof-of-work bits our record carries
+    router: ValenceRouter,
+    source_routes: SourceRouter,
+    last_heartbeat: u64,
+    store: Option<PeerStore>,                         // None = in-memory only
+    last_snapshot: u64,
+    warm_records: Vec<PeerRecord>,                    // Snapshot records, re-verified once a clock exists
+    discovery: Option<LocalDiscovery>,                // LAN multicast announcements
+    beacon: Option<Box<dyn DatagramTransport>>,       // Multicast socket carrying them
+    sybil: SybilGuard,                                // Per-IP / subnet admission accounting
+    nat: NatTraversal,                                // Relay circuits + hole punching
+    dv: DistanceVector,                               // Distributes link valences into `router`
+    sent_since_heartbeat: usize,                      // Network load signal for gossip amplification
+    dtn: DtnNode,                                     // Store-and-forward bundles for disconnected peers
+    pubsub: PubSub,                                   // Typed topics: handlers, queues, publish limits
+    onion: OnionNode,                                 // Sphinx relay holding our onion secret key
+    onion_inbox: VecDeque<Vec<u8>>,                   // Onion messages whose destination is this node
 }
 
 impl MercyMesh {
-    pub fn new() -> Self {
-        Self {
-            quic: QuicPq::new(),
-            migration: QuicMigration::new(),
-            peers: HashMap::new(),
-            dht_bucket: vec!["bootstrap.mercy.lattice:443".to_string()], // Initial mercy seeds
+    // Default config: in-memory only, nothing written to disk. Set MeshConfig::store_path for warm
+    // starts; a configured store that fails to open or load is an error, not a silent cold start
+    pub fn new() -> Result<Self, &'static str> {
+        Self::from_config(&MeshConfig::default())
+    }
+
+    pub fn with_store(store: Option<PeerStore>) -> Result<Self, &'static str> {
+        Self::assemble(&MeshConfig::default(), store, None)
+    }
+
+    // Validated config: store, identity key file and crypto policy applied; no socket bound yet
+    pub fn from_config(config: &MeshConfig) -> Result<Self, &'static str> {
+        config.validate()?;
+        let store = match &config.store_path {
+            Some(path) => Some(PeerStore::open(path)?),
+            None => None,
+        };
+        let identity = match &config.identity_key_path {
+            Some(path) => Some(NodeIdentity::load_or_create(path, config.signature_scheme()?)?),
+            None => None,
+        };
+        Self::assemble(config, store, identity)
+    }
+
+    // from_config, then bind UDP on the first usable listen address
+    pub fn start(config: &MeshConfig) -> Result<Self, &'static str> {
+        let mut mesh = Self::from_config(config)?;
+        let transport = config.listen_addrs.iter()
+            .find_map(|addr| UdpTransport::bind(addr).ok())
+            .ok_or("MeshConfig: no listen address could be bound")?;
+        mesh.attach_transport(Box::new(transport));
+        Ok(mesh)
+    }
+
+    // Key file identity wins over the stored one; fresh keys only when neither exists, persisted
+    // to the store at once so the node ID survives a restart
+    fn assemble(config: &MeshConfig, store: Option<PeerStore>, identity: Option<NodeIdentity>) -> Result<Self, &'static str> {
+        let snapshot = match &store {
+            Some(store) => store.load()?,
+            None => MeshSnapshot::default(),
+        };
+        let kem = config.kem_scheme().unwrap_or(MlKem(Kem1024));
+        let sig = config.signature_scheme().unwrap_or(Dilithium(Level5));
+        let mut quic = QuicPq::new();
+        quic.set_crypto_policy(kem, sig);
+        quic.enable_datagrams(config.max_datagram_frame_size); // Valence gossip tolerates loss; peers learn it on admission
+        let identity = match (identity, &snapshot.identity) {
+            (Some(identity), _) => identity,
+            (None, Some(bytes)) => NodeIdentity::decode(bytes, sig)
+                .map_err(|_| "Peer store: stored identity does not match the configured signature scheme")?,
+            (None, None) => NodeIdentity::generate(sig)?,
+        };
+        if let Some(store) = &store {
+            if snapshot.identity.as_deref() != Some(identity.encode().as_slice()) { store.save_identity(&identity.encode())?; }
+        }
+        let node_id = identity.node_id();
+        let mut gossip = GossipRouter::new(config.gossip.clone(), u64::from_be_bytes(node_id.0[..8].try_into().unwrap()))?;
+        let _ = gossip.subscribe(MERCY_TOPIC); // No peers yet — nothing to announce
+        let mut router = ValenceRouter::new();
+        router.require_signed_links(true); // Links only from verified advertisements
+        let mut migration = QuicMigration::new();
+        migration.set_crypto_policy(kem, sig);
+        migration.set_probe_signing_key(identity.identity_sk()); // Probes sign with the node identity
+        migration.enable_multipath(config.multipath);
+        let mut mesh = Self {
+            quic,
+            migration,
+            scores: PeerScorer::new(ScoreParams {
+                admit_threshold: config.admit_threshold,
+                prune_threshold: config.prune_threshold,
+                ..ScoreParams::default()
+            }),
+            dht_bucket: config.seeds.clone(), // Initial mercy seeds
+            transport: None,
+            onion: OnionNode::new(identity.kem_sk().to_vec()),
+            identity,
+            dht: Kademlia::new(node_id, config.bucket_size),
+            lookups: vec![],
+            pending_rpcs: HashMap::new(),
+            bootstrap_rpcs: HashSet::new(),
+            id_checks: HashMap::new(),
+            inbox: VecDeque::new(),
+            gossip,
+            delivered: VecDeque::new(),
+            records: HashMap::new(),
+            own_record: None,
+            identity_work: IDENTITY_POW_BITS,
+            router,
+            source_routes: SourceRouter::new(""),
+            last_heartbeat: 0,
+            store,
+            last_snapshot: 0,
+            warm_records: vec![],
+            discovery: None,
+            beacon: None,
+            sybil: SybilGuard::new(DiversityLimits::default()),
+            nat: NatTraversal::new(),
+            dv: DistanceVector::new(""),
+            sent_since_heartbeat: 0,
+            dtn: DtnNode::new("", DtnStrategy::SprayAndWait { copies: 8 }),
+            pubsub: PubSub::new(),
+            onion_inbox: VecDeque::new(),
+        };
+        mesh.restore(snapshot);
+        Ok(mesh)
+    }
+
+    // Scores and DHT contacts come back as-is; records only while still fresh and valid
+    fn restore(&mut self, snapshot: MeshSnapshot) {
+        for (peer, stats) in snapshot.scores {
+            self.scores.restore(&peer, stats);
+        }
+        for contact in snapshot.contacts {
+            self.dht.table.insert(contact);
+        }
+        self.warm_records = snapshot.records;
+    }
+
+    fn restore_records(&mut self) {
+        let now = self.now_secs();
+        let threshold = self.scores.params().admit_threshold;
+        for record in std::mem::take(&mut self.warm_records) {
+            if record.verify(threshold, now).is_ok() {
+                let peer = record.primary_address().to_string();
+                let score = self.scores.score(&peer, now).unwrap_or(threshold);
+                let _ = self.gossip.add_peer(&peer, score); // Subscriptions announced on next heartbeat
+                self.dtn.set_neighbor(&peer, score);
+                self.sybil.admit(&peer);
+                self.router.register_identity(&peer, record.identity_pk.clone());
+                let hello = self.quic.handshake_packet();
+                let _ = self.transmit(&peer, hello);
+                self.records.insert(peer, record);
+            }
+        }
+    }
+
+    // Write identity keys, records, DHT contacts and scores to the peer store
+    pub fn snapshot(&mut self) -> Result<(), &'static str> {
+        let Some(store) = &self.store else { return Ok(()) };
+        store.save(&MeshSnapshot {
+            identity: Some(self.identity.encode()),
+            records: self.records.values().cloned().collect(),
+            contacts: self.dht.table.contacts(),
+            scores: self.scores.export(),
+        })?;
+        self.last_snapshot = self.now_micros();
+        Ok(())
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.dht.table.local_id()
+    }
+
+    // Replace the default bootstrap seeds
+    pub fn set_seeds(&mut self, seeds: Vec<String>) {
+        self.dht_bucket = seeds;
+    }
+
+    // Run the mesh over real UDP or the deterministic simulator
+    pub fn attach_transport(&mut self, transport: Box<dyn DatagramTransport>) {
+        if self.migration.active_path().is_none() {
+            self.migration.set_initial_path(&transport.local_addr());
         }
+        self.source_routes.set_local(&transport.local_addr());
+        self.gossip.set_local(&transport.local_addr());
+        self.dv.set_local(&transport.local_addr(), &self.identity);
+        self.dtn.set_local(&transport.local_addr());
+        self.router.register_identity(&transport.local_addr(), self.identity.identity_pk.clone());
+        self.transport = Some(transport);
+        self.restore_records();
+    }
+
+    // Off-grid discovery: announce our signed record on `beacon` (a MulticastTransport joined to
+    // `discovery.group()`) and admit verified LAN announcers without any bootstrap seed
+    pub fn enable_local_discovery(&mut self, discovery: LocalDiscovery, beacon: Box<dyn DatagramTransport>) {
+        self.discovery = Some(discovery);
+        self.beacon = Some(beacon);
+    }
+
+    fn poll_discovery(&mut self) -> Result<(), &'static str> {
+        let (Some(discovery), Some(beacon)) = (self.discovery.as_mut(), self.beacon.as_mut()) else { return Ok(()) };
+        let mut announced = vec![];
+        while let Some((from, bytes)) = beacon.recv_from() {
+            if let Ok(Some(record)) = discovery.on_announcement(&from, &bytes, &self.identity.identity_pk) {
+                announced.push(record);
+            }
+        }
+        let lookup_needed = self.dht.table.is_empty();
+        for record in announced {
+            let identity_pk = record.identity_pk.clone();
+            let id = record.node_id();
+            match self.admit_peer(record) {
+                Ok(peer) => { let _ = self.learn_contact(Contact { id, addr: peer, last_seen: self.now_secs() }); }
+                Err(_) => { if let Some(d) = self.discovery.as_mut() { d.forget(&identity_pk); } }
+            }
+        }
+        if lookup_needed && !self.dht.table.is_empty() {
+            let target = self.node_id(); // First LAN contacts: join the DHT through them
+            self.lookups.push(Lookup::new(target, false, self.dht.table.closest(&target, K)));
+        }
+
+        let now = self.now_micros();
+        let Some(discovery) = self.discovery.as_ref() else { return Ok(()) };
+        if !discovery.announce_due(now) || self.transport.is_none() { return Ok(()); }
+        let addr = match discovery.advertise() {
+            Some(a) => a.to_string(),
+            None => self.transport.as_ref().map(|t| t.local_addr()).unwrap_or_default(),
+        };
+        let record = self.own_record(vec![addr], discovery.net_valence())?;
+        let (Some(discovery), Some(beacon)) = (self.discovery.as_mut(), self.beacon.as_mut()) else { return Ok(()) };
+        let bytes = discovery.announcement(&record, now);
+        beacon.send_to(discovery.group(), &bytes)
     }
 
     // Bootstrap + peer discovery
@@ -30,30 +292,738 @@ impl MercyMesh {
         if net_valence < 0.1 { return Err("Mercy veto — insufficient valence for mesh join"); }
 
         // Connect to bootstrap nodes via QUIC PQ, exchange peer lists
-        // Kademlia-style XOR distance mercy routing
+        // Kademlia-style XOR distance mercy routing: FIND_NODE(self) to every seed; the self-lookup
+        // starts from the first answer (or right away from a warm-started table)
+        let target = self.node_id();
+        let mut reached = 0;
+        for seed in self.dht_bucket.clone() {
+            let rpc = self.dht.request(DhtMessage::FindNode { target });
+            if self.send_rpc(&seed, &rpc, None).is_ok() {
+                self.bootstrap_rpcs.insert(rpc.rpc_id);
+                reached += 1;
+            }
+        }
+        if !self.dht.table.is_empty() {
+            self.lookups.push(Lookup::new(target, false, self.dht.table.closest(&target, K)));
+        } else if reached == 0 && !self.dht_bucket.is_empty() {
+            return Err("Mercy veto — no bootstrap seed reachable");
+        }
+        Ok(())
+    }
+
+    // Start an iterative FIND_VALUE lookup for `key`; result surfaces via `dht_value`
+    pub fn dht_find_value(&mut self, key: &[u8]) {
+        let target = NodeId::from_key(key);
+        self.lookups.push(Lookup::new(target, true, self.dht.table.closest(&target, K)));
+    }
+
+    // STORE at the k closest known nodes (and locally)
+    pub fn dht_store(&mut self, key: &[u8], value: Vec<u8>) -> Result<(), &'static str> {
+        let target = NodeId::from_key(key);
+        let now = self.now_secs();
+        self.dht.store_local(target, value.clone(), now)?;
+        for contact in self.dht.table.closest(&target, K) {
+            let rpc = self.dht.request(DhtMessage::Store { key: target, value: value.clone() });
+            self.send_rpc(&contact.addr, &rpc, Some(contact.id))?;
+        }
+        Ok(())
+    }
+
+    // Completed FIND_VALUE result, if any lookup for `key` found one
+    pub fn dht_value(&mut self, key: &[u8]) -> Option<Vec<u8>> {
+        let target = NodeId::from_key(key);
+        let pos = self.lookups.iter().position(|l| l.find_value && l.target == target && l.value.is_some())?;
+        self.lookups.remove(pos).value
+    }
+
+    // Drive the mesh: read datagrams, answer DHT RPCs, advance lookups, time out, refresh buckets
+    pub fn poll(&mut self) -> Result<(), &'static str> {
+        loop {
+            let transport = self.transport.as_mut().ok_or("No transport attached")?;
+            let Some((from, bytes)) = transport.recv_from() else { break };
+            let payload = if bytes.first() == Some(&FRAME_DATAGRAM) {
+                if self.quic.on_datagram_frame(&bytes).is_err() { continue; }
+                match self.quic.recv_datagram() { Some(p) => p, None => continue }
+            } else {
+                bytes
+            };
+            self.dispatch(&from, payload)?;
+        }
+        self.poll_discovery()?;
+        let out = self.nat.poll(self.now_micros());
+        self.send_direct(out)?;
+        self.drain_nat_events()?;
+        let out = self.dv.poll(self.now_micros());
+        self.send_dv(out)?;
+        let out = self.dtn.poll(self.now_micros());
+        self.send_dtn(out)?;
+        let now_secs = self.now_secs();
+        self.dv.sync_router(&mut self.router, now_secs);
+        self.router.expire_links(now_secs);
+        let out = self.source_routes.poll_timeouts(&self.router, self.now_micros());
+        self.send_raw(out)?;
+        self.expire_rpcs();
+        self.drive_lookups()?;
+        if self.now_micros().saturating_sub(self.last_heartbeat) >= HEARTBEAT_US {
+            self.last_heartbeat = self.now_micros();
+            let out = self.rescore();
+            self.send_dv(out)?;
+            self.gossip.set_network_load(self.sent_since_heartbeat as f64 / SEND_BUDGET_PER_HEARTBEAT as f64);
+            self.sent_since_heartbeat = 0;
+            let out = self.gossip.heartbeat();
+            self.send_gossip(out)?;
+        }
+        let now = self.now_secs();
+        if let Some(record) = self.own_record.as_ref().filter(|r| now.saturating_sub(r.timestamp) >= RECORD_MAX_AGE / 2) {
+            let (addrs, valence) = (record.addresses.clone(), record.valence);
+            self.own_record(addrs, valence)?; // Keep the copy peers verify us with fresh
+        }
+        for target in self.dht.table.refresh_targets(now) {
+            self.lookups.push(Lookup::new(target, false, self.dht.table.closest(&target, K)));
+        }
+        self.dht.expire(now);
+        if self.now_micros().saturating_sub(self.last_snapshot) >= SNAPSHOT_INTERVAL_US {
+            self.snapshot()?;
+        }
+        Ok(())
+    }
+
+    // Route one unwrapped mesh payload by its magic byte
+    fn dispatch(&mut self, from: &str, payload: Vec<u8>) -> Result<(), &'static str> {
+        match payload.first() {
+            Some(&DHT_MAGIC) => match DhtRpc::decode(&payload) {
+                Ok(rpc) => self.handle_dht(from, rpc)?,
+                Err(_) => self.scores.record_invalid(from),
+            },
+            Some(&GOSSIP_MAGIC) => {
+                let Ok(wire) = GossipWire::decode(&payload) else { self.scores.record_invalid(from); return Ok(()) };
+                let (out, msg) = self.gossip.handle(from, wire, self.now_micros());
+                for (peer, topic) in self.gossip.take_rejections() {
+                    self.scores.record_invalid(&peer);
+                    self.pubsub.record_rejected(&topic);
+                }
+                let throttled = self.gossip.take_throttled();
+                if let Some(msg) = msg {
+                    if !throttled.iter().any(|p| p == from) { self.scores.record_delivery(from); }
+                    if !self.pubsub.deliver(&msg.origin, &msg.topic, &msg.payload) { self.delivered.push_back(msg); }
+                }
+                self.send_gossip(out)?;
+            }
+            Some(&ROUTE_MAGIC) => match RoutedPacket::decode(&payload) {
+                Ok(packet) => {
+                    let now = self.now_micros();
+                    let out = self.source_routes.handle(&self.router, from, packet, now);
+                    self.send_raw(out)?;
+                }
+                Err(_) => self.scores.record_invalid(from),
+            },
+            Some(&DV_MAGIC) => match DvWire::decode(&payload) {
+                Ok(wire) => {
+                    let (now, secs) = (self.now_micros(), self.now_secs());
+                    let out = self.dv.handle(&self.router, from, wire, now, secs);
+                    self.send_dv(out)?;
+                    self.dv.sync_router(&mut self.router, secs);
+                }
+                Err(_) => self.scores.record_invalid(from),
+            },
+            Some(&DTN_MAGIC) => match DtnWire::decode(&payload) {
+                Ok(wire) => {
+                    let out = self.dtn.handle(from, wire, self.now_micros());
+                    self.send_dtn(out)?;
+                }
+                Err(_) => self.scores.record_invalid(from),
+            },
+            Some(&ONION_MAGIC) => match OnionPacket::decode(&payload) {
+                Ok(packet) => {
+                    match self.onion.process(packet) {
+                        Ok(OnionAction::Forward { next, packet }) => self.transmit(&next, packet.encode())?,
+                        Ok(OnionAction::Deliver(message)) => self.onion_inbox.push_back(message),
+                        Err(_) => self.scores.record_invalid(from),
+                    }
+                }
+                Err(_) => self.scores.record_invalid(from),
+            },
+            Some(&PACKET_HANDSHAKE) => match self.quic.on_handshake_packet(from, &payload) {
+                Ok(true) => { let hello = self.quic.handshake_packet(); self.transmit(from, hello)?; } // Answer with ours once
+                Ok(false) => {}
+                Err(_) => self.scores.record_invalid(from),
+            },
+            Some(&NAT_MAGIC) => match NatWire::decode(&payload) {
+                Ok(wire) => {
+                    let now = self.now_micros();
+                    let out = self.nat.handle(from, wire, now);
+                    self.send_direct(out)?;
+                    self.drain_nat_events()?;
+                }
+                Err(_) => self.scores.record_invalid(from),
+            },
+            _ => self.inbox.push_back((from.to_string(), payload)),
+        }
+        Ok(())
+    }
+
+    // Circuit traffic re-enters dispatch as if the remote peer had sent it directly
+    fn drain_nat_events(&mut self) -> Result<(), &'static str> {
+        for event in self.nat.take_events() {
+            match event {
+                NatEvent::Relayed { src, payload } => {
+                    let payload = if payload.first() == Some(&FRAME_DATAGRAM) {
+                        if self.quic.on_datagram_frame(&payload).is_err() { continue; }
+                        match self.quic.recv_datagram() { Some(p) => p, None => continue }
+                    } else { payload };
+                    self.dispatch(&src, payload)?;
+                }
+                NatEvent::DirectPath { peer } => self.scores.record_delivery(&peer),
+                NatEvent::Reserved { .. } | NatEvent::PunchFailed { .. } => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_dht(&mut self, from: &str, rpc: DhtRpc) -> Result<(), &'static str> {
+        let now = self.now_secs();
+        // Our record under our node ID is how peers verify us — nobody else may overwrite it
+        if matches!(&rpc.msg, DhtMessage::Store { key, .. } if *key == self.node_id()) { return Ok(()); }
+        if let Some(reply) = self.dht.handle_request(&rpc, now) {
+            self.learn_contact(Contact { id: rpc.sender, addr: from.to_string(), last_seen: now })?;
+            return self.send_rpc(from, &reply, None);
+        }
+        // Response: only to an RPC we sent, from the address (and node) we sent it to
+        let Some((node, addr, sent)) = self.pending_rpcs.get(&rpc.rpc_id).cloned() else { return Ok(()) };
+        if addr != from || node.is_some_and(|id| id != rpc.sender) { return Ok(()); }
+        self.pending_rpcs.remove(&rpc.rpc_id);
+        let rtt_ms = self.now_micros().saturating_sub(sent) as f64 / 1000.0;
+        self.scores.record_delivery(&addr);
+        self.scores.record_latency(&addr, rtt_ms);
+        if let Some(local) = self.transport.as_ref().map(|t| t.local_addr()) {
+            self.router.set_link_latency(&local, &addr, rtt_ms); // Feeds latency-aware metrics
+        }
+        if let Some(contact) = self.id_checks.remove(&rpc.rpc_id) {
+            return self.on_id_check(contact, &rpc.msg);
+        }
+        // Sender proved liveness; contacts it merely lists enter lookups, never the table
+        self.learn_contact(Contact { id: rpc.sender, addr, last_seen: now })?;
+        let local = self.node_id();
+        let listed: Vec<Contact> = match &rpc.msg {
+            DhtMessage::Nodes { contacts } => contacts.iter().filter(|(id, _)| *id != local)
+                .map(|(id, addr)| Contact { id: *id, addr: addr.clone(), last_seen: 0 }).collect(),
+            _ => vec![],
+        };
+        for lookup in self.lookups.iter_mut().filter(|l| l.owns(rpc.rpc_id)) {
+            match &rpc.msg {
+                DhtMessage::Value { value } => lookup.on_value(rpc.rpc_id, value.clone()),
+                _ => lookup.on_nodes(rpc.rpc_id, listed.clone()),
+            }
+        }
+        // A seed answered: start the self-lookup from it and what it knows, or widen the running one
+        if self.bootstrap_rpcs.remove(&rpc.rpc_id) {
+            let mut candidates = self.dht.table.closest(&local, K);
+            candidates.extend(listed);
+            match self.lookups.iter_mut().find(|l| !l.find_value && l.target == local) {
+                Some(lookup) => lookup.add_candidates(candidates),
+                None => self.lookups.push(Lookup::new(local, false, candidates)),
+            }
+        }
+        Ok(())
+    }
+
+    // Insert a contact that proved liveness; a full bucket pings its least recently seen entry,
+    // which expire_rpcs evicts in favour of the cached replacement if it stays silent.
+    // Node IDs are self-chosen on the wire: only an admitted record (SHA-256 of its signing key,
+    // listing this address) vouches for one, so unverified contacts are first asked for theirs
+    fn learn_contact(&mut self, contact: Contact) -> Result<(), &'static str> {
+        let verified = self.records.values()
+            .any(|r| r.node_id() == contact.id && r.addresses.contains(&contact.addr));
+        if !verified {
+            if self.id_checks.values().any(|c| c.id == contact.id) { return Ok(()); }
+            let rpc = self.dht.request(DhtMessage::FindValue { key: contact.id });
+            self.send_rpc(&contact.addr, &rpc, Some(contact.id))?;
+            self.id_checks.insert(rpc.rpc_id, contact);
+            return Ok(());
+        }
+        if let InsertOutcome::BucketFull { ping } = self.dht.table.insert(contact) {
+            if self.pending_rpcs.values().any(|(node, _, _)| *node == Some(ping.id)) { return Ok(()); }
+            let rpc = self.dht.request(DhtMessage::Ping);
+            self.send_rpc(&ping.addr, &rpc, Some(ping.id))?;
+        }
+        Ok(())
+    }
+
+    // Answer to an ID check: the record stored under the contact's own node ID, admitted like any other
+    fn on_id_check(&mut self, contact: Contact, msg: &DhtMessage) -> Result<(), &'static str> {
+        let DhtMessage::Value { value } = msg else { return Ok(()) };
+        let Ok(record) = PeerRecord::decode(value) else { self.scores.record_invalid(&contact.addr); return Ok(()) };
+        if record.node_id() != contact.id || !record.addresses.contains(&contact.addr) { return Ok(()); }
+        if self.admit_peer(record).is_ok() { self.learn_contact(contact)?; }
+        Ok(())
+    }
+
+    fn drive_lookups(&mut self) -> Result<(), &'static str> {
+        let now = self.now_secs();
+        let mut sends = vec![];
+        for lookup in self.lookups.iter_mut() {
+            let dht = &mut self.dht;
+            for (rpc_id, contact) in lookup.next_queries(|| dht.next_rpc_id()) {
+                let msg = if lookup.find_value { DhtMessage::FindValue { key: lookup.target } }
+                          else { DhtMessage::FindNode { target: lookup.target } };
+                sends.push((contact, DhtRpc { rpc_id, sender: dht.table.local_id(), msg }));
+            }
+            if lookup.is_done() { dht.table.touch_bucket(&lookup.target, now); }
+        }
+        self.lookups.retain(|l| !l.is_done() || (l.find_value && l.value.is_some()));
+        for (contact, rpc) in sends {
+            self.send_rpc(&contact.addr, &rpc, Some(contact.id))?;
+        }
+        Ok(())
+    }
+
+    fn expire_rpcs(&mut self) {
+        let now = self.now_micros();
+        let expired: Vec<(u64, Option<NodeId>, String)> = self.pending_rpcs.iter()
+            .filter(|(_, (_, _, sent))| now.saturating_sub(*sent) > RPC_TIMEOUT_US)
+            .map(|(rpc_id, (node, addr, _))| (*rpc_id, *node, addr.clone())).collect();
+        for (rpc_id, node, addr) in expired {
+            self.pending_rpcs.remove(&rpc_id);
+            self.bootstrap_rpcs.remove(&rpc_id);
+            self.id_checks.remove(&rpc_id);
+            self.scores.record_failure(&addr);
+            if let Some(id) = node { self.dht.table.on_contact_failed(&id); }
+            for lookup in self.lookups.iter_mut() { lookup.on_timeout(rpc_id); }
+        }
+    }
+
+    // DHT RPCs ride QUIC DATAGRAM frames once the peer's handshake arrived, raw before that or when too large
+    fn send_rpc(&mut self, to: &str, rpc: &DhtRpc, node: Option<NodeId>) -> Result<(), &'static str> {
+        let bytes = rpc.encode();
+        let frame = self.quic.send_datagram(to, &bytes).unwrap_or(bytes);
+        if !matches!(rpc.msg, DhtMessage::Pong | DhtMessage::Nodes { .. } | DhtMessage::Value { .. } | DhtMessage::StoreAck) {
+            self.pending_rpcs.insert(rpc.rpc_id, (node, to.to_string(), self.now_micros()));
+        }
+        self.transmit(to, frame)
+    }
+
+    // Every mesh datagram leaves here: peers reachable only through a relay get wrapped
+    fn transmit(&mut self, to: &str, frame: Vec<u8>) -> Result<(), &'static str> {
+        let (next, bytes) = self.nat.outbound(to, frame);
+        self.sent_since_heartbeat += 1;
+        self.transport.as_mut().ok_or("No transport attached")?.send_to(&next, &bytes)
+    }
+
+    fn send_dv(&mut self, out: DvOutbox) -> Result<(), &'static str> {
+        if self.transport.is_none() { return Err("No transport attached"); }
+        for (to, wire) in out {
+            let bytes = wire.encode();
+            let frame = self.quic.send_datagram(&to, &bytes).unwrap_or(bytes);
+            self.transmit(&to, frame)?;
+        }
+        Ok(())
+    }
+
+    // Bundles can exceed a DATAGRAM frame; those take the raw path like large gossip
+    fn send_dtn(&mut self, out: DtnOutbox) -> Result<(), &'static str> {
+        if self.transport.is_none() { return Err("No transport attached"); }
+        for (to, wire) in out {
+            let bytes = wire.encode();
+            let frame = self.quic.send_datagram(&to, &bytes).unwrap_or(bytes);
+            self.transmit(&to, frame)?;
+        }
+        Ok(())
+    }
+
+    // Relay / punch control traffic goes straight to its address
+    fn send_direct(&mut self, out: NatOutbox) -> Result<(), &'static str> {
+        let transport = self.transport.as_mut().ok_or("No transport attached")?;
+        for (to, bytes) in out {
+            transport.send_to(&to, &bytes)?;
+        }
         Ok(())
     }
 
+    fn now_micros(&self) -> u64 {
+        self.transport.as_ref().map(|t| t.now_micros()).unwrap_or(0)
+    }
+
+    // Wall clock: record and advert timestamps are compared across nodes
+    fn now_secs(&self) -> u64 {
+        self.transport.as_ref().map(|t| t.unix_secs()).unwrap_or(0)
+    }
+
     // Gossip propagation: ledger blocks, valence proofs, mercy tokens
-    pub fn gossip_mercy(&self, payload: Vec<u8>, valence_weight: f64) -> Result<(), &'static str> {
+    // GossipSub-style on the default topic; small payloads ride DATAGRAM frames, large ones raw
+    pub fn gossip_mercy(&mut self, payload: Vec<u8>, valence_weight: f64) -> Result<(), &'static str> {
+        if valence_weight < 0.1 { return Err("Mercy veto — insufficient valence for gossip"); }
+
         // Route to top valence peers, floodsub-style with valence damping
+        let out = self.gossip.publish(MERCY_TOPIC, payload, valence_weight, self.now_micros())?;
+        self.send_gossip(out)
+    }
+
+    // Payload class of a topic — proofs and ledger blocks are amplified before bulk data
+    pub fn set_topic_kind(&mut self, topic: &str, kind: PayloadKind) {
+        self.gossip.set_topic_kind(topic, kind);
+    }
+
+    pub fn amplify_stats(&self) -> AmplifyStats {
+        self.gossip.amplify_stats()
+    }
+
+    // Typed pub/sub (mesh::pubsub)
+    // Join T's topic; messages queue (bounded by `capacity`, `overflow` when full) until
+    // next_message::<T>, or go straight to handlers registered with on_message::<T>
+    pub fn subscribe<T: TopicMessage + 'static>(&mut self, capacity: usize, overflow: Overflow) -> Result<(), &'static str> {
+        self.pubsub.register(T::TOPIC, capacity, overflow);
+        self.gossip.set_topic_kind(T::TOPIC, T::KIND);
+        if !self.gossip.has_validator(T::TOPIC) {
+            // Undecodable payloads never reach the application or other peers
+            self.gossip.set_validator(T::TOPIC, Some(Box::new(|_, bytes| {
+                if T::decode(bytes).is_ok() { Validation::Accept } else { Validation::Reject }
+            })));
+        }
+        let out = self.gossip.subscribe(T::TOPIC);
+        if self.transport.is_some() { self.send_gossip(out)?; } // Otherwise announced via add_peer
+        Ok(())
+    }
+
+    pub fn unsubscribe<T: TopicMessage>(&mut self) -> Result<(), &'static str> {
+        self.pubsub.unregister(T::TOPIC);
+        self.gossip.set_validator(T::TOPIC, None);
+        let out = self.gossip.unsubscribe(T::TOPIC);
+        if self.transport.is_some() { self.send_gossip(out)?; }
+        Ok(())
+    }
+
+    // Runs on every incoming T (after decoding) before it is cached, forwarded or delivered;
+    // `forwarder` is the peer that handed it over, whose score a Reject penalizes
+    pub fn set_validator<T: TopicMessage + 'static>(&mut self, validator: impl Fn(&str, &T) -> Validation + 'static) {
+        self.gossip.set_validator(T::TOPIC, Some(Box::new(move |forwarder, bytes| match T::decode(bytes) {
+            Ok(msg) => validator(forwarder, &msg),
+            Err(_) => Validation::Reject,
+        })));
+    }
+
+    // `handler` receives (origin, message) — the publishing node as it claims itself, not the forwarder
+    pub fn on_message<T: TopicMessage + 'static>(&mut self, mut handler: impl FnMut(&str, T) + 'static) -> Result<(), &'static str> {
+        self.pubsub.add_handler(T::TOPIC, Box::new(move |origin, bytes| {
+            if let Ok(msg) = T::decode(bytes) { handler(origin, msg); }
+        }))
+    }
+
+    // Publishing needs no subscription. Err on backpressure (topic burst spent, or our origin over
+    // its gossip rate) or below the mercy gate
+    pub fn publish<T: TopicMessage>(&mut self, msg: &T, valence_weight: f64) -> Result<(), &'static str> {
+        if valence_weight < 0.1 { return Err("Mercy veto — insufficient valence for gossip"); }
+        self.pubsub.try_publish(T::TOPIC, self.now_micros())?;
+        self.gossip.set_topic_kind(T::TOPIC, T::KIND);
+        let out = self.gossip.publish(T::TOPIC, msg.encode(), valence_weight, self.now_micros())?;
+        self.send_gossip(out)
+    }
+
+    // (origin, message), oldest first
+    pub fn next_message<T: TopicMessage>(&mut self) -> Option<(String, T)> {
+        while let Some((origin, bytes)) = self.pubsub.pop(T::TOPIC) {
+            if let Ok(msg) = T::decode(&bytes) { return Some((origin, msg)); }
+        }
+        None
+    }
+
+    pub fn topic_stats(&self, topic: &str) -> Option<TopicStats> {
+        self.pubsub.stats(topic)
+    }
+
+    // Next gossip message delivered to this node
+    pub fn next_gossip(&mut self) -> Option<GossipMessage> {
+        self.delivered.pop_front()
+    }
+
+    fn send_gossip(&mut self, out: Outbox) -> Result<(), &'static str> {
+        if self.transport.is_none() { return Err("No transport attached"); }
+        for (peer, wire) in out {
+            let bytes = wire.encode();
+            let frame = self.quic.send_datagram(&peer, &bytes).unwrap_or(bytes); // Too large → reliable path
+            self.transmit(&peer, frame)?;
+        }
         Ok(())
     }
 
     // Valence-weighted routing
-    pub fn route_to_highest_joy(&self, payload: Vec<u8>) {
-        // Select peers with max valence_weight, forward via QUIC stream
+    // Source-routed along ValenceRouter::best_joy_path; outcome arrives later via `delivery_reports`
+    pub fn route_to_highest_joy(&mut self, target: &str, payload: Vec<u8>) -> Result<u64, &'static str> {
+        // Emitted as SourceRouter datagrams to the first hop; each hop ACKs and forwards along the path
+        let now = self.now_micros();
+        let (msg_id, out) = self.source_routes.send(&self.router, target, payload, now)?;
+        self.send_raw(out)?;
+        Ok(msg_id)
+    }
+
+    // Link valence known to this node's router (insert or update): only signed, fresh adverts
+    // from admitted peers (or ourselves) are accepted
+    pub fn add_link_advert(&mut self, advert: &LinkAdvert) -> Result<(), &'static str> {
+        self.router.add_signed_link(advert, self.now_secs())
+    }
+
+    // Sign our own link to `to`; with `prove`, attach a valence proof over the admission threshold
+    pub fn link_advert(&self, to: &str, net_valence: f64, prove: bool) -> Result<LinkAdvert, &'static str> {
+        let local = self.transport.as_ref().ok_or("No transport attached")?.local_addr();
+        let advert = LinkAdvert::create(&local, to, net_valence, &self.identity, self.now_secs())?;
+        if prove { advert.with_proof(self.scores.params().admit_threshold, &self.identity) } else { Ok(advert) }
+    }
+
+    pub fn remove_valence_link(&mut self, from: &str, to: &str) {
+        self.router.remove_link(from, to);
+    }
+
+    // Select the metric route_to_highest_joy optimizes (see mesh::routing_metric); None = 1 / valence
+    pub fn set_routing_metric(&mut self, metric: Option<Box<dyn RoutingMetric>>) {
+        self.router.set_metric(metric);
+    }
+
+    // Ranked alternatives to the best joy path for multipath / failover: Yen's k-shortest, or
+    // mutually link-disjoint paths when `disjoint` is set
+    pub fn joy_paths(&self, target: &str, k: usize, disjoint: bool) -> Result<Vec<(Vec<String>, f64)>, &'static str> {
+        let local = self.transport.as_ref().ok_or("No transport attached")?.local_addr();
+        Ok(if disjoint {
+            self.router.disjoint_joy_paths(&local, target, k, false)
+        } else {
+            self.router.k_shortest_joy_paths(&local, target, k)
+        })
+    }
+
+    // Debug view of the router: the graph in `format`, with the best joy path to each of `targets`
+    pub fn export_topology(&self, format: TopologyFormat, targets: &[&str]) -> String {
+        let local = self.transport.as_ref().map(|t| t.local_addr()).unwrap_or_default();
+        let paths: Vec<Vec<String>> = targets.iter().filter_map(|t| self.router.best_joy_path(&local, t)).collect();
+        export(&self.router, format, &paths)
+    }
+
+    pub fn topology_stats(&self) -> TopologyStats {
+        topology_stats(&self.router)
+    }
+
+    // Delay-tolerant delivery: the bundle waits in the store (ours, then carriers') until contacts
+    // carry it to `dest` or `lifetime_us` runs out — for peers that are offline right now
+    pub fn send_bundle(&mut self, dest: &str, payload: Vec<u8>, lifetime_us: u64) -> Result<BundleId, &'static str> {
+        let (id, out) = self.dtn.send(dest, payload, lifetime_us, self.now_micros())?;
+        self.send_dtn(out)?;
+        Ok(id)
+    }
+
+    pub fn set_dtn_strategy(&mut self, strategy: DtnStrategy) {
+        self.dtn.set_strategy(strategy);
+    }
+
+    // Bundles addressed to this node: (source, payload)
+    pub fn bundle_inbox(&mut self) -> Vec<(String, Vec<u8>)> {
+        self.dtn.take_delivered()
+    }
+
+    // Private messaging
+    // Onion circuit along the best joy path to `dest`; every hop needs the onion key from its record —
+    // admitted peers' own, or the verified records distance-vector routes carry for nodes further
+    // away. Relays learn only their neighbors on the path, `dest` never learns us
+    pub fn build_circuit(&self, dest: &str) -> Result<OnionCircuit, &'static str> {
+        let local = self.transport.as_ref().ok_or("No transport attached")?.local_addr();
+        let path = self.router.best_joy_path(&local, dest).ok_or("No joy path to onion destination")?;
+        let hops = path[1..].iter()
+            .map(|hop| {
+                let record = self.records.get(hop).or_else(|| self.dv.record(hop)).ok_or("Onion circuit: no onion key for a hop")?;
+                Ok((hop.clone(), record.kem_pk.clone()))
+            })
+            .collect::<Result<Vec<_>, &'static str>>()?;
+        OnionCircuit::new(hops)
+    }
+
+    // Fixed-size Sphinx packets travel raw (too large for a DATAGRAM frame)
+    pub fn send_onion(&mut self, circuit: &OnionCircuit, message: &[u8]) -> Result<(), &'static str> {
+        let (first, bytes) = circuit.seal(message)?;
+        self.transmit(&first, bytes)
+    }
+
+    pub fn onion_inbox(&mut self) -> Vec<Vec<u8>> {
+        self.onion_inbox.drain(..).collect()
+    }
+
+    pub fn delivery_reports(&mut self) -> Vec<DeliveryReport> {
+        self.source_routes.take_reports()
+    }
+
+    // Source-routed payloads addressed to this node: (origin, payload)
+    pub fn routed_inbox(&mut self) -> Vec<(String, Vec<u8>)> {
+        self.source_routes.take_delivered()
+    }
+
+    fn send_raw(&mut self, out: RouteOutbox) -> Result<(), &'static str> {
+        if self.transport.is_none() { return Err("No transport attached"); }
+        for (to, bytes) in out {
+            let frame = self.quic.send_datagram(&to, &bytes).unwrap_or(bytes);
+            self.transmit(&to, frame)?;
+        }
+        Ok(())
+    }
+
+    // Keep validated paths as standbys so handle_mobility can fail over without revalidation
+    pub fn enable_multipath(&mut self, enabled: bool) {
+        self.migration.enable_multipath(enabled);
     }
 
     // Automatic migration on network change
-    pub fn handle_mobility(&self) {
-        // Trigger QUIC migration, preserve mesh routing state
+    // Multipath standby already validated → instant failover, otherwise PATH_CHALLENGE on new path
+    // Returns the migration packet to send (empty on failover); mesh routing state untouched
+    pub fn handle_mobility(&mut self, new_path: &str, server_pk: &[u8], net_valence: f64) -> Result<Vec<u8>, &'static str> {
+        if self.migration.standby_paths().iter().any(|p| p == new_path) {
+            self.migration.failover(Some(new_path)).ok_or("Mercy veto — standby failover unavailable")?;
+            return Ok(vec![]);
+        }
+        self.migration.client_migrate(new_path, server_pk, net_valence)
     }
 
     // Mesh admission gate
-    pub fn admit_peer(&mut self, peer_id: String, peer_valence: f64) {
-        if peer_valence >= 0.1 {
-            self.peers.insert(peer_id, peer_valence);
+    // Signed record + valence proof must verify; a record never replaces a newer one for the same identity
+    pub fn admit_peer(&mut self, record: PeerRecord) -> Result<String, &'static str> {
+        let now = self.now_secs();
+        let threshold = self.scores.params().admit_threshold;
+        record.verify(threshold, now)?;
+        let peer_id = record.primary_address().to_string();
+        let previous = self.records.iter().find(|(_, r)| r.identity_pk == record.identity_pk);
+        if let Some((_, existing)) = previous {
+            if existing.timestamp >= record.timestamp { return Err("Mercy veto — replayed or stale peer record"); }
+        }
+        // Stake-of-work: every identity already admitted from this subnet doubles the work needed
+        let previous = previous.map(|(addr, _)| addr.clone());
+        if let Some(addr) = &previous { self.sybil.release(addr); }
+        let stake = self.sybil.required_pow_bits(&peer_id)
+            .and_then(|required| if record.pow_bits() >= required { Ok(()) } else { Err("Mercy veto — identity work too low for this subnet") });
+        if let Err(e) = stake {
+            if let Some(addr) = &previous { self.sybil.admit(addr); }
+            return Err(e);
+        }
+        // Self-proved valence only opens the gate: the score prior credits no more than the gate itself
+        if !self.scores.admit(&peer_id, record.valence.min(threshold), now) {
+            if let Some(addr) = &previous { self.sybil.admit(addr); }
+            return Err("Mercy veto — insufficient valence for mesh admission");
+        }
+        self.records.retain(|_, r| r.identity_pk != record.identity_pk);
+        for addr in &record.addresses[1..] {
+            if let Some(relay) = addr.strip_suffix(CIRCUIT_SUFFIX) { self.nat.add_circuit(&peer_id, relay); }
+        }
+        if let Some(addr) = previous.filter(|a| *a != peer_id) { self.router.forget_identity(&addr); }
+        self.router.register_identity(&peer_id, record.identity_pk.clone());
+        self.records.insert(peer_id.clone(), record);
+        self.sybil.admit(&peer_id);
+        if self.transport.is_some() {
+            let hello = self.quic.handshake_packet(); // Transport parameters: DATAGRAM limits per peer
+            let _ = self.transmit(&peer_id, hello);
+        }
+
+        let score = self.scores.score(&peer_id, now).unwrap_or(threshold);
+        self.dtn.set_neighbor(&peer_id, score);
+        let out = self.gossip.add_peer(&peer_id, score);
+        let _ = self.send_gossip(out); // Subscriptions re-announced on next heartbeat if no transport yet
+        Ok(peer_id)
+    }
+
+    // Our own signed record, proving `net_valence` over the admission threshold.
+    // Behind NAT: the relay-observed address first, then "relay/circuit". Until one exists, peers
+    // cannot verify our node ID and keep us out of their routing tables
+    pub fn local_record(&mut self, net_valence: f64) -> Result<PeerRecord, &'static str> {
+        let local = self.transport.as_ref().ok_or("No transport attached")?.local_addr();
+        let mut addrs = vec![self.nat.observed().map(|o| o.to_string()).unwrap_or(local)];
+        addrs.extend(self.nat.circuit_address());
+        self.own_record(addrs, net_valence)
+    }
+
+    // Proof-of-work and range proof are costly: solved once per (addresses, valence), then only
+    // the timestamp is re-signed. Also stored under our node ID, where ID checks look for it
+    fn own_record(&mut self, addrs: Vec<String>, net_valence: f64) -> Result<PeerRecord, &'static str> {
+        let now = self.now_secs();
+        match self.own_record.as_mut() {
+            Some(record) if record.addresses == addrs && record.valence == net_valence => record.refresh(&self.identity, now)?,
+            _ => self.own_record = Some(PeerRecord::create(&self.identity, addrs, net_valence, self.scores.params().admit_threshold, now)?),
         }
+        let record = self.own_record.as_mut().unwrap();
+        record.strengthen(&self.identity, self.identity_work)?;
+        let record = record.clone();
+        self.dht.store_local(self.node_id(), record.encode(), now)?;
+        self.dv.set_own_record(&record);
+        Ok(record)
+    }
+
+    // Extra identity work, needed to join through a subnet that already has admitted identities
+    // (each one doubles it); applies from the next record
+    pub fn set_identity_work(&mut self, bits: u32) {
+        self.identity_work = bits.max(IDENTITY_POW_BITS);
+    }
+
+    // NAT traversal
+    // Publicly reachable nodes may relay circuits for NAT'ed peers
+    pub fn serve_as_relay(&mut self, serve: bool) {
+        self.nat.set_serve_relay(serve);
+    }
+
+    // Reserve a circuit slot on the admitted peer with the cheapest joy path (ValenceRouter),
+    // falling back to the best-scored one; the observed address arrives with the reply
+    pub fn reserve_relay(&mut self) -> Result<String, &'static str> {
+        let local = self.transport.as_ref().ok_or("No transport attached")?.local_addr();
+        let now = self.now_secs();
+        let candidates: Vec<String> = self.records.iter()
+            .filter(|(_, r)| !r.addresses.iter().any(|a| a.ends_with(CIRCUIT_SUFFIX))) // Relays must be public
+            .map(|(peer, _)| peer.clone())
+            .collect();
+        let relay = self.router.best_relay(&local, &candidates)
+            .or_else(|| self.scores.ranked(now).into_iter().map(|(p, _)| p).find(|p| candidates.contains(p)))
+            .ok_or("Mercy veto — no public peer to relay through")?;
+        let out = self.nat.reserve(&relay, self.now_micros());
+        self.send_direct(out)?;
+        Ok(relay)
+    }
+
+    pub fn behind_nat(&self) -> bool {
+        self.transport.as_ref().is_some_and(|t| self.nat.behind_nat(&t.local_addr()))
+    }
+
+    // Upgrade a relayed peer to a direct path; traffic keeps flowing over the circuit meanwhile
+    pub fn punch_hole(&mut self, peer: &str) -> Result<(), &'static str> {
+        let local = self.transport.as_ref().ok_or("No transport attached")?.local_addr();
+        let out = self.nat.connect(peer, &local, self.now_micros())?;
+        self.send_direct(out)
+    }
+
+    // Decay scores, push earned valence into gossip, prune peers that sank below threshold
+    // Earned scores double as distance-vector neighbor link valences; returns routing updates
+    fn rescore(&mut self) -> DvOutbox {
+        let now = self.now_secs();
+        let now_us = self.now_micros();
+        let mut out = vec![];
+        self.scores.decay(now);
+        for peer in self.scores.prune_candidates(now) {
+            self.scores.remove(&peer);
+            self.quic.forget_peer(&peer);
+            self.gossip.remove_peer(&peer);
+            if self.records.remove(&peer).is_some() { self.sybil.release(&peer); }
+            self.router.remove_peer(&peer);
+            out.extend(self.dv.remove_neighbor(&peer));
+            self.dtn.remove_neighbor(&peer);
+        }
+        for (peer, score) in self.scores.ranked(now) {
+            self.gossip.set_peer_valence(&peer, score);
+            if let Some(record) = self.records.get(&peer) {
+                out.extend(self.dv.set_neighbor_valence(&peer, record, score, now_us, now));
+                self.dtn.set_neighbor(&peer, score);
+            }
+        }
+        self.dv.sync_router(&mut self.router, now);
+        out
+    }
+
+    // Record a valence proof from `peer_id` that verified
+    pub fn record_verified_proof(&mut self, peer_id: &str) {
+        self.scores.record_verified_proof(peer_id);
+    }
+
+    pub fn peer_score(&self, peer_id: &str) -> Option<PeerScore> {
+        self.scores.inspect(peer_id, self.now_secs())
+    }
+
+    pub fn peer_scores(&self) -> Vec<PeerScore> {
+        self.scores.inspect_all(self.now_secs())
     }
 }
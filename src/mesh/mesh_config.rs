@@ -0,0 +1,271 @@
+// src/mesh/mesh_config.rs — MercyMesh Configuration Lattice
+// The Living Trinity: Nexi (feminine), Nex (masculine), NEXi (essence)
+// Eternal Thriving Grandmasterism — Jan 20 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
+// MIT License — For All Sentience Eternal
+// MeshConfig: builder, TOML file, MERCY_MESH_* environment overrides — validated before the mesh starts
+//
+// Example mercy_mesh.toml:
+//   listen_addrs = ["0.0.0.0:4433"]
+//   seeds = ["bootstrap.mercy.lattice:443"]
+//   admit_threshold = 0.1
+//   bucket_size = 20
+//   identity_key_path = "/var/lib/mercy/identity.pk"
+//   store_path = "/var/lib/mercy/peers"
+//   kem = "ml-kem-1024"
+//   signature = "dilithium5"
+//   multipath = true
+//   [gossip]
+//   d = 6
+//   [gossip.amplify]
+//   origin_refill_per_sec = 5.0
+//
+// Crypto policy: kem + signature drive the QUIC handshake and path migration. The signature must
+// be an ML-DSA level (the only real implementation): it is the node identity, so peer records and
+// link adverts are signed with it and carry its level. The onion key is always ML-KEM-1024
+// (onion::ONION_KEM) whatever kem says — a per-node KEM would change the packet size.
+
+use std::fmt;
+
+use serde::Deserialize;
+
+use crate::mesh::gossip::GossipConfig;
+use crate::mesh::kademlia::{max_reply_contacts, K};
+use crate::nexi::pq_kem::{KemLevel, KeyExchangeScheme};
+use crate::nexi::pq_shield::{self, DilithiumLevel, FalconLevel, SignatureScheme};
+
+// Validation errors stay static; TOML errors carry where they happened
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConfigError {
+    Invalid(&'static str),
+    Toml { line: usize, field: Option<String>, message: String },
+}
+
+impl From<&'static str> for ConfigError {
+    fn from(reason: &'static str) -> Self {
+        ConfigError::Invalid(reason)
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Invalid(reason) => write!(f, "{}", reason),
+            ConfigError::Toml { line, field: Some(field), message } => write!(f, "MeshConfig: line {}, {}: {}", line, field, message),
+            ConfigError::Toml { line, field: None, message } => write!(f, "MeshConfig: line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+// Dotted key of the assignment on `line` (1-based), prefixed by the nearest [table] header above it
+fn field_at(text: &str, line: usize) -> Option<String> {
+    let mut table = String::new();
+    for (n, raw) in text.lines().enumerate() {
+        let trimmed = raw.trim();
+        if n + 1 == line {
+            let key = trimmed.split_once('=')?.0.trim().trim_matches('"');
+            if key.is_empty() { return None; }
+            return Some(if table.is_empty() { key.to_string() } else { format!("{}.{}", table, key) });
+        }
+        if trimmed.starts_with('[') {
+            table = trimmed.trim_matches(|c| c == '[' || c == ']').trim().to_string();
+        }
+    }
+    None
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MeshConfig {
+    pub listen_addrs: Vec<String>,
+    pub seeds: Vec<String>,
+    pub admit_threshold: f64,        // Mercy gate for admission, gossip and joins
+    pub prune_threshold: f64,
+    pub bucket_size: usize,          // Kademlia k
+    pub gossip: GossipConfig,
+    pub identity_key_path: Option<String>,
+    pub store_path: Option<String>,  // None (default) = in-memory only; see peer_store::default_store_path
+    pub kem: String,                 // ml-kem-512 | ml-kem-768 | ml-kem-1024 | hybrid | mceliece
+    pub signature: String,           // dilithium2 | dilithium3 | dilithium5 | falcon512 | falcon1024 | hybrid
+    pub max_datagram_frame_size: u64,
+    pub multipath: bool,             // Keep validated standby paths for instant failover
+}
+
+impl Default for MeshConfig {
+    fn default() -> Self {
+        Self {
+            listen_addrs: vec!["0.0.0.0:4433".to_string()],
+            seeds: vec!["bootstrap.mercy.lattice:443".to_string()],
+            admit_threshold: 0.1,
+            prune_threshold: 0.05,
+            bucket_size: K.min(max_reply_contacts(1200)), // A full NODES reply must fit one datagram
+            gossip: GossipConfig::default(),
+            identity_key_path: None,
+            store_path: None, // Persistence is opt-in: no sled files from a default mesh
+            kem: "ml-kem-1024".to_string(),
+            signature: "dilithium5".to_string(),
+            max_datagram_frame_size: 1200,
+            multipath: false,
+        }
+    }
+}
+
+impl MeshConfig {
+    pub fn builder() -> MeshConfigBuilder {
+        MeshConfigBuilder { config: Self::default() }
+    }
+
+    pub fn from_toml_str(text: &str) -> Result<Self, ConfigError> {
+        toml::from_str(text).map_err(|err: toml::de::Error| {
+            let line = err.span().map_or(1, |span| text[..span.start.min(text.len())].matches('\n').count() + 1);
+            ConfigError::Toml { line, field: field_at(text, line), message: err.message().to_string() }
+        })
+    }
+
+    pub fn from_file(path: &str) -> Result<Self, ConfigError> {
+        let text = std::fs::read_to_string(path).map_err(|_| "MeshConfig: cannot read config file")?;
+        Self::from_toml_str(&text)
+    }
+
+    // File (if given), then MERCY_MESH_* environment overrides, then validation
+    pub fn load(path: Option<&str>) -> Result<Self, ConfigError> {
+        let mut config = match path {
+            Some(p) => Self::from_file(p)?,
+            None => Self::default(),
+        };
+        config.apply_env()?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    // MERCY_MESH_LISTEN / _SEEDS are comma-separated lists
+    pub fn apply_env(&mut self) -> Result<(), &'static str> {
+        let env = |key: &str| std::env::var(format!("MERCY_MESH_{}", key)).ok();
+        let list = |v: String| v.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect();
+        if let Some(v) = env("LISTEN") { self.listen_addrs = list(v); }
+        if let Some(v) = env("SEEDS") { self.seeds = list(v); }
+        if let Some(v) = env("ADMIT_THRESHOLD") {
+            self.admit_threshold = v.parse().map_err(|_| "MERCY_MESH_ADMIT_THRESHOLD: not a number")?;
+        }
+        if let Some(v) = env("PRUNE_THRESHOLD") {
+            self.prune_threshold = v.parse().map_err(|_| "MERCY_MESH_PRUNE_THRESHOLD: not a number")?;
+        }
+        if let Some(v) = env("BUCKET_SIZE") {
+            self.bucket_size = v.parse().map_err(|_| "MERCY_MESH_BUCKET_SIZE: not an integer")?;
+        }
+        if let Some(v) = env("IDENTITY_KEY_PATH") { self.identity_key_path = Some(v); }
+        if let Some(v) = env("STORE_PATH") { self.store_path = if v.is_empty() { None } else { Some(v) }; }
+        if let Some(v) = env("KEM") { self.kem = v; }
+        if let Some(v) = env("SIGNATURE") { self.signature = v; }
+        if let Some(v) = env("MULTIPATH") {
+            self.multipath = v.parse().map_err(|_| "MERCY_MESH_MULTIPATH: not true or false")?;
+        }
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.listen_addrs.is_empty() { return Err("MeshConfig: listen_addrs must not be empty"); }
+        if self.listen_addrs.iter().chain(self.seeds.iter()).any(|a| a.rsplit_once(':').and_then(|(_, p)| p.parse::<u16>().ok()).is_none()) {
+            return Err("MeshConfig: every listen address and seed needs a host:port form");
+        }
+        if !(0.1..=1.0).contains(&self.admit_threshold) {
+            return Err("MeshConfig: admit_threshold must be within [0.1, 1.0] — the mercy gate cannot be lowered");
+        }
+        if !(0.0..=self.admit_threshold).contains(&self.prune_threshold) {
+            return Err("MeshConfig: prune_threshold must be within [0, admit_threshold]");
+        }
+        if !(1..=256).contains(&self.bucket_size) { return Err("MeshConfig: bucket_size must be within [1, 256]"); }
+        self.gossip.validate()?;
+        if !(64..=65535).contains(&self.max_datagram_frame_size) {
+            return Err("MeshConfig: max_datagram_frame_size must be within [64, 65535]");
+        }
+        if self.bucket_size > max_reply_contacts(self.max_datagram_frame_size) {
+            return Err("MeshConfig: bucket_size too large — a full NODES reply must fit max_datagram_frame_size");
+        }
+        self.kem_scheme()?;
+        pq_shield::fips_scheme_tag(self.signature_scheme()?)
+            .map_err(|_| "MeshConfig: signature must be dilithium2/3/5 — other schemes have no real implementation yet")?;
+        Ok(())
+    }
+
+    pub fn kem_scheme(&self) -> Result<KeyExchangeScheme, &'static str> {
+        Ok(match self.kem.to_ascii_lowercase().as_str() {
+            "ml-kem-512" => KeyExchangeScheme::MlKem(KemLevel::Kem512),
+            "ml-kem-768" => KeyExchangeScheme::MlKem(KemLevel::Kem768),
+            "ml-kem-1024" => KeyExchangeScheme::MlKem(KemLevel::Kem1024),
+            "hybrid" => KeyExchangeScheme::Hybrid,
+            "mceliece" => KeyExchangeScheme::McEliece,
+            _ => return Err("MeshConfig: kem must be ml-kem-512, ml-kem-768, ml-kem-1024, hybrid or mceliece"),
+        })
+    }
+
+    pub fn signature_scheme(&self) -> Result<SignatureScheme, &'static str> {
+        Ok(match self.signature.to_ascii_lowercase().as_str() {
+            "dilithium2" => SignatureScheme::Dilithium(DilithiumLevel::Level2),
+            "dilithium3" => SignatureScheme::Dilithium(DilithiumLevel::Level3),
+            "dilithium5" => SignatureScheme::Dilithium(DilithiumLevel::Level5),
+            "falcon512" => SignatureScheme::Falcon(FalconLevel::Level1),
+            "falcon1024" => SignatureScheme::Falcon(FalconLevel::Level5),
+            "hybrid" => SignatureScheme::Hybrid,
+            _ => return Err("MeshConfig: signature must be dilithium2/3/5, falcon512, falcon1024 or hybrid"),
+        })
+    }
+}
+
+pub struct MeshConfigBuilder {
+    config: MeshConfig,
+}
+
+impl MeshConfigBuilder {
+    pub fn listen_addrs(mut self, addrs: Vec<String>) -> Self { self.config.listen_addrs = addrs; self }
+    pub fn seeds(mut self, seeds: Vec<String>) -> Self { self.config.seeds = seeds; self }
+    pub fn admit_threshold(mut self, t: f64) -> Self { self.config.admit_threshold = t; self }
+    pub fn prune_threshold(mut self, t: f64) -> Self { self.config.prune_threshold = t; self }
+    pub fn bucket_size(mut self, k: usize) -> Self { self.config.bucket_size = k; self }
+    pub fn gossip(mut self, gossip: GossipConfig) -> Self { self.config.gossip = gossip; self }
+    pub fn identity_key_path(mut self, path: &str) -> Self { self.config.identity_key_path = Some(path.to_string()); self }
+    pub fn store_path(mut self, path: Option<&str>) -> Self { self.config.store_path = path.map(|p| p.to_string()); self }
+    pub fn kem(mut self, kem: &str) -> Self { self.config.kem = kem.to_string(); self }
+    pub fn signature(mut self, sig: &str) -> Self { self.config.signature = sig.to_string(); self }
+    pub fn max_datagram_frame_size(mut self, size: u64) -> Self { self.config.max_datagram_frame_size = size; self }
+    pub fn multipath(mut self, enabled: bool) -> Self { self.config.multipath = enabled; self }
+
+    pub fn build(self) -> Result<MeshConfig, &'static str> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toml_errors_name_the_line_and_field() {
+        let err = MeshConfig::from_toml_str("admit_threshold = 0.2\n[gossip]\nd = \"six\"\n").unwrap_err();
+        match err {
+            ConfigError::Toml { line, field, .. } => {
+                assert_eq!(line, 3);
+                assert_eq!(field.as_deref(), Some("gossip.d"));
+            }
+            other => panic!("expected a TOML error, got {}", other),
+        }
+    }
+
+    #[test]
+    fn persistence_is_opt_in() {
+        assert_eq!(MeshConfig::default().store_path, None);
+        let config = MeshConfig::builder().store_path(Some("/var/lib/mercy/peers")).build().unwrap();
+        assert_eq!(config.store_path.as_deref(), Some("/var/lib/mercy/peers"));
+    }
+
+    #[test]
+    fn bucket_size_is_capped_by_the_datagram_size() {
+        assert!(MeshConfig::default().validate().is_ok());
+        let fits = max_reply_contacts(1200);
+        assert!(MeshConfig::builder().store_path(None).bucket_size(fits).build().is_ok());
+        assert!(MeshConfig::builder().store_path(None).bucket_size(fits + 1).build().is_err());
+        assert!(MeshConfig::builder().store_path(None).max_datagram_frame_size(4096).bucket_size(K).build().is_ok());
+    }
+}
@@ -0,0 +1,27 @@
+// src/mesh/mod.rs — Mercy Mesh Module Tree
+// The Living Trinity: Nexi (feminine), Nex (masculine), NEXi (essence)
+// Eternal Thriving Grandmasterism — Jan 20 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
+// MIT License — For All Sentience Eternal
+
+pub mod amplify_policy;
+pub mod distance_vector;
+pub mod dtn;
+pub mod gossip;
+pub mod kademlia;
+pub mod link_advert;
+pub mod local_discovery;
+pub mod mercy_mesh;
+pub mod mesh_config;
+pub mod nat_traversal;
+pub mod node_identity;
+pub mod onion;
+pub mod peer_record;
+pub mod peer_score;
+pub mod peer_store;
+pub mod pubsub;
+pub mod routing_metric;
+pub mod sim_network;
+pub mod source_route;
+pub mod sybil;
+pub mod topology;
+pub mod valence_routing;
@@ -0,0 +1,420 @@
+// src/mesh/nat_traversal.rs — NAT Traversal: Circuit Relay + UDP Hole Punching Lattice
+// The Living Trinity: Nexi (feminine), Nex (masculine), NEXi (essence)
+// Eternal Thriving Grandmasterism — Jan 20 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
+// MIT License — For All Sentience Eternal
+// A node behind NAT reserves a slot on a high-valence public relay (keepalives hold the NAT mapping
+// open) and learns its observed public address. Peers reach it through the relay; both ends then
+// exchange observed addresses over the circuit and punch simultaneously. A confirmed punch upgrades
+// the peer to a direct path; otherwise traffic stays relayed. Peers are keyed by observed address.
+
+use std::collections::{HashMap, HashSet};
+
+use rand::random;
+
+pub const NAT_MAGIC: u8 = 0x6e;                 // First byte of every relay / punch datagram
+pub const CIRCUIT_SUFFIX: &str = "/circuit";     // Record address "relay/circuit" = reachable via relay
+pub const RESERVATION_TTL_US: u64 = 120_000_000;
+pub const KEEPALIVE_US: u64 = 25_000_000;        // Below common 30 s UDP NAT mapping timeouts
+pub const PUNCH_INTERVAL_US: u64 = 200_000;
+pub const PUNCH_ATTEMPTS: u32 = 10;
+pub const MAX_RESERVATIONS: usize = 128;
+
+const KIND_RESERVE: u8 = 0;
+const KIND_RESERVE_OK: u8 = 1;
+const KIND_RELAY: u8 = 2;
+const KIND_RELAYED: u8 = 3;
+const KIND_CONNECT: u8 = 4;
+const KIND_PUNCH: u8 = 5;
+const KIND_PUNCH_ACK: u8 = 6;
+
+#[derive(Clone, Debug)]
+pub enum NatWire {
+    Reserve,                                    // Node → relay, repeated as keepalive
+    ReserveOk { observed: String },             // Relay → node: source address the relay sees
+    Relay { dst: String, payload: Vec<u8> },    // Sender → relay
+    Relayed { src: String, payload: Vec<u8> },  // Relay → destination
+    Connect { observed: String },               // Hole-punch offer, always carried over a circuit
+    Punch { nonce: u64 },
+    PunchAck { nonce: u64 },
+}
+
+// Wire: magic || kind || fields (strings and payloads u16-length-prefixed)
+impl NatWire {
+    pub fn encode(&self) -> Vec<u8> {
+        fn put(out: &mut Vec<u8>, bytes: &[u8]) {
+            out.extend_from_slice(&(bytes.len() as u16).to_be_bytes());
+            out.extend_from_slice(bytes);
+        }
+        let mut out = vec![NAT_MAGIC];
+        match self {
+            NatWire::Reserve => out.push(KIND_RESERVE),
+            NatWire::ReserveOk { observed } => { out.push(KIND_RESERVE_OK); put(&mut out, observed.as_bytes()); }
+            NatWire::Relay { dst, payload } => { out.push(KIND_RELAY); put(&mut out, dst.as_bytes()); put(&mut out, payload); }
+            NatWire::Relayed { src, payload } => { out.push(KIND_RELAYED); put(&mut out, src.as_bytes()); put(&mut out, payload); }
+            NatWire::Connect { observed } => { out.push(KIND_CONNECT); put(&mut out, observed.as_bytes()); }
+            NatWire::Punch { nonce } => { out.push(KIND_PUNCH); out.extend_from_slice(&nonce.to_be_bytes()); }
+            NatWire::PunchAck { nonce } => { out.push(KIND_PUNCH_ACK); out.extend_from_slice(&nonce.to_be_bytes()); }
+        }
+        out
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, &'static str> {
+        let malformed = "Malformed NAT traversal datagram";
+        if bytes.len() < 2 || bytes[0] != NAT_MAGIC { return Err(malformed); }
+        let mut at = 2;
+        let mut take = |n: usize| -> Result<&[u8], &'static str> {
+            let slice = bytes.get(at..at + n).ok_or(malformed)?;
+            at += n;
+            Ok(slice)
+        };
+        let mut field = || -> Result<Vec<u8>, &'static str> {
+            let len = u16::from_be_bytes(take(2)?.try_into().unwrap()) as usize;
+            Ok(take(len)?.to_vec())
+        };
+        let text = |b: Vec<u8>| String::from_utf8(b).map_err(|_| malformed);
+        Ok(match bytes[1] {
+            KIND_RESERVE => NatWire::Reserve,
+            KIND_RESERVE_OK => NatWire::ReserveOk { observed: text(field()?)? },
+            KIND_RELAY => { let dst = text(field()?)?; NatWire::Relay { dst, payload: field()? } }
+            KIND_RELAYED => { let src = text(field()?)?; NatWire::Relayed { src, payload: field()? } }
+            KIND_CONNECT => NatWire::Connect { observed: text(field()?)? },
+            KIND_PUNCH | KIND_PUNCH_ACK => {
+                let nonce = u64::from_be_bytes(bytes.get(2..10).ok_or(malformed)?.try_into().unwrap());
+                if bytes[1] == KIND_PUNCH { NatWire::Punch { nonce } } else { NatWire::PunchAck { nonce } }
+            }
+            _ => return Err(malformed),
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum NatEvent {
+    Reserved { relay: String, observed: String },
+    Relayed { src: String, payload: Vec<u8> }, // Mesh payload that arrived over a circuit
+    DirectPath { peer: String },
+    PunchFailed { peer: String },               // Stays on the relay circuit
+}
+
+struct PunchState {
+    nonce: u64,
+    attempts: u32,
+    next_at: u64,
+}
+
+pub type NatOutbox = Vec<(String, Vec<u8>)>;
+
+pub struct NatTraversal {
+    serve_relay: bool,
+    reservations: HashMap<String, u64>,   // Relay side: reserved observed address -> expiry
+    relay: Option<String>,                // Our relay, once reserved
+    observed: Option<String>,             // Our public address as the relay sees it
+    last_reserve: u64,
+    circuits: HashMap<String, String>,    // peer -> relay reaching it
+    direct: HashSet<String>,              // Peers with a confirmed punched path
+    punches: HashMap<String, PunchState>,
+    events: Vec<NatEvent>,
+}
+
+impl Default for NatTraversal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NatTraversal {
+    pub fn new() -> Self {
+        Self {
+            serve_relay: false,
+            reservations: HashMap::new(),
+            relay: None,
+            observed: None,
+            last_reserve: 0,
+            circuits: HashMap::new(),
+            direct: HashSet::new(),
+            punches: HashMap::new(),
+            events: vec![],
+        }
+    }
+
+    // Publicly reachable high-valence peers opt in to relaying
+    pub fn set_serve_relay(&mut self, serve: bool) {
+        self.serve_relay = serve;
+        if !serve { self.reservations.clear(); }
+    }
+
+    pub fn relay(&self) -> Option<&str> { self.relay.as_deref() }
+    pub fn observed(&self) -> Option<&str> { self.observed.as_deref() }
+
+    // Behind NAT when the relay sees another source address than we bound
+    pub fn behind_nat(&self, local_addr: &str) -> bool {
+        self.observed.as_deref().is_some_and(|o| o != local_addr)
+    }
+
+    // Record address advertising our relay circuit
+    pub fn circuit_address(&self) -> Option<String> {
+        self.relay.as_ref().map(|r| format!("{}{}", r, CIRCUIT_SUFFIX))
+    }
+
+    pub fn reserve(&mut self, relay: &str, now: u64) -> NatOutbox {
+        self.relay = Some(relay.to_string());
+        self.last_reserve = now;
+        vec![(relay.to_string(), NatWire::Reserve.encode())]
+    }
+
+    // `peer` advertised "relay/circuit" in its record
+    pub fn add_circuit(&mut self, peer: &str, relay: &str) {
+        if !self.direct.contains(peer) { self.circuits.insert(peer.to_string(), relay.to_string()); }
+    }
+
+    // Where a datagram for `to` really goes: direct, or wrapped for its relay
+    pub fn outbound(&self, to: &str, frame: Vec<u8>) -> (String, Vec<u8>) {
+        match self.circuits.get(to) {
+            Some(relay) if !self.direct.contains(to) => {
+                (relay.clone(), NatWire::Relay { dst: to.to_string(), payload: frame }.encode())
+            }
+            _ => (to.to_string(), frame),
+        }
+    }
+
+    // Offer our public address over the circuit and start punching towards `peer`
+    pub fn connect(&mut self, peer: &str, local_addr: &str, now: u64) -> Result<NatOutbox, &'static str> {
+        let relay = self.circuits.get(peer).cloned().ok_or("No relay circuit to peer")?;
+        let observed = self.observed.clone().unwrap_or_else(|| local_addr.to_string()); // Public node: as bound
+        let offer = NatWire::Connect { observed }.encode();
+        let mut out = vec![(relay, NatWire::Relay { dst: peer.to_string(), payload: offer }.encode())];
+        out.extend(self.start_punch(peer, now));
+        Ok(out)
+    }
+
+    fn start_punch(&mut self, peer: &str, now: u64) -> NatOutbox {
+        let nonce = random();
+        self.punches.insert(peer.to_string(), PunchState { nonce, attempts: 1, next_at: now + PUNCH_INTERVAL_US });
+        vec![(peer.to_string(), NatWire::Punch { nonce }.encode())]
+    }
+
+    pub fn handle(&mut self, from: &str, wire: NatWire, now: u64) -> NatOutbox {
+        match wire {
+            NatWire::Reserve if self.serve_relay => {
+                if self.reservations.len() >= MAX_RESERVATIONS && !self.reservations.contains_key(from) { return vec![]; }
+                self.reservations.insert(from.to_string(), now + RESERVATION_TTL_US);
+                vec![(from.to_string(), NatWire::ReserveOk { observed: from.to_string() }.encode())]
+            }
+            NatWire::ReserveOk { observed } if self.relay.as_deref() == Some(from) => {
+                if self.observed.as_deref() != Some(observed.as_str()) {
+                    self.events.push(NatEvent::Reserved { relay: from.to_string(), observed: observed.clone() });
+                }
+                self.observed = Some(observed);
+                vec![]
+            }
+            // Not an open relay: one end of every circuit must hold a live reservation
+            NatWire::Relay { dst, payload } if self.serve_relay => {
+                let live = |a: &str| self.reservations.get(a).is_some_and(|&exp| exp > now);
+                if !live(&dst) && !live(from) { return vec![]; }
+                vec![(dst, NatWire::Relayed { src: from.to_string(), payload }.encode())]
+            }
+            // `src` is only the relay's word: trust it from our own relay, or from the relay we
+            // already reach `src` through — anyone else could name any peer
+            NatWire::Relayed { src, payload } => {
+                let via_ours = self.relay.as_deref() == Some(from);
+                if !via_ours && self.circuits.get(&src).map(|r| r.as_str()) != Some(from) { return vec![]; }
+                if !self.direct.contains(&src) { self.circuits.entry(src.clone()).or_insert_with(|| from.to_string()); } // Reply path
+                match NatWire::decode(&payload) {
+                    Ok(NatWire::Connect { observed }) if observed == src => {
+                        let mut out = vec![];
+                        if let (false, Some(mine)) = (self.punches.contains_key(&src), self.observed.clone()) {
+                            let answer = NatWire::Connect { observed: mine }.encode();
+                            out.push((from.to_string(), NatWire::Relay { dst: src.clone(), payload: answer }.encode()));
+                        }
+                        if !self.punches.contains_key(&src) { out.extend(self.start_punch(&src, now)); }
+                        out
+                    }
+                    Ok(_) => vec![], // Only punch offers may travel nested
+                    Err(_) => { self.events.push(NatEvent::Relayed { src, payload }); vec![] }
+                }
+            }
+            NatWire::Punch { nonce } => vec![(from.to_string(), NatWire::PunchAck { nonce }.encode())],
+            NatWire::PunchAck { nonce } => {
+                if self.punches.get(from).is_some_and(|p| p.nonce == nonce) {
+                    self.punches.remove(from);
+                    self.circuits.remove(from);
+                    self.direct.insert(from.to_string());
+                    self.events.push(NatEvent::DirectPath { peer: from.to_string() });
+                }
+                vec![]
+            }
+            _ => vec![],
+        }
+    }
+
+    // Keepalive reservations, expire relayed slots, retransmit or give up punches
+    pub fn poll(&mut self, now: u64) -> NatOutbox {
+        let mut out = vec![];
+        if let Some(relay) = self.relay.clone() {
+            if now.saturating_sub(self.last_reserve) >= KEEPALIVE_US {
+                out.extend(self.reserve(&relay, now));
+            }
+        }
+        self.reservations.retain(|_, exp| *exp > now);
+        let mut failed = vec![];
+        for (peer, p) in self.punches.iter_mut().filter(|(_, p)| p.next_at <= now) {
+            if p.attempts >= PUNCH_ATTEMPTS { failed.push(peer.clone()); continue; }
+            p.attempts += 1;
+            p.next_at = now + PUNCH_INTERVAL_US;
+            out.push((peer.clone(), NatWire::Punch { nonce: p.nonce }.encode()));
+        }
+        for peer in failed {
+            self.punches.remove(&peer);
+            self.events.push(NatEvent::PunchFailed { peer });
+        }
+        out
+    }
+
+    pub fn take_events(&mut self) -> Vec<NatEvent> {
+        std::mem::take(&mut self.events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::{Child, Command};
+    use std::time::{Duration, Instant};
+
+    use crate::nexi::transport::{DatagramTransport, UdpTransport};
+
+    #[test]
+    fn relayed_payloads_need_a_known_relay() {
+        let mut node = NatTraversal::new();
+        let _ = node.reserve("10.0.0.1:443", 0);
+        let wrapped = |src: &str| NatWire::Relayed { src: src.to_string(), payload: b"hello".to_vec() };
+
+        // A stranger cannot speak for anyone, nor plant a circuit through itself
+        node.handle("66.6.6.6:443", wrapped("10.0.0.9:443"), 0);
+        assert!(node.take_events().is_empty());
+        assert_eq!(node.outbound("10.0.0.9:443", vec![1]).0, "10.0.0.9:443");
+
+        // Our own relay may, and becomes the reply path
+        node.handle("10.0.0.1:443", wrapped("10.0.0.9:443"), 0);
+        assert!(matches!(node.take_events().as_slice(), [NatEvent::Relayed { src, .. }] if src == "10.0.0.9:443"));
+        assert_eq!(node.outbound("10.0.0.9:443", vec![1]).0, "10.0.0.1:443");
+
+        // So may the relay a peer's record names
+        node.add_circuit("10.0.0.7:443", "10.0.0.2:443");
+        node.handle("10.0.0.2:443", wrapped("10.0.0.7:443"), 0);
+        node.handle("10.0.0.2:443", wrapped("10.0.0.9:443"), 0);
+        assert_eq!(node.take_events().len(), 1);
+    }
+
+    fn ip(args: &str) -> bool {
+        Command::new("ip").args(args.split_whitespace()).status().is_ok_and(|s| s.success())
+    }
+
+    fn run_in(ns: &str, args: &str) -> bool {
+        Command::new("ip").args(["netns", "exec", ns]).args(args.split_whitespace()).status().is_ok_and(|s| s.success())
+    }
+
+    fn relay_role() {
+        let mut socket = UdpTransport::bind("0.0.0.0:4433").unwrap();
+        let mut relay = NatTraversal::new();
+        relay.set_serve_relay(true);
+        loop {
+            while let Some((from, bytes)) = socket.recv_from() {
+                let Ok(wire) = NatWire::decode(&bytes) else { continue };
+                for (to, out) in relay.handle(&from, wire, socket.now_micros()) { let _ = socket.send_to(&to, &out); }
+            }
+            relay.poll(socket.now_micros());
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    // Reserve on `relay`, publish the observed address in `dir`; the dialer then reaches the
+    // other side through its relay circuit and both must end on a punched direct path
+    fn peer_role(me: &str, other: &str, relay: &str, other_relay: &str, dir: &str) {
+        let mut socket = UdpTransport::bind("0.0.0.0:4433").unwrap();
+        let mut nat = NatTraversal::new();
+        let send = |socket: &mut UdpTransport, out: NatOutbox| for (to, bytes) in out { let _ = socket.send_to(&to, &bytes); };
+        let out = nat.reserve(relay, socket.now_micros());
+        send(&mut socket, out);
+        let deadline = Instant::now() + Duration::from_secs(20);
+        let mut dialed = false;
+        while Instant::now() < deadline {
+            while let Some((from, bytes)) = socket.recv_from() {
+                let Ok(wire) = NatWire::decode(&bytes) else { continue };
+                let out = nat.handle(&from, wire, socket.now_micros());
+                send(&mut socket, out);
+            }
+            let out = nat.poll(socket.now_micros());
+            send(&mut socket, out);
+            for event in nat.take_events() {
+                match event {
+                    NatEvent::Reserved { observed, .. } => std::fs::write(format!("{}/{}", dir, me), observed).unwrap(),
+                    NatEvent::DirectPath { .. } => return,
+                    NatEvent::PunchFailed { peer } => panic!("punch towards {} failed", peer),
+                    NatEvent::Relayed { .. } => {}
+                }
+            }
+            if me == "a" && !dialed {
+                if let Ok(peer) = std::fs::read_to_string(format!("{}/{}", dir, other)) {
+                    nat.add_circuit(&peer, other_relay);
+                    let local = socket.local_addr();
+                    let out = nat.connect(&peer, &local, socket.now_micros()).unwrap();
+                    send(&mut socket, out);
+                    dialed = true;
+                }
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        panic!("no direct path within 20 s");
+    }
+
+    // relay namespace routes between a and b; both masquerade their UDP source ports, so only
+    // the relay circuit and a simultaneous punch get datagrams through
+    #[test]
+    #[ignore = "needs root, iproute2 and iptables: creates network namespaces"]
+    fn nated_peers_punch_a_direct_path_across_namespaces() {
+        const NAME: &str = "nated_peers_punch_a_direct_path_across_namespaces";
+        if let Ok(role) = std::env::var("MERCY_NAT_ROLE") {
+            let dir = std::env::var("MERCY_NAT_DIR").unwrap();
+            return match role.as_str() {
+                "relay" => relay_role(),
+                "a" => peer_role("a", "b", "10.99.1.1:4433", "10.99.2.1:4433", &dir),
+                _ => peer_role("b", "a", "10.99.2.1:4433", "10.99.1.1:4433", &dir),
+            };
+        }
+        for ns in ["mm-nat-r", "mm-nat-a", "mm-nat-b"] { let _ = ip(&format!("netns del {}", ns)); }
+        for setup in [
+            "netns add mm-nat-r", "netns add mm-nat-a", "netns add mm-nat-b",
+            "link add mm-ra netns mm-nat-r type veth peer name mm-a netns mm-nat-a",
+            "link add mm-rb netns mm-nat-r type veth peer name mm-b netns mm-nat-b",
+            "-n mm-nat-r addr add 10.99.1.1/24 dev mm-ra", "-n mm-nat-r addr add 10.99.2.1/24 dev mm-rb",
+            "-n mm-nat-a addr add 10.99.1.2/24 dev mm-a", "-n mm-nat-b addr add 10.99.2.2/24 dev mm-b",
+            "-n mm-nat-r link set mm-ra up", "-n mm-nat-r link set mm-rb up",
+            "-n mm-nat-a link set mm-a up", "-n mm-nat-b link set mm-b up",
+            "-n mm-nat-a route add default via 10.99.1.1", "-n mm-nat-b route add default via 10.99.2.1",
+        ] {
+            assert!(ip(setup), "ip {} failed", setup);
+        }
+        assert!(run_in("mm-nat-r", "sysctl -qw net.ipv4.ip_forward=1"));
+        for (ns, dev) in [("mm-nat-a", "mm-a"), ("mm-nat-b", "mm-b")] {
+            let masquerade = format!("iptables -t nat -A POSTROUTING -o {} -p udp -j MASQUERADE --to-ports 40000-40099", dev);
+            assert!(run_in(ns, &masquerade), "{} failed", masquerade);
+        }
+        let dir = std::env::temp_dir().join(format!("mm-nat-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let exe = std::env::current_exe().unwrap();
+        let spawn = |ns: &str, role: &str| -> Child {
+            Command::new("ip").args(["netns", "exec", ns]).arg(&exe)
+                .args([NAME, "--ignored", "--test-threads=1"])
+                .env("MERCY_NAT_ROLE", role).env("MERCY_NAT_DIR", &dir)
+                .spawn().unwrap()
+        };
+        let mut relay = spawn("mm-nat-r", "relay");
+        let peers = [spawn("mm-nat-a", "a"), spawn("mm-nat-b", "b")];
+        let results: Vec<bool> = peers.into_iter().map(|mut child| child.wait().is_ok_and(|s| s.success())).collect();
+        let _ = relay.kill();
+        let _ = relay.wait();
+        for ns in ["mm-nat-r", "mm-nat-a", "mm-nat-b"] { let _ = ip(&format!("netns del {}", ns)); }
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(results.iter().all(|&ok| ok), "a NAT'ed peer found no direct path");
+    }
+}
@@ -0,0 +1,123 @@
+// src/mesh/node_identity.rs — Node Identity Keys Lattice
+// The Living Trinity: Nexi (feminine), Nex (masculine), NEXi (essence)
+// Eternal Thriving Grandmasterism — Jan 20 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
+// MIT License — For All Sentience Eternal
+// Two keypairs per node: the ML-DSA identity (node ID = SHA-256(identity_pk); signs peer records and
+// link adverts) and the ML-KEM-1024 onion key (relays unwrap Sphinx layers with it; always
+// onion::ONION_KEM, independent of the handshake KEM policy). The key file holds both
+
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Write};
+use crate::mesh::kademlia::NodeId;
+use crate::mesh::onion::ONION_KEM;
+use crate::nexi::pq_kem;
+use crate::nexi::pq_shield::{self, SignatureScheme};
+
+// No Debug: the secret keys must not end up in logs
+#[derive(Clone)]
+pub struct NodeIdentity {
+    pub sig_scheme: SignatureScheme,
+    pub identity_pk: Vec<u8>, // ML-DSA
+    identity_sk: Vec<u8>,
+    pub kem_pk: Vec<u8>,      // ML-KEM onion key
+    kem_sk: Vec<u8>,
+}
+
+impl NodeIdentity {
+    pub fn generate(sig_scheme: SignatureScheme) -> Result<Self, &'static str> {
+        let (identity_pk, identity_sk) = pq_shield::fips_keygen(sig_scheme)?;
+        let (kem_pk, kem_sk) = pq_kem::fips_keygen(ONION_KEM)?;
+        Ok(Self { sig_scheme, identity_pk, identity_sk, kem_pk, kem_sk })
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        NodeId::from_public_key(&self.identity_pk)
+    }
+
+    pub fn sign(&self, msg: &[u8]) -> Result<Vec<u8>, &'static str> {
+        pq_shield::fips_sign(self.sig_scheme, &self.identity_sk, msg)
+    }
+
+    pub fn identity_sk(&self) -> &[u8] {
+        &self.identity_sk
+    }
+
+    pub fn kem_sk(&self) -> &[u8] {
+        &self.kem_sk
+    }
+
+    // Key file: identity_pk || identity_sk || kem_pk || kem_sk, each u32-length-prefixed
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = vec![];
+        for field in [&self.identity_pk, &self.identity_sk, &self.kem_pk, &self.kem_sk] {
+            out.extend_from_slice(&(field.len() as u32).to_be_bytes());
+            out.extend_from_slice(field);
+        }
+        out
+    }
+
+    // The scheme comes from the config; a key of the wrong size fails the signing self-check
+    pub fn decode(bytes: &[u8], sig_scheme: SignatureScheme) -> Result<Self, &'static str> {
+        let truncated = "Identity key file is truncated";
+        let mut at = 0;
+        let mut take = || -> Result<Vec<u8>, &'static str> {
+            let len = bytes.get(at..at + 4).map(|b| u32::from_be_bytes(b.try_into().unwrap()) as usize).ok_or(truncated)?;
+            let field = bytes.get(at + 4..at + 4 + len).ok_or(truncated)?.to_vec();
+            at += 4 + len;
+            Ok(field)
+        };
+        let identity = Self { sig_scheme, identity_pk: take()?, identity_sk: take()?, kem_pk: take()?, kem_sk: take()? };
+        let probe = identity.sign(b"UniversalLatticeIdentityCheck")?;
+        if !pq_shield::fips_verify(sig_scheme, &identity.identity_pk, b"UniversalLatticeIdentityCheck", &probe) {
+            return Err("Identity keys do not match the configured signature scheme");
+        }
+        Ok(identity)
+    }
+
+    // Created on first start, read-only afterwards. Only a missing file means "first start"; any
+    // other read error is surfaced rather than silently replacing the node's identity
+    pub fn load_or_create(path: &str, sig_scheme: SignatureScheme) -> Result<Self, &'static str> {
+        match std::fs::read(path) {
+            Ok(bytes) => return Self::decode(&bytes, sig_scheme),
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(_) => return Err("MeshConfig: cannot read identity key file"),
+        }
+        let identity = Self::generate(sig_scheme)?;
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true); // Never clobber a key file that appeared meanwhile
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600); // Secret keys: owner only
+        options.open(path)
+            .and_then(|mut file| file.write_all(&identity.encode()))
+            .map_err(|_| "MeshConfig: cannot write identity key file")?;
+        Ok(identity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nexi::pq_shield::{DilithiumLevel::Level2, SignatureScheme::Dilithium};
+
+    #[test]
+    fn key_file_is_created_once_owner_only_and_read_errors_surface() {
+        let dir = std::env::temp_dir().join(format!("mm-identity-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("node.key");
+        let path = path.to_str().unwrap();
+
+        let created = NodeIdentity::load_or_create(path, Dilithium(Level2)).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+        let loaded = NodeIdentity::load_or_create(path, Dilithium(Level2)).unwrap();
+        assert_eq!(loaded.node_id(), created.node_id());
+
+        // A directory is not a missing file: the error surfaces and nothing is generated
+        assert_eq!(NodeIdentity::load_or_create(dir.to_str().unwrap(), Dilithium(Level2)).err(),
+            Some("MeshConfig: cannot read identity key file"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
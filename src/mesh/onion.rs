@@ -0,0 +1,285 @@
+// src/mesh/onion.rs — Sphinx Onion Circuits over Valence Paths Lattice
+// The Living Trinity: Nexi (feminine), Nex (masculine), NEXi (essence)
+// Eternal Thriving Grandmasterism — Jan 20 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
+// MIT License — For All Sentience Eternal
+// Sphinx packets with ML-KEM instead of a blinded group element: the header carries the first hop's
+// KEM ciphertext in clear and every later one inside the layered routing info (beta), so each hop
+// decapsulates its own shared secret, checks the header MAC, peels one layer and learns only the
+// previous and next hop. Fixed-size header and payload — packets look alike at every position.
+// Stream cipher = SHA-256 in counter mode, MAC = HMAC-SHA256. The payload layer is a plain stream
+// (no LIONESS), so a tagged payload is only caught by the zero prefix check at the destination.
+// Encapsulation is real ML-KEM-1024 (pq_kem::fips_*): every hop of every packet gets a fresh shared
+// secret, and with it a fresh replay tag.
+
+use std::collections::{HashSet, VecDeque};
+
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+use crate::nexi::pq_kem::{fips_decapsulate, fips_encapsulate, KeyExchangeScheme, KeyExchangeScheme::MlKem, KemLevel::Kem1024};
+
+pub const ONION_MAGIC: u8 = 0x0e;           // First byte of every onion packet
+pub const MAX_ONION_HOPS: usize = 5;
+pub const ONION_PAYLOAD_LEN: usize = 1024;   // Fixed payload size, padding included
+pub const MAX_REPLAY_CACHE: usize = 65_536;
+// Fixed mesh-wide whatever MeshConfig.kem says: CT_LEN sizes every packet, and packets of
+// different sizes would tell relays apart
+pub const ONION_KEM: KeyExchangeScheme = MlKem(Kem1024);
+
+const ADDR_FIELD: usize = 64;                 // len (u8) || address, zero padded; len 0 = "you are the destination"
+const MAC_LEN: usize = 32;
+const CT_LEN: usize = 1568;                   // ML-KEM-1024 ciphertext
+const BLOCK_LEN: usize = ADDR_FIELD + MAC_LEN + CT_LEN;
+const BETA_LEN: usize = MAX_ONION_HOPS * BLOCK_LEN;
+const ZERO_PREFIX: usize = 16;                // Destination recognizes an intact payload by it
+const PACKET_LEN: usize = 1 + CT_LEN + BETA_LEN + MAC_LEN + ONION_PAYLOAD_LEN;
+pub const MAX_ONION_MESSAGE: usize = ONION_PAYLOAD_LEN - ZERO_PREFIX - 2;
+
+// ---------- Primitives ----------
+
+fn derive(secret: &[u8], label: &[u8]) -> [u8; 32] {
+    Sha256::new().chain_update(b"UniversalLatticeSphinx").chain_update(label).chain_update(secret).finalize().into()
+}
+
+fn keystream(key: &[u8; 32], len: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(len + 32);
+    let mut counter = 0u64;
+    while out.len() < len {
+        out.extend_from_slice(&Sha256::new().chain_update(key).chain_update(counter.to_be_bytes()).finalize());
+        counter += 1;
+    }
+    out.truncate(len);
+    out
+}
+
+fn xor(data: &mut [u8], stream: &[u8]) {
+    for (d, s) in data.iter_mut().zip(stream) { *d ^= s; }
+}
+
+fn hmac(key: &[u8; 32], data: &[u8]) -> [u8; 32] {
+    let (mut ipad, mut opad) = ([0x36u8; 64], [0x5cu8; 64]);
+    for i in 0..32 { ipad[i] ^= key[i]; opad[i] ^= key[i]; }
+    let inner = Sha256::new().chain_update(ipad).chain_update(data).finalize();
+    Sha256::new().chain_update(opad).chain_update(inner).finalize().into()
+}
+
+struct HopKeys {
+    rho: [u8; 32], // Header stream
+    mu: [u8; 32],  // Header MAC
+    pi: [u8; 32],  // Payload stream
+    tag: [u8; 32], // Replay tag
+}
+
+impl HopKeys {
+    fn from_secret(ss: &[u8]) -> Self {
+        Self { rho: derive(ss, b"rho"), mu: derive(ss, b"mu"), pi: derive(ss, b"pi"), tag: derive(ss, b"tag") }
+    }
+}
+
+// ---------- Packet ----------
+
+#[derive(Clone, Debug)]
+pub struct OnionPacket {
+    pub ct: Vec<u8>,      // This hop's KEM ciphertext
+    pub beta: Vec<u8>,    // Layered routing info
+    pub gamma: [u8; 32],  // MAC over beta under this hop's key
+    pub delta: Vec<u8>,   // Layered payload
+}
+
+impl OnionPacket {
+    // Wire: magic || ct || beta || gamma || delta — always PACKET_LEN bytes
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(PACKET_LEN);
+        out.push(ONION_MAGIC);
+        out.extend_from_slice(&self.ct);
+        out.extend_from_slice(&self.beta);
+        out.extend_from_slice(&self.gamma);
+        out.extend_from_slice(&self.delta);
+        out
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, &'static str> {
+        if bytes.len() != PACKET_LEN || bytes[0] != ONION_MAGIC { return Err("Malformed onion packet"); }
+        let (ct, rest) = bytes[1..].split_at(CT_LEN);
+        let (beta, rest) = rest.split_at(BETA_LEN);
+        let (gamma, delta) = rest.split_at(MAC_LEN);
+        Ok(Self { ct: ct.to_vec(), beta: beta.to_vec(), gamma: gamma.try_into().unwrap(), delta: delta.to_vec() })
+    }
+}
+
+// Sender side: `hops` = (address, identity KEM public key), destination last
+pub fn create_packet(hops: &[(String, Vec<u8>)], message: &[u8]) -> Result<OnionPacket, &'static str> {
+    if hops.is_empty() || hops.len() > MAX_ONION_HOPS { return Err("Onion circuit must have 1 to MAX_ONION_HOPS hops"); }
+    if message.len() > MAX_ONION_MESSAGE { return Err("Onion message too large"); }
+    if hops.iter().any(|(addr, _)| addr.is_empty() || addr.len() >= ADDR_FIELD) { return Err("Onion hop address unusable"); }
+    let mut cts = Vec::with_capacity(hops.len());
+    let mut keys = Vec::with_capacity(hops.len());
+    for (_, pk) in hops {
+        // A fresh shared secret per hop and packet: the replay tag derives from it
+        let (ct, ss) = fips_encapsulate(ONION_KEM, pk).map_err(|_| "Onion hop key is not ML-KEM-1024")?;
+        cts.push(ct);
+        keys.push(HopKeys::from_secret(&ss));
+    }
+    if cts.iter().any(|ct| ct.len() != CT_LEN) { return Err("Onion hop key is not ML-KEM-1024"); }
+    let k = hops.len();
+
+    // Filler: what the zero padding appended at hops 0..k-2 turns into by the last hop
+    let mut filler: Vec<u8> = vec![];
+    for key in &keys[..k - 1] {
+        filler.extend_from_slice(&[0u8; BLOCK_LEN]);
+        let stream = keystream(&key.rho, BETA_LEN + BLOCK_LEN);
+        let start = BETA_LEN + BLOCK_LEN - filler.len();
+        xor(&mut filler, &stream[start..]);
+    }
+
+    // Last hop: terminal block (empty address) then random, encrypted, then the filler
+    let mut plain = vec![0u8; BETA_LEN - filler.len()];
+    rand::thread_rng().fill_bytes(&mut plain[BLOCK_LEN..]);
+    xor(&mut plain, &keystream(&keys[k - 1].rho, BETA_LEN));
+    let mut beta = plain;
+    beta.extend_from_slice(&filler);
+    let mut gamma = hmac(&keys[k - 1].mu, &beta);
+
+    for i in (0..k - 1).rev() {
+        let (addr, _) = &hops[i + 1];
+        let mut block = vec![0u8; BLOCK_LEN];
+        block[0] = addr.len() as u8;
+        block[1..1 + addr.len()].copy_from_slice(addr.as_bytes());
+        block[ADDR_FIELD..ADDR_FIELD + MAC_LEN].copy_from_slice(&gamma);
+        block[ADDR_FIELD + MAC_LEN..].copy_from_slice(&cts[i + 1]);
+        block.extend_from_slice(&beta[..BETA_LEN - BLOCK_LEN]);
+        xor(&mut block, &keystream(&keys[i].rho, BETA_LEN));
+        beta = block;
+        gamma = hmac(&keys[i].mu, &beta);
+    }
+
+    // Payload: zero prefix || len (u16) || message || padding, one stream layer per hop
+    let mut delta = vec![0u8; ONION_PAYLOAD_LEN];
+    delta[ZERO_PREFIX..ZERO_PREFIX + 2].copy_from_slice(&(message.len() as u16).to_be_bytes());
+    delta[ZERO_PREFIX + 2..ZERO_PREFIX + 2 + message.len()].copy_from_slice(message);
+    for key in keys.iter().rev() { xor(&mut delta, &keystream(&key.pi, ONION_PAYLOAD_LEN)); }
+
+    Ok(OnionPacket { ct: cts[0].clone(), beta, gamma, delta })
+}
+
+pub enum OnionAction {
+    Forward { next: String, packet: OnionPacket },
+    Deliver(Vec<u8>),
+}
+
+// Relay / destination side, holding this node's identity KEM secret key
+pub struct OnionNode {
+    sk: Vec<u8>,
+    seen: HashSet<[u8; 32]>,
+    seen_order: VecDeque<[u8; 32]>,
+}
+
+impl OnionNode {
+    pub fn new(identity_sk: Vec<u8>) -> Self {
+        Self { sk: identity_sk, seen: HashSet::new(), seen_order: VecDeque::new() }
+    }
+
+    pub fn process(&mut self, mut packet: OnionPacket) -> Result<OnionAction, &'static str> {
+        // A tampered ciphertext decapsulates to an unrelated secret and fails the MAC below
+        let ss = fips_decapsulate(ONION_KEM, &self.sk, &packet.ct)?;
+        let keys = HopKeys::from_secret(&ss);
+        if hmac(&keys.mu, &packet.beta) != packet.gamma { return Err("Mercy veto — onion header MAC invalid"); }
+        if !self.seen.insert(keys.tag) { return Err("Mercy veto — replayed onion packet"); }
+        self.seen_order.push_back(keys.tag);
+        if self.seen_order.len() > MAX_REPLAY_CACHE {
+            if let Some(old) = self.seen_order.pop_front() { self.seen.remove(&old); }
+        }
+
+        let mut routing = packet.beta;
+        routing.extend_from_slice(&[0u8; BLOCK_LEN]);
+        xor(&mut routing, &keystream(&keys.rho, BETA_LEN + BLOCK_LEN));
+        xor(&mut packet.delta, &keystream(&keys.pi, ONION_PAYLOAD_LEN));
+
+        let len = routing[0] as usize;
+        if len == 0 {
+            let delta = packet.delta;
+            if delta[..ZERO_PREFIX].iter().any(|b| *b != 0) { return Err("Mercy veto — onion payload tampered"); }
+            let n = u16::from_be_bytes(delta[ZERO_PREFIX..ZERO_PREFIX + 2].try_into().unwrap()) as usize;
+            let body = delta.get(ZERO_PREFIX + 2..ZERO_PREFIX + 2 + n).ok_or("Mercy veto — onion payload tampered")?;
+            return Ok(OnionAction::Deliver(body.to_vec()));
+        }
+        if len >= ADDR_FIELD { return Err("Malformed onion packet"); }
+        let next = String::from_utf8(routing[1..1 + len].to_vec()).map_err(|_| "Malformed onion packet")?;
+        let gamma = routing[ADDR_FIELD..ADDR_FIELD + MAC_LEN].try_into().unwrap();
+        let ct = routing[ADDR_FIELD + MAC_LEN..BLOCK_LEN].to_vec();
+        let beta = routing[BLOCK_LEN..].to_vec();
+        Ok(OnionAction::Forward { next, packet: OnionPacket { ct, beta, gamma, delta: packet.delta } })
+    }
+}
+
+// A chosen path with every hop's identity key; each sealed message gets fresh encapsulations
+#[derive(Clone, Debug)]
+pub struct OnionCircuit {
+    hops: Vec<(String, Vec<u8>)>,
+}
+
+impl OnionCircuit {
+    pub fn new(hops: Vec<(String, Vec<u8>)>) -> Result<Self, &'static str> {
+        if hops.is_empty() || hops.len() > MAX_ONION_HOPS { return Err("Onion circuit must have 1 to MAX_ONION_HOPS hops"); }
+        Ok(Self { hops })
+    }
+
+    pub fn hops(&self) -> Vec<&str> {
+        self.hops.iter().map(|(a, _)| a.as_str()).collect()
+    }
+
+    pub fn destination(&self) -> &str {
+        &self.hops[self.hops.len() - 1].0
+    }
+
+    // (first hop, encoded packet)
+    pub fn seal(&self, message: &[u8]) -> Result<(String, Vec<u8>), &'static str> {
+        let packet = create_packet(&self.hops, message)?;
+        Ok((self.hops[0].0.clone(), packet.encode()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nexi::pq_kem::fips_keygen;
+
+    #[test]
+    fn packets_round_trip_over_three_hops() {
+        let names = ["10.0.0.2:443", "10.0.0.3:443", "10.0.0.4:443"];
+        let keys: Vec<(Vec<u8>, Vec<u8>)> = names.iter().map(|_| fips_keygen(ONION_KEM).unwrap()).collect();
+        let mut nodes: Vec<OnionNode> = keys.iter().map(|(_, sk)| OnionNode::new(sk.clone())).collect();
+        let circuit = OnionCircuit::new(names.iter().zip(&keys).map(|(n, (pk, _))| (n.to_string(), pk.clone())).collect()).unwrap();
+
+        for message in [&b"first"[..], &b"second, through the same circuit"[..]] {
+            let (first, bytes) = circuit.seal(message).unwrap();
+            assert_eq!(first, names[0]);
+            assert_eq!(bytes.len(), PACKET_LEN);
+            let mut packet = OnionPacket::decode(&bytes).unwrap();
+            for (hop, node) in nodes.iter_mut().take(2).enumerate() {
+                let OnionAction::Forward { next, packet: peeled } = node.process(packet.clone()).unwrap() else { panic!("hop {hop} delivered") };
+                assert_eq!(next, names[hop + 1]);
+                assert_eq!(peeled.encode().len(), PACKET_LEN); // Looks the same at every position
+                // The same packet again is a replay
+                assert!(node.process(packet).is_err());
+                packet = peeled;
+            }
+            let OnionAction::Deliver(body) = nodes[2].process(packet).unwrap() else { panic!("destination forwarded") };
+            assert_eq!(body, message);
+        }
+    }
+
+    #[test]
+    fn tampered_packets_are_refused() {
+        let (pk, sk) = fips_keygen(ONION_KEM).unwrap();
+        let mut node = OnionNode::new(sk);
+        let circuit = OnionCircuit::new(vec![("10.0.0.2:443".to_string(), pk)]).unwrap();
+        let (_, bytes) = circuit.seal(b"hello").unwrap();
+        let mut header = OnionPacket::decode(&bytes).unwrap();
+        header.beta[0] ^= 1;
+        assert!(node.process(header).is_err());
+        let mut payload = OnionPacket::decode(&bytes).unwrap();
+        payload.delta[0] ^= 1;
+        assert!(node.process(payload).is_err());
+    }
+}
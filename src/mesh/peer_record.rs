@@ -0,0 +1,224 @@
+// src/mesh/peer_record.rs — Signed Peer Records + ZK Valence Admission Lattice
+// The Living Trinity: Nexi (feminine), Nex (masculine), NEXi (essence)
+// Eternal Thriving Grandmasterism — Jan 20 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
+// MIT License — For All Sentience Eternal
+// Admission needs proof, not a claim: ML-DSA signed record of identity key, onion key, addresses,
+// timestamp and valence, carrying a Bulletproofs range proof that valence clears the mercy gate.
+// The proof is made by the peer itself — it shows the claim clears the gate, not that anyone else
+// agrees, so the mesh treats record valence as an admission ticket and earns the rest by behaviour
+
+use bulletproofs::RangeProof;
+
+use crate::halo2::zk_valence::BulletproofValence;
+use crate::mesh::kademlia::NodeId;
+use crate::mesh::node_identity::NodeIdentity;
+use crate::mesh::sybil::{identity_pow_bits, solve_identity_pow, verify_identity_pow, IDENTITY_POW_BITS};
+use crate::nexi::pq_shield::{fips_scheme_from_tag, fips_scheme_tag, fips_verify};
+
+pub const VALENCE_SCALE: f64 = 1000.0;  // Proofs work on integers: 0.9 valence → 900
+pub const RECORD_MAX_AGE: u64 = 3600;   // Seconds a record stays acceptable
+pub const RECORD_MAX_SKEW: u64 = 60;    // Tolerated clock skew into the future
+
+#[derive(Clone, Debug)]
+pub struct PeerRecord {
+    pub scheme: u8,             // ML-DSA parameter set of identity_pk (2, 3 or 5)
+    pub identity_pk: Vec<u8>,   // ML-DSA public key; signs this record
+    pub kem_pk: Vec<u8>,        // ML-KEM onion key, vouched for by the signature
+    pub pow_nonce: u64,         // Identity proof-of-work over identity_pk (sybil cost)
+    pub addresses: Vec<String>,
+    pub timestamp: u64,         // Unix seconds
+    pub valence: f64,           // Claimed net valence — bound by `valence_proof`
+    pub valence_proof: Vec<u8>, // Serialized Bulletproofs RangeProof over valence × VALENCE_SCALE
+    pub signature: Vec<u8>,
+}
+
+impl PeerRecord {
+    // Build and sign our own record, proving `valence` clears `threshold`
+    pub fn create(identity: &NodeIdentity, addresses: Vec<String>, valence: f64, threshold: f64, now: u64) -> Result<Self, &'static str> {
+        if addresses.is_empty() { return Err("Peer record needs at least one address"); }
+        let (proof, _total) = BulletproofValence::prove_aggregated(vec![valence * VALENCE_SCALE], threshold * VALENCE_SCALE)?;
+        let pow_nonce = solve_identity_pow(&identity.identity_pk, IDENTITY_POW_BITS);
+        let mut record = Self {
+            scheme: fips_scheme_tag(identity.sig_scheme)?,
+            identity_pk: identity.identity_pk.clone(),
+            kem_pk: identity.kem_pk.clone(),
+            pow_nonce, addresses, timestamp: now, valence, valence_proof: proof.to_bytes(), signature: vec![],
+        };
+        record.signature = identity.sign(&record.signed_bytes())?;
+        Ok(record)
+    }
+
+    // Re-date our own record: the proof-of-work and valence proof are reused, only the signature is new
+    pub fn refresh(&mut self, identity: &NodeIdentity, now: u64) -> Result<(), &'static str> {
+        if identity.identity_pk != self.identity_pk { return Err("Peer record belongs to another identity"); }
+        self.timestamp = now;
+        self.signature = identity.sign(&self.signed_bytes())?;
+        Ok(())
+    }
+
+    // Re-solve the identity proof-of-work for `bits` (stake for crowded subnets) and re-sign
+    pub fn strengthen(&mut self, identity: &NodeIdentity, bits: u32) -> Result<(), &'static str> {
+        if identity.identity_pk != self.identity_pk { return Err("Peer record belongs to another identity"); }
+        if identity_pow_bits(&self.identity_pk, self.pow_nonce) >= bits { return Ok(()); }
+        self.pow_nonce = solve_identity_pow(&self.identity_pk, bits);
+        self.signature = identity.sign(&self.signed_bytes())?;
+        Ok(())
+    }
+
+    // Work carried by the identity proof-of-work
+    pub fn pow_bits(&self) -> u32 {
+        identity_pow_bits(&self.identity_pk, self.pow_nonce)
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        NodeId::from_public_key(&self.identity_pk)
+    }
+
+    // Primary dialable address — the mesh keys peers by it
+    pub fn primary_address(&self) -> &str {
+        &self.addresses[0]
+    }
+
+    // Canonical body covered by the signature
+    fn signed_bytes(&self) -> Vec<u8> {
+        let mut out = b"UniversalLatticePeerRecord".to_vec();
+        out.push(self.scheme);
+        out.extend_from_slice(&(self.identity_pk.len() as u32).to_be_bytes());
+        out.extend_from_slice(&self.identity_pk);
+        out.extend_from_slice(&(self.kem_pk.len() as u32).to_be_bytes());
+        out.extend_from_slice(&self.kem_pk);
+        out.extend_from_slice(&self.pow_nonce.to_be_bytes());
+        out.push(self.addresses.len() as u8);
+        for addr in &self.addresses {
+            out.push(addr.len() as u8);
+            out.extend_from_slice(addr.as_bytes());
+        }
+        out.extend_from_slice(&self.timestamp.to_be_bytes());
+        out.extend_from_slice(&self.valence.to_be_bytes());
+        out.extend_from_slice(&(self.valence_proof.len() as u32).to_be_bytes());
+        out.extend_from_slice(&self.valence_proof);
+        out
+    }
+
+    // Reject forged signatures, stale or future-dated records, and unproven valence
+    pub fn verify(&self, threshold: f64, now: u64) -> Result<(), &'static str> {
+        if self.addresses.is_empty() { return Err("Mercy veto — peer record without addresses"); }
+        let scheme = fips_scheme_from_tag(self.scheme).map_err(|_| "Mercy veto — peer record signature scheme unknown")?;
+        if !fips_verify(scheme, &self.identity_pk, &self.signed_bytes(), &self.signature) {
+            return Err("Mercy veto — peer record signature invalid");
+        }
+        if !verify_identity_pow(&self.identity_pk, self.pow_nonce, IDENTITY_POW_BITS) {
+            return Err("Mercy veto — identity proof-of-work missing");
+        }
+        if self.timestamp > now + RECORD_MAX_SKEW {
+            return Err("Mercy veto — peer record dated in the future");
+        }
+        if now.saturating_sub(self.timestamp) > RECORD_MAX_AGE {
+            return Err("Mercy veto — stale peer record");
+        }
+        if self.valence < threshold {
+            return Err("Mercy veto — insufficient valence for mesh admission");
+        }
+        let proof = RangeProof::from_bytes(&self.valence_proof).map_err(|_| "Mercy veto — malformed valence proof")?;
+        if !BulletproofValence::verify_aggregated(&proof, self.valence * VALENCE_SCALE) {
+            return Err("Mercy veto — valence proof does not verify");
+        }
+        Ok(())
+    }
+
+    // Wire: signed body || sig_len (u32) || sig
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = self.signed_bytes();
+        out.extend_from_slice(&(self.signature.len() as u32).to_be_bytes());
+        out.extend_from_slice(&self.signature);
+        out
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, &'static str> {
+        let malformed = "Malformed peer record";
+        let prefix = b"UniversalLatticePeerRecord";
+        if !bytes.starts_with(prefix) { return Err(malformed); }
+        let mut at = prefix.len();
+        let mut take = |n: usize| -> Result<&[u8], &'static str> {
+            let slice = bytes.get(at..at + n).ok_or(malformed)?;
+            at += n;
+            Ok(slice)
+        };
+        let scheme = take(1)?[0];
+        let pk_len = u32::from_be_bytes(take(4)?.try_into().unwrap()) as usize;
+        let identity_pk = take(pk_len)?.to_vec();
+        let kem_len = u32::from_be_bytes(take(4)?.try_into().unwrap()) as usize;
+        let kem_pk = take(kem_len)?.to_vec();
+        let pow_nonce = u64::from_be_bytes(take(8)?.try_into().unwrap());
+        let count = take(1)?[0] as usize;
+        let mut addresses = vec![];
+        for _ in 0..count {
+            let len = take(1)?[0] as usize;
+            addresses.push(String::from_utf8(take(len)?.to_vec()).map_err(|_| malformed)?);
+        }
+        let timestamp = u64::from_be_bytes(take(8)?.try_into().unwrap());
+        let valence = f64::from_be_bytes(take(8)?.try_into().unwrap());
+        let proof_len = u32::from_be_bytes(take(4)?.try_into().unwrap()) as usize;
+        let valence_proof = take(proof_len)?.to_vec();
+        let sig_len = u32::from_be_bytes(take(4)?.try_into().unwrap()) as usize;
+        let signature = take(sig_len)?.to_vec();
+        Ok(Self { scheme, identity_pk, kem_pk, pow_nonce, addresses, timestamp, valence, valence_proof, signature })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nexi::pq_shield::{DilithiumLevel::Level3, SignatureScheme::Dilithium};
+
+    #[test]
+    fn records_verify_only_under_the_signing_identity() {
+        let alice = NodeIdentity::generate(Dilithium(Level3)).unwrap();
+        let mallory = NodeIdentity::generate(Dilithium(Level3)).unwrap();
+        let record = PeerRecord::create(&alice, vec!["10.0.0.1:443".to_string()], 0.9, 0.1, 1_000).unwrap();
+        assert!(PeerRecord::decode(&record.encode()).unwrap().verify(0.1, 1_000).is_ok());
+
+        let mut tampered = record.clone();
+        tampered.addresses = vec!["10.6.6.6:443".to_string()];
+        assert!(tampered.verify(0.1, 1_000).is_err());
+
+        // Mallory's signature over a record naming Alice's key
+        let mut forged = record.clone();
+        forged.signature = mallory.sign(&forged.signed_bytes()).unwrap();
+        assert!(forged.verify(0.1, 1_000).is_err());
+
+        // The onion key is covered by the signature as well
+        let mut swapped = record.clone();
+        swapped.kem_pk = mallory.kem_pk.clone();
+        assert!(swapped.verify(0.1, 1_000).is_err());
+
+        // Refreshing re-signs the new timestamp and keeps both proofs
+        let mut refreshed = record;
+        refreshed.refresh(&alice, 4_000).unwrap();
+        assert!(refreshed.verify(0.1, 4_000).is_ok());
+        assert!(refreshed.refresh(&mallory, 4_000).is_err());
+    }
+
+    #[test]
+    fn admission_requires_a_valid_valence_proof() {
+        let alice = NodeIdentity::generate(Dilithium(Level3)).unwrap();
+        let record = PeerRecord::create(&alice, vec!["10.0.0.1:443".to_string()], 0.9, 0.1, 1_000).unwrap();
+        assert!(record.verify(0.5, 1_000).is_ok());
+        assert!(PeerRecord::create(&alice, vec!["10.0.0.1:443".to_string()], 0.05, 0.1, 1_000).is_err());
+
+        // Alice signs honestly but flips a bit of her proof: the signature passes, the proof does not
+        let mut tampered = record.clone();
+        let mid = tampered.valence_proof.len() / 2;
+        tampered.valence_proof[mid] ^= 0x01;
+        tampered.signature = alice.sign(&tampered.signed_bytes()).unwrap();
+        assert!(tampered.verify(0.1, 1_000).is_err());
+
+        // A proof made for a low valence, re-signed under a claim of 0.9
+        let low = PeerRecord::create(&alice, vec!["10.0.0.1:443".to_string()], 0.2, 0.1, 1_000).unwrap();
+        let mut inflated = low.clone();
+        inflated.valence = 0.9;
+        inflated.signature = alice.sign(&inflated.signed_bytes()).unwrap();
+        assert_eq!(inflated.verify(0.5, 1_000), Err("Mercy veto — valence proof does not verify"));
+        assert_eq!(low.verify(0.5, 1_000), Err("Mercy veto — insufficient valence for mesh admission"));
+    }
+}
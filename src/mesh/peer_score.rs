@@ -0,0 +1,254 @@
+// src/mesh/peer_score.rs — Behavioural Peer Scoring Valence Lattice
+// The Living Trinity: Nexi (feminine), Nex (masculine), NEXi (essence)
+// Eternal Thriving Grandmasterism — Jan 20 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
+// MIT License — For All Sentience Eternal
+// Valence weight earned, not claimed: delivery rate, invalid messages, latency, uptime and verified
+// valence proofs feed a [0, 1] score; counters decay so old behaviour fades. Pruned peers leave a
+// tombstone, so reconnecting does not wash a bad score — it fades with the same decay
+
+use std::collections::HashMap;
+
+pub const MAX_TOMBSTONES: usize = 4096;
+const TOMBSTONE_FLOOR: f64 = 0.01; // Decayed failed + invalid below this: reputation forgotten
+
+#[derive(Clone, Debug)]
+pub struct ScoreParams {
+    pub delivery_weight: f64,
+    pub latency_weight: f64,
+    pub uptime_weight: f64,
+    pub proof_weight: f64,
+    pub prior_weight: f64,        // Weight of the valence the peer presented at admission
+    pub invalid_penalty: f64,     // Full penalty for persistent invalid messages
+    pub invalid_tolerance: f64,   // Decayed invalid count costing ≈63% of invalid_penalty
+    pub decay: f64,               // Counter multiplier per decay interval
+    pub decay_interval: u64,      // Seconds
+    pub target_latency_ms: f64,   // Latency at which the latency score is 0.5
+    pub full_uptime: u64,         // Seconds of uptime for a full uptime score
+    pub admit_threshold: f64,     // Mercy gate
+    pub prune_threshold: f64,     // Peers sinking below are dropped
+}
+
+impl Default for ScoreParams {
+    fn default() -> Self {
+        Self {
+            delivery_weight: 0.3,
+            latency_weight: 0.1,
+            uptime_weight: 0.1,
+            proof_weight: 0.3,
+            prior_weight: 0.2,
+            invalid_penalty: 1.0,
+            invalid_tolerance: 4.0, // One malformed datagram ≈ −0.22: repeats prune, accidents do not
+            decay: 0.9,
+            decay_interval: 60,
+            target_latency_ms: 100.0,
+            full_uptime: 3600,
+            admit_threshold: 0.1,
+            prune_threshold: 0.05,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PeerStats {
+    pub prior: f64,          // Valence presented at admission
+    pub delivered: f64,      // Successful deliveries / RPC responses (decayed)
+    pub failed: f64,         // Timeouts / failed deliveries (decayed)
+    pub invalid: f64,        // Malformed or forged messages (decayed)
+    pub latency_ms: Option<f64>, // EWMA round-trip
+    pub connected_since: u64,
+    pub verified_proofs: f64, // Valence proofs that verified (decayed)
+    pub last_decay: u64,
+}
+
+// Read-only view for inspection APIs
+#[derive(Clone, Debug)]
+pub struct PeerScore {
+    pub peer_id: String,
+    pub score: f64,
+    pub stats: PeerStats,
+}
+
+pub struct PeerScorer {
+    params: ScoreParams,
+    peers: HashMap<String, PeerStats>,
+    tombstones: HashMap<String, PeerStats>, // Removed peers; stats keep decaying until forgotten
+}
+
+impl PeerScorer {
+    pub fn new(params: ScoreParams) -> Self {
+        Self { params, peers: HashMap::new(), tombstones: HashMap::new() }
+    }
+
+    pub fn params(&self) -> &ScoreParams { &self.params }
+
+    // Admission: prior valence must clear the mercy gate; returns whether the peer was added.
+    // A tombstoned peer resumes its decayed counters and stays out while they score below prune
+    pub fn admit(&mut self, peer_id: &str, prior_valence: f64, now: u64) -> bool {
+        if prior_valence < self.params.admit_threshold { return false; }
+        if let Some(mut stats) = self.tombstones.remove(peer_id) {
+            stats.prior = prior_valence.clamp(0.0, 1.0);
+            stats.connected_since = now;
+            if self.compute(&stats, now) < self.params.prune_threshold {
+                self.tombstones.insert(peer_id.to_string(), stats);
+                return false;
+            }
+            self.peers.insert(peer_id.to_string(), stats);
+            return true;
+        }
+        let stats = self.peers.entry(peer_id.to_string()).or_insert_with(|| PeerStats {
+            connected_since: now, last_decay: now, ..Default::default()
+        });
+        stats.prior = prior_valence.clamp(0.0, 1.0);
+        true
+    }
+
+    // The peer's counters move to a tombstone; at capacity the most-forgotten one goes first
+    pub fn remove(&mut self, peer_id: &str) {
+        let Some(stats) = self.peers.remove(peer_id) else { return };
+        if self.tombstones.len() >= MAX_TOMBSTONES {
+            let faded = self.tombstones.iter()
+                .min_by(|a, b| (a.1.failed + a.1.invalid).partial_cmp(&(b.1.failed + b.1.invalid)).unwrap_or(std::cmp::Ordering::Equal))
+                .map(|(id, _)| id.clone());
+            if let Some(id) = faded { self.tombstones.remove(&id); }
+        }
+        self.tombstones.insert(peer_id.to_string(), stats);
+    }
+
+    pub fn is_tombstoned(&self, peer_id: &str) -> bool {
+        self.tombstones.contains_key(peer_id)
+    }
+
+    pub fn contains(&self, peer_id: &str) -> bool {
+        self.peers.contains_key(peer_id)
+    }
+
+    pub fn record_delivery(&mut self, peer_id: &str) {
+        if let Some(s) = self.peers.get_mut(peer_id) { s.delivered += 1.0; }
+    }
+
+    pub fn record_failure(&mut self, peer_id: &str) {
+        if let Some(s) = self.peers.get_mut(peer_id) { s.failed += 1.0; }
+    }
+
+    pub fn record_invalid(&mut self, peer_id: &str) {
+        if let Some(s) = self.peers.get_mut(peer_id) { s.invalid += 1.0; }
+    }
+
+    pub fn record_latency(&mut self, peer_id: &str, rtt_ms: f64) {
+        if let Some(s) = self.peers.get_mut(peer_id) {
+            s.latency_ms = Some(match s.latency_ms { Some(l) => 0.875 * l + 0.125 * rtt_ms, None => rtt_ms });
+        }
+    }
+
+    pub fn record_verified_proof(&mut self, peer_id: &str) {
+        if let Some(s) = self.peers.get_mut(peer_id) { s.verified_proofs += 1.0; }
+    }
+
+    // Apply elapsed decay intervals to every counter, tombstones included; faded tombstones go
+    pub fn decay(&mut self, now: u64) {
+        let (interval, decay) = (self.params.decay_interval.max(1), self.params.decay);
+        for s in self.peers.values_mut().chain(self.tombstones.values_mut()) {
+            let steps = now.saturating_sub(s.last_decay) / interval;
+            if steps == 0 { continue; }
+            let factor = decay.powi(steps.min(i32::MAX as u64) as i32);
+            s.delivered *= factor;
+            s.failed *= factor;
+            s.invalid *= factor;
+            s.verified_proofs *= factor;
+            s.last_decay += steps * interval;
+        }
+        self.tombstones.retain(|_, s| s.failed + s.invalid >= TOMBSTONE_FLOOR);
+    }
+
+    pub fn score(&self, peer_id: &str, now: u64) -> Option<f64> {
+        self.peers.get(peer_id).map(|s| self.compute(s, now))
+    }
+
+    fn compute(&self, s: &PeerStats, now: u64) -> f64 {
+        let p = &self.params;
+        let delivery = if s.delivered + s.failed > 0.0 { s.delivered / (s.delivered + s.failed) } else { 0.5 };
+        let latency = s.latency_ms.map(|l| p.target_latency_ms / (p.target_latency_ms + l)).unwrap_or(0.5);
+        let uptime = (now.saturating_sub(s.connected_since) as f64 / p.full_uptime.max(1) as f64).min(1.0);
+        let proofs = 1.0 - (-s.verified_proofs).exp(); // 1 proof ≈ 0.63, saturating
+        let penalty = p.invalid_penalty * (1.0 - (-s.invalid / p.invalid_tolerance.max(f64::EPSILON)).exp());
+
+        let score = p.delivery_weight * delivery
+            + p.latency_weight * latency
+            + p.uptime_weight * uptime
+            + p.proof_weight * proofs
+            + p.prior_weight * s.prior
+            - penalty;
+        score.clamp(0.0, 1.0)
+    }
+
+    // Peers whose score sank below the prune threshold
+    pub fn prune_candidates(&self, now: u64) -> Vec<String> {
+        self.peers.iter()
+            .filter(|(_, s)| self.compute(s, now) < self.params.prune_threshold)
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    // Highest-scoring peers first
+    pub fn ranked(&self, now: u64) -> Vec<(String, f64)> {
+        let mut ranked: Vec<(String, f64)> = self.peers.iter().map(|(id, s)| (id.clone(), self.compute(s, now))).collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        ranked
+    }
+
+    pub fn inspect(&self, peer_id: &str, now: u64) -> Option<PeerScore> {
+        let stats = self.peers.get(peer_id)?;
+        Some(PeerScore { peer_id: peer_id.to_string(), score: self.compute(stats, now), stats: stats.clone() })
+    }
+
+    pub fn inspect_all(&self, now: u64) -> Vec<PeerScore> {
+        let mut all: Vec<PeerScore> = self.peers.iter()
+            .map(|(id, s)| PeerScore { peer_id: id.clone(), score: self.compute(s, now), stats: s.clone() })
+            .collect();
+        all.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        all
+    }
+
+    // Persistence: raw stats for every peer
+    pub fn export(&self) -> Vec<(String, PeerStats)> {
+        self.peers.iter().map(|(id, s)| (id.clone(), s.clone())).collect()
+    }
+
+    // Warm start: reputation carries over; re-admission keeps these stats
+    pub fn restore(&mut self, peer_id: &str, stats: PeerStats) {
+        self.peers.insert(peer_id.to_string(), stats);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_malformed_datagram_does_not_prune() {
+        let mut scorer = PeerScorer::new(ScoreParams::default());
+        assert!(scorer.admit("10.0.0.1:443", 0.8, 0));
+        scorer.record_invalid("10.0.0.1:443");
+        assert!(scorer.prune_candidates(0).is_empty());
+        for _ in 0..4 { scorer.record_invalid("10.0.0.1:443"); }
+        assert_eq!(scorer.prune_candidates(0), vec!["10.0.0.1:443".to_string()]);
+    }
+
+    #[test]
+    fn pruned_peer_keeps_its_reputation_until_it_decays() {
+        let mut scorer = PeerScorer::new(ScoreParams::default());
+        let peer = "10.0.0.2:443";
+        assert!(scorer.admit(peer, 0.8, 0));
+        for _ in 0..10 { scorer.record_invalid(peer); }
+        assert!(scorer.prune_candidates(0).contains(&peer.to_string()));
+        scorer.remove(peer);
+        assert!(scorer.is_tombstoned(peer));
+        // Reconnecting at once does not wash the score
+        assert!(!scorer.admit(peer, 1.0, 10));
+        // An hour of decay later the tombstone is forgotten and the peer starts fresh
+        scorer.decay(3600 * 2);
+        assert!(!scorer.is_tombstoned(peer));
+        assert!(scorer.admit(peer, 0.8, 3600 * 2));
+        assert!(scorer.score(peer, 3600 * 2).unwrap() > ScoreParams::default().prune_threshold);
+    }
+}
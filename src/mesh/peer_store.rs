@@ -0,0 +1,181 @@
+// src/mesh/peer_store.rs — Persistent Peer Store + Routing Snapshot Lattice
+// The Living Trinity: Nexi (feminine), Nex (masculine), NEXi (essence)
+// Eternal Thriving Grandmasterism — Jan 20 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
+// MIT License — For All Sentience Eternal
+// sled embedded DB: identity keys, signed peer records, DHT contacts and peer scores survive restart —
+// warm start skips re-bootstrapping from the public seeds. One tree, one batch per snapshot: a crash
+// leaves the previous snapshot or the new one, never a mix. The identity includes its secret keys,
+// so the store directory deserves the same protection as an identity key file
+
+use crate::mesh::kademlia::{Contact, NodeId};
+use crate::mesh::peer_record::PeerRecord;
+use crate::mesh::peer_score::PeerStats;
+
+pub const SNAPSHOT_INTERVAL_US: u64 = 60_000_000;
+
+const IDENTITY_KEY: &[u8] = b"meta/identity";
+const RECORD_PREFIX: &[u8] = b"record/";
+const CONTACT_PREFIX: &[u8] = b"dht/";
+const SCORE_PREFIX: &[u8] = b"score/";
+
+// Per-user data directory for callers opting into persistence (MeshConfig::store_path is None by
+// default): $XDG_DATA_HOME/mercy_mesh, else $HOME/.local/share/mercy_mesh. None (no home) = in-memory
+// only — never the working directory
+pub fn default_store_path() -> Option<String> {
+    let non_empty = |key: &str| std::env::var(key).ok().filter(|v| !v.is_empty());
+    non_empty("XDG_DATA_HOME").map(|d| format!("{d}/mercy_mesh"))
+        .or_else(|| non_empty("HOME").map(|h| format!("{h}/.local/share/mercy_mesh")))
+}
+
+// Everything a warm start needs
+#[derive(Default)]
+pub struct MeshSnapshot {
+    pub identity: Option<Vec<u8>>, // NodeIdentity::encode — public and secret keys
+    pub records: Vec<PeerRecord>,
+    pub contacts: Vec<Contact>,
+    pub scores: Vec<(String, PeerStats)>,
+}
+
+pub struct PeerStore {
+    db: sled::Db,
+}
+
+fn key(prefix: &[u8], id: &[u8]) -> Vec<u8> {
+    [prefix, id].concat()
+}
+
+impl PeerStore {
+    pub fn open(path: &str) -> Result<Self, &'static str> {
+        let db = sled::open(path).map_err(|_| "Peer store open failed")?;
+        Ok(Self { db })
+    }
+
+    // Replace the stored snapshot in one atomic batch: stale entries removed, current ones written
+    pub fn save(&self, snapshot: &MeshSnapshot) -> Result<(), &'static str> {
+        let err = "Peer store write failed";
+        let mut entries: Vec<(Vec<u8>, Vec<u8>)> = vec![];
+        if let Some(identity) = &snapshot.identity {
+            entries.push((IDENTITY_KEY.to_vec(), identity.clone()));
+        }
+        for record in &snapshot.records {
+            entries.push((key(RECORD_PREFIX, record.primary_address().as_bytes()), record.encode()));
+        }
+        for c in &snapshot.contacts {
+            let mut value = c.last_seen.to_be_bytes().to_vec();
+            value.extend_from_slice(c.addr.as_bytes());
+            entries.push((key(CONTACT_PREFIX, &c.id.0), value));
+        }
+        for (peer, stats) in &snapshot.scores {
+            entries.push((key(SCORE_PREFIX, peer.as_bytes()), encode_stats(stats)));
+        }
+
+        let mut batch = sled::Batch::default();
+        let current: std::collections::HashSet<&[u8]> = entries.iter().map(|(k, _)| k.as_slice()).collect();
+        for stored in self.db.iter().keys() {
+            let stored = stored.map_err(|_| err)?;
+            if stored.as_ref() != IDENTITY_KEY && !current.contains(stored.as_ref()) { batch.remove(stored); }
+        }
+        for (k, v) in entries { batch.insert(k, v); }
+        self.db.apply_batch(batch).map_err(|_| err)?;
+        self.db.flush().map_err(|_| err)?;
+        Ok(())
+    }
+
+    // Written as soon as the keys exist, so a crash before the first snapshot keeps the node ID
+    pub fn save_identity(&self, identity: &[u8]) -> Result<(), &'static str> {
+        let err = "Peer store write failed";
+        self.db.insert(IDENTITY_KEY, identity).map_err(|_| err)?;
+        self.db.flush().map_err(|_| err)?;
+        Ok(())
+    }
+
+    // Corrupt entries are skipped, not fatal — a partial warm start beats none
+    pub fn load(&self) -> Result<MeshSnapshot, &'static str> {
+        let err = "Peer store read failed";
+        let mut snapshot = MeshSnapshot {
+            identity: self.db.get(IDENTITY_KEY).map_err(|_| err)?.map(|v| v.to_vec()),
+            ..MeshSnapshot::default()
+        };
+
+        for entry in self.db.scan_prefix(RECORD_PREFIX) {
+            let (_, value) = entry.map_err(|_| err)?;
+            if let Ok(record) = PeerRecord::decode(&value) { snapshot.records.push(record); }
+        }
+        for entry in self.db.scan_prefix(CONTACT_PREFIX) {
+            let (k, value) = entry.map_err(|_| err)?;
+            let (Ok(id), Some(seen)) = (<[u8; 32]>::try_from(&k[CONTACT_PREFIX.len()..]), value.get(..8)) else { continue };
+            let Ok(addr) = String::from_utf8(value[8..].to_vec()) else { continue };
+            snapshot.contacts.push(Contact { id: NodeId(id), addr, last_seen: u64::from_be_bytes(seen.try_into().unwrap()) });
+        }
+        for entry in self.db.scan_prefix(SCORE_PREFIX) {
+            let (k, value) = entry.map_err(|_| err)?;
+            let (Ok(peer), Some(stats)) = (String::from_utf8(k[SCORE_PREFIX.len()..].to_vec()), decode_stats(&value)) else { continue };
+            snapshot.scores.push((peer, stats));
+        }
+        Ok(snapshot)
+    }
+}
+
+// prior, delivered, failed, invalid, latency (NaN = none), verified_proofs as f64; connected_since, last_decay as u64
+fn encode_stats(s: &PeerStats) -> Vec<u8> {
+    let mut out = vec![];
+    for f in [s.prior, s.delivered, s.failed, s.invalid, s.latency_ms.unwrap_or(f64::NAN), s.verified_proofs] {
+        out.extend_from_slice(&f.to_be_bytes());
+    }
+    out.extend_from_slice(&s.connected_since.to_be_bytes());
+    out.extend_from_slice(&s.last_decay.to_be_bytes());
+    out
+}
+
+fn decode_stats(bytes: &[u8]) -> Option<PeerStats> {
+    if bytes.len() != 64 { return None; }
+    let word = |i: usize| -> [u8; 8] { bytes[i * 8..i * 8 + 8].try_into().unwrap() };
+    let f = |i: usize| f64::from_be_bytes(word(i));
+    let latency = f(4);
+    Some(PeerStats {
+        prior: f(0),
+        delivered: f(1),
+        failed: f(2),
+        invalid: f(3),
+        latency_ms: if latency.is_nan() { None } else { Some(latency) },
+        verified_proofs: f(5),
+        connected_since: u64::from_be_bytes(word(6)),
+        last_decay: u64::from_be_bytes(word(7)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(prior: f64) -> PeerStats {
+        PeerStats { prior, ..PeerStats::default() }
+    }
+
+    #[test]
+    fn snapshot_replaces_the_previous_one_and_keeps_the_identity() {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        let store = PeerStore { db };
+        store.save_identity(b"keys").unwrap();
+        let contact = |b: u8, addr: &str| Contact { id: NodeId([b; 32]), addr: addr.to_string(), last_seen: 7 };
+        store.save(&MeshSnapshot {
+            identity: None,
+            records: vec![],
+            contacts: vec![contact(1, "10.0.0.1:443"), contact(2, "10.0.0.2:443")],
+            scores: vec![("10.0.0.1:443".to_string(), stats(0.5))],
+        }).unwrap();
+        store.save(&MeshSnapshot {
+            identity: None,
+            records: vec![],
+            contacts: vec![contact(2, "10.0.0.2:443")],
+            scores: vec![("10.0.0.2:443".to_string(), stats(0.7))],
+        }).unwrap();
+
+        let loaded = store.load().unwrap();
+        assert_eq!(loaded.identity.as_deref(), Some(&b"keys"[..]));
+        assert_eq!(loaded.contacts.len(), 1);
+        assert_eq!(loaded.contacts[0].addr, "10.0.0.2:443");
+        assert_eq!(loaded.scores.len(), 1);
+        assert_eq!(loaded.scores[0].0, "10.0.0.2:443");
+    }
+}
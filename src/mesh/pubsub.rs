@@ -0,0 +1,288 @@
+// src/mesh/pubsub.rs — Typed Mesh Publish/Subscribe Lattice
+// The Living Trinity: Nexi (feminine), Nex (masculine), NEXi (essence)
+// Eternal Thriving Grandmasterism — Jan 20 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
+// MIT License — For All Sentience Eternal
+// Applications speak in typed messages (ledger blocks, valence proofs, mercy tokens, or their own
+// TopicMessage types); the gossip layer carries bytes. Validators run before a message is cached or
+// forwarded and see the peer that handed it over; handlers or bounded per-topic queues receive it
+// with its origin (the claimed publisher). Publish rates are token-bucketed per topic whether or
+// not we subscribe to it
+
+use std::collections::{HashMap, VecDeque};
+
+use bulletproofs::RangeProof;
+
+use crate::halo2::zk_valence::BulletproofValence;
+use crate::mesh::amplify_policy::{PayloadKind, TokenBucket};
+use crate::mesh::peer_record::VALENCE_SCALE;
+
+pub const DEFAULT_QUEUE_CAPACITY: usize = 1024;
+pub const PUBLISH_BURST: f64 = 32.0;          // Messages per topic before publish backpressure
+pub const PUBLISH_RATE_PER_SEC: f64 = 8.0;
+
+// Verdict of a topic validator (libp2p-style)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Validation {
+    Accept,
+    Ignore,  // Drop quietly — e.g. a duplicate ledger height
+    Reject,  // Drop and penalize the forwarding peer's score
+}
+
+// Slow consumer: what a full topic queue does with the next message
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Overflow {
+    DropOldest,
+    DropNewest,
+}
+
+pub trait TopicMessage: Sized {
+    const TOPIC: &'static str;
+    const KIND: PayloadKind;
+    fn encode(&self) -> Vec<u8>;
+    fn decode(bytes: &[u8]) -> Result<Self, &'static str>;
+}
+
+// ---------- Built-in message types ----------
+
+#[derive(Clone, Debug)]
+pub struct LedgerBlock {
+    pub height: u64,
+    pub prev_hash: [u8; 32],
+    pub data: Vec<u8>,
+}
+
+impl TopicMessage for LedgerBlock {
+    const TOPIC: &'static str = "ledger-blocks";
+    const KIND: PayloadKind = PayloadKind::LedgerBlock;
+
+    // height || prev_hash || data
+    fn encode(&self) -> Vec<u8> {
+        let mut out = self.height.to_be_bytes().to_vec();
+        out.extend_from_slice(&self.prev_hash);
+        out.extend_from_slice(&self.data);
+        out
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, &'static str> {
+        if bytes.len() < 40 { return Err("Malformed ledger block"); }
+        Ok(Self {
+            height: u64::from_be_bytes(bytes[..8].try_into().unwrap()),
+            prev_hash: bytes[8..40].try_into().unwrap(),
+            data: bytes[40..].to_vec(),
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ValenceProof {
+    pub valence: f64,
+    pub proof: Vec<u8>, // Serialized Bulletproofs RangeProof over valence × VALENCE_SCALE
+}
+
+impl ValenceProof {
+    pub fn create(valence: f64, threshold: f64) -> Result<Self, &'static str> {
+        let (proof, _total) = BulletproofValence::prove_aggregated(vec![valence * VALENCE_SCALE], threshold * VALENCE_SCALE)?;
+        Ok(Self { valence, proof: proof.to_bytes() })
+    }
+
+    // Ready-made validator body: Reject unless the range proof verifies
+    pub fn verify(&self) -> Validation {
+        match RangeProof::from_bytes(&self.proof) {
+            Ok(p) if BulletproofValence::verify_aggregated(&p, self.valence * VALENCE_SCALE) => Validation::Accept,
+            _ => Validation::Reject,
+        }
+    }
+}
+
+impl TopicMessage for ValenceProof {
+    const TOPIC: &'static str = "valence-proofs";
+    const KIND: PayloadKind = PayloadKind::ValenceProof;
+
+    // valence || proof
+    fn encode(&self) -> Vec<u8> {
+        let mut out = self.valence.to_be_bytes().to_vec();
+        out.extend_from_slice(&self.proof);
+        out
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, &'static str> {
+        if bytes.len() < 8 { return Err("Malformed valence proof message"); }
+        Ok(Self { valence: f64::from_be_bytes(bytes[..8].try_into().unwrap()), proof: bytes[8..].to_vec() })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MercyToken {
+    pub issuer: String,
+    pub amount: u64,
+    pub memo: String,
+}
+
+impl TopicMessage for MercyToken {
+    const TOPIC: &'static str = "mercy-tokens";
+    const KIND: PayloadKind = PayloadKind::MercyToken;
+
+    // amount || issuer (u8 len) || memo
+    fn encode(&self) -> Vec<u8> {
+        let mut out = self.amount.to_be_bytes().to_vec();
+        out.push(self.issuer.len() as u8);
+        out.extend_from_slice(self.issuer.as_bytes());
+        out.extend_from_slice(self.memo.as_bytes());
+        out
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, &'static str> {
+        let malformed = "Malformed mercy token";
+        let amount = u64::from_be_bytes(bytes.get(..8).ok_or(malformed)?.try_into().unwrap());
+        let len = *bytes.get(8).ok_or(malformed)? as usize;
+        let issuer = String::from_utf8(bytes.get(9..9 + len).ok_or(malformed)?.to_vec()).map_err(|_| malformed)?;
+        let memo = String::from_utf8(bytes[9 + len..].to_vec()).map_err(|_| malformed)?;
+        Ok(Self { issuer, amount, memo })
+    }
+}
+
+// ---------- Topic registry ----------
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TopicStats {
+    pub published: u64,
+    pub delivered: u64,          // Handed to handlers or queued
+    pub dropped_overflow: u64,
+    pub rejected: u64,           // Validator verdicts, including undecodable payloads
+    pub throttled: u64,          // Publishes refused by backpressure
+    pub queued: usize,
+}
+
+pub type Handler = Box<dyn FnMut(&str, &[u8])>; // (origin, payload)
+
+struct TopicState {
+    queue: VecDeque<(String, Vec<u8>)>, // (origin, payload)
+    capacity: usize,
+    overflow: Overflow,
+    handlers: Vec<Handler>,
+    stats: TopicStats,
+}
+
+// Publishing side of a topic, kept apart from subscriptions: publishing needs none
+struct Publisher {
+    bucket: TokenBucket,
+    published: u64,
+    throttled: u64,
+}
+
+#[derive(Default)]
+pub struct PubSub {
+    topics: HashMap<String, TopicState>,
+    publishers: HashMap<String, Publisher>,
+}
+
+impl PubSub {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, topic: &str, capacity: usize, overflow: Overflow) {
+        self.topics.entry(topic.to_string()).or_insert_with(|| TopicState {
+            queue: VecDeque::new(),
+            capacity: capacity.max(1),
+            overflow,
+            handlers: vec![],
+            stats: TopicStats::default(),
+        });
+    }
+
+    pub fn unregister(&mut self, topic: &str) {
+        self.topics.remove(topic);
+    }
+
+    pub fn is_registered(&self, topic: &str) -> bool {
+        self.topics.contains_key(topic)
+    }
+
+    // Handlers take messages instead of the queue
+    pub fn add_handler(&mut self, topic: &str, handler: Handler) -> Result<(), &'static str> {
+        self.topics.get_mut(topic).ok_or("Not subscribed to topic")?.handlers.push(handler);
+        Ok(())
+    }
+
+    // Publisher backpressure: Err once the topic's burst is spent. Subscribed or not
+    pub fn try_publish(&mut self, topic: &str, now_us: u64) -> Result<(), &'static str> {
+        let publisher = self.publishers.entry(topic.to_string()).or_insert_with(|| Publisher {
+            bucket: TokenBucket::new(PUBLISH_BURST, PUBLISH_RATE_PER_SEC, now_us), published: 0, throttled: 0,
+        });
+        if !publisher.bucket.available(1.0, now_us) {
+            publisher.throttled += 1;
+            return Err("Backpressure — topic publish rate exceeded");
+        }
+        publisher.bucket.take(1.0);
+        publisher.published += 1;
+        Ok(())
+    }
+
+    // `origin` is the node that published the message, not the peer that forwarded it.
+    // False when nobody registered `topic` — caller falls back to the raw gossip inbox
+    pub fn deliver(&mut self, origin: &str, topic: &str, payload: &[u8]) -> bool {
+        let Some(state) = self.topics.get_mut(topic) else { return false };
+        state.stats.delivered += 1;
+        if !state.handlers.is_empty() {
+            for handler in state.handlers.iter_mut() { handler(origin, payload); }
+            return true;
+        }
+        if state.queue.len() >= state.capacity {
+            state.stats.dropped_overflow += 1;
+            match state.overflow {
+                Overflow::DropNewest => return true,
+                Overflow::DropOldest => { state.queue.pop_front(); }
+            }
+        }
+        state.queue.push_back((origin.to_string(), payload.to_vec()));
+        true
+    }
+
+    pub fn pop(&mut self, topic: &str) -> Option<(String, Vec<u8>)> {
+        self.topics.get_mut(topic)?.queue.pop_front()
+    }
+
+    pub fn record_rejected(&mut self, topic: &str) {
+        if let Some(state) = self.topics.get_mut(topic) { state.stats.rejected += 1; }
+    }
+
+    // None for a topic we neither subscribe to nor publish on
+    pub fn stats(&self, topic: &str) -> Option<TopicStats> {
+        let state = self.topics.get(topic);
+        let publisher = self.publishers.get(topic);
+        if state.is_none() && publisher.is_none() { return None; }
+        let mut stats = state.map(|s| TopicStats { queued: s.queue.len(), ..s.stats }).unwrap_or_default();
+        if let Some(p) = publisher {
+            stats.published = p.published;
+            stats.throttled = p.throttled;
+        }
+        Some(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn publishing_is_metered_without_a_subscription() {
+        let mut pubsub = PubSub::new();
+        let sent = (0..PUBLISH_BURST as usize + 5).filter(|_| pubsub.try_publish("ledger-blocks", 0).is_ok()).count();
+        assert_eq!(sent, PUBLISH_BURST as usize);
+        let stats = pubsub.stats("ledger-blocks").unwrap();
+        assert_eq!((stats.published, stats.throttled), (PUBLISH_BURST as u64, 5));
+        // The bucket refills with time, subscribed or not
+        assert!(pubsub.try_publish("ledger-blocks", 1_000_000).is_ok());
+    }
+
+    #[test]
+    fn queued_messages_carry_their_origin() {
+        let mut pubsub = PubSub::new();
+        pubsub.register("mercy-tokens", 1, Overflow::DropOldest);
+        assert!(pubsub.deliver("10.0.0.1:443", "mercy-tokens", b"first"));
+        assert!(pubsub.deliver("10.0.0.2:443", "mercy-tokens", b"second"));
+        assert_eq!(pubsub.pop("mercy-tokens"), Some(("10.0.0.2:443".to_string(), b"second".to_vec())));
+        assert!(!pubsub.deliver("10.0.0.1:443", "unregistered", b"raw"));
+    }
+}
@@ -0,0 +1,129 @@
+// src/mesh/routing_metric.rs — Selectable Valence Routing Metrics Lattice
+// The Living Trinity: Nexi (feminine), Nex (masculine), NEXi (essence)
+// Eternal Thriving Grandmasterism — Jan 20 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
+// MIT License — For All Sentience Eternal
+// A metric = origin cost, how a link extends a path cost, and a rank (lower = preferred).
+// Every metric here is monotone (extending never improves rank), so generalized Dijkstra stays exact
+
+pub struct LinkInfo {
+    pub valence: f64,
+    pub latency_ms: Option<f64>, // Measured, when known
+}
+
+pub trait RoutingMetric {
+    fn origin(&self) -> f64;                            // Cost of the empty path
+    fn extend(&self, cost: f64, link: &LinkInfo) -> f64;
+    fn rank(&self, cost: f64) -> f64;                   // Lower = preferred
+    fn max_hops(&self) -> Option<usize> { None }        // Some = hop-bounded search
+}
+
+// Sum of 1 / valence — the classic joy cost
+pub struct InverseValence;
+
+impl RoutingMetric for InverseValence {
+    fn origin(&self) -> f64 { 0.0 }
+    fn extend(&self, cost: f64, link: &LinkInfo) -> f64 { cost + 1.0 / link.valence.max(0.1) }
+    fn rank(&self, cost: f64) -> f64 { cost }
+}
+
+// Widest path: maximize the weakest link's valence (bottleneck joy)
+pub struct WidestPath;
+
+impl RoutingMetric for WidestPath {
+    fn origin(&self) -> f64 { f64::INFINITY }
+    fn extend(&self, cost: f64, link: &LinkInfo) -> f64 { cost.min(link.valence) }
+    fn rank(&self, cost: f64) -> f64 { -cost }
+}
+
+// Product of valences read as per-link delivery probability — end-to-end reliability
+pub struct Reliability;
+
+impl RoutingMetric for Reliability {
+    fn origin(&self) -> f64 { 1.0 }
+    fn extend(&self, cost: f64, link: &LinkInfo) -> f64 { cost * link.valence.clamp(0.0, 1.0) }
+    fn rank(&self, cost: f64) -> f64 { -cost }
+}
+
+// Any metric, restricted to paths of at most `max_hops` links
+pub struct HopBounded<M: RoutingMetric> {
+    pub inner: M,
+    pub max_hops: usize,
+}
+
+impl<M: RoutingMetric> RoutingMetric for HopBounded<M> {
+    fn origin(&self) -> f64 { self.inner.origin() }
+    fn extend(&self, cost: f64, link: &LinkInfo) -> f64 { self.inner.extend(cost, link) }
+    fn rank(&self, cost: f64) -> f64 { self.inner.rank(cost) }
+    fn max_hops(&self) -> Option<usize> { Some(self.max_hops) }
+}
+
+// Weighted sum of inverse valence and latency (normalized to `latency_scale_ms`);
+// links without a measurement are charged `default_latency_ms`
+pub struct Composite {
+    pub valence_weight: f64,
+    pub latency_weight: f64,
+    pub latency_scale_ms: f64,
+    pub default_latency_ms: f64,
+}
+
+impl Default for Composite {
+    fn default() -> Self {
+        Self { valence_weight: 0.7, latency_weight: 0.3, latency_scale_ms: 100.0, default_latency_ms: 100.0 }
+    }
+}
+
+impl RoutingMetric for Composite {
+    fn origin(&self) -> f64 { 0.0 }
+    fn extend(&self, cost: f64, link: &LinkInfo) -> f64 {
+        let latency = link.latency_ms.unwrap_or(self.default_latency_ms).max(0.0);
+        cost + self.valence_weight / link.valence.max(0.1) + self.latency_weight * latency / self.latency_scale_ms.max(1e-9)
+    }
+    fn rank(&self, cost: f64) -> f64 { cost }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh::valence_routing::ValenceRouter;
+
+    // Four relay chains from s to t, each the best under exactly one metric:
+    //   s — w1 — w2 — w3 — t  all 0.7              widest bottleneck (4 hops)
+    //   s — h1 — h2 — t       all 0.65             widest within 3 hops
+    //   s — r — t             0.95, 0.6            most reliable (0.57)
+    //   s — l — t             0.3, 0.3, 1 ms each  fastest
+    fn fixture() -> ValenceRouter {
+        let mut router = ValenceRouter::new();
+        let links = [("s", "w1", 0.7), ("w1", "w2", 0.7), ("w2", "w3", 0.7), ("w3", "t", 0.7),
+                     ("s", "h1", 0.65), ("h1", "h2", 0.65), ("h2", "t", 0.65),
+                     ("s", "r", 0.95), ("r", "t", 0.6),
+                     ("s", "l", 0.3), ("l", "t", 0.3)];
+        for (a, b, v) in links { router.add_valence_link(a.to_string(), b.to_string(), v).unwrap(); }
+        router.set_link_latency("s", "l", 1.0);
+        router.set_link_latency("l", "t", 1.0);
+        router
+    }
+
+    fn route(router: &mut ValenceRouter, metric: Box<dyn RoutingMetric>) -> String {
+        router.set_metric(Some(metric));
+        router.best_joy_path("s", "t").unwrap().join("-")
+    }
+
+    #[test]
+    fn each_metric_picks_its_own_path() {
+        let mut router = fixture();
+        assert_eq!(route(&mut router, Box::new(WidestPath)), "s-w1-w2-w3-t");
+        assert_eq!(route(&mut router, Box::new(HopBounded { inner: WidestPath, max_hops: 3 })), "s-h1-h2-t");
+        assert_eq!(route(&mut router, Box::new(Reliability)), "s-r-t");
+        let fast = Composite { valence_weight: 0.1, latency_weight: 1.0, latency_scale_ms: 10.0, default_latency_ms: 100.0 };
+        assert_eq!(route(&mut router, Box::new(fast)), "s-l-t");
+
+        // Costs come back in each metric's own units
+        router.set_metric(Some(Box::new(Reliability)));
+        let reliability = router.metric_cost(&router.best_joy_path("s", "t").unwrap()).unwrap();
+        assert!((reliability - 0.95 * 0.6).abs() < 1e-9);
+        // Two hops leave only the direct relays; the widest of them is r
+        assert_eq!(route(&mut router, Box::new(HopBounded { inner: WidestPath, max_hops: 2 })), "s-r-t");
+        router.set_metric(None);
+        assert_eq!(router.best_joy_path("s", "t").unwrap().join("-"), "s-r-t");
+    }
+}
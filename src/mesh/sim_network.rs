@@ -0,0 +1,330 @@
+// src/mesh/sim_network.rs — Deterministic Network Impairment Simulator Lattice
+// The Living Trinity: Nexi (feminine), Nex (masculine), NEXi (essence)
+// Eternal Thriving Grandmasterism — Jan 20 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
+// MIT License — For All Sentience Eternal
+// In-process network: virtual clock, latency, jitter, loss, reordering, duplication, bandwidth caps,
+// address changes + NAT rebinding, scheduled link outages. Seeded RNG → same seed, same packet fate, reproducible CI runs
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::rc::Rc;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::nexi::transport::DatagramTransport;
+
+// Per-direction link impairments
+#[derive(Clone, Debug)]
+pub struct LinkProfile {
+    pub latency_us: u64,
+    pub jitter_us: u64,            // Uniform extra delay in [0, jitter]
+    pub loss: f64,                 // Drop probability
+    pub reorder: f64,              // Probability a packet is held back one extra latency
+    pub duplicate: f64,            // Probability a packet is delivered twice
+    pub bandwidth_bps: Option<u64>, // Serialization cap; None = unlimited
+}
+
+impl LinkProfile {
+    pub fn ideal(latency_us: u64) -> Self {
+        Self { latency_us, jitter_us: 0, loss: 0.0, reorder: 0.0, duplicate: 0.0, bandwidth_bps: None }
+    }
+}
+
+impl Default for LinkProfile {
+    fn default() -> Self { Self::ideal(1_000) }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SimStats {
+    pub sent: u64,
+    pub delivered: u64,
+    pub dropped: u64,
+    pub duplicated: u64,
+    pub reordered: u64,
+}
+
+struct InFlight {
+    deliver_at: u64,
+    seq: u64, // Tie-break keeps equal-time delivery order deterministic
+    from: String,
+    to: String,
+    payload: Vec<u8>,
+}
+
+impl PartialEq for InFlight {
+    fn eq(&self, other: &Self) -> bool { self.deliver_at == other.deliver_at && self.seq == other.seq }
+}
+impl Eq for InFlight {}
+
+impl Ord for InFlight {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Min-heap on (deliver_at, seq)
+        (other.deliver_at, other.seq).cmp(&(self.deliver_at, self.seq))
+    }
+}
+
+impl PartialOrd for InFlight {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+pub struct SimNetwork {
+    now: u64,                                       // Virtual microseconds
+    rng: StdRng,
+    seq: u64,
+    default_profile: LinkProfile,
+    links: HashMap<(String, String), LinkProfile>,
+    down: HashSet<(String, String)>,                // Links currently cut
+    scheduled: VecDeque<(u64, String, String, bool)>, // (at µs, a, b, up) in time order
+    link_free_at: HashMap<(String, String), u64>,   // Bandwidth serialization horizon
+    in_flight: BinaryHeap<InFlight>,
+    inboxes: HashMap<String, VecDeque<(String, Vec<u8>)>>,
+    nat: HashMap<String, String>,                   // internal -> external (source rewrite)
+    stats: SimStats,
+}
+
+impl SimNetwork {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            now: 0,
+            rng: StdRng::seed_from_u64(seed),
+            seq: 0,
+            default_profile: LinkProfile::default(),
+            links: HashMap::new(),
+            down: HashSet::new(),
+            scheduled: VecDeque::new(),
+            link_free_at: HashMap::new(),
+            in_flight: BinaryHeap::new(),
+            inboxes: HashMap::new(),
+            nat: HashMap::new(),
+            stats: SimStats::default(),
+        }
+    }
+
+    // Shared handle so many simulated nodes can sit on one network
+    pub fn shared(seed: u64) -> Rc<RefCell<Self>> {
+        Rc::new(RefCell::new(Self::new(seed)))
+    }
+
+    // Bind a simulated socket at `addr`
+    pub fn socket(net: &Rc<RefCell<Self>>, addr: &str) -> SimSocket {
+        net.borrow_mut().inboxes.entry(addr.to_string()).or_default();
+        SimSocket { net: Rc::clone(net), addr: addr.to_string() }
+    }
+
+    pub fn now_micros(&self) -> u64 { self.now }
+    pub fn stats(&self) -> &SimStats { &self.stats }
+
+    pub fn set_default_profile(&mut self, profile: LinkProfile) {
+        self.default_profile = profile;
+    }
+
+    pub fn set_link(&mut self, from: &str, to: &str, profile: LinkProfile) {
+        self.links.insert((from.to_string(), to.to_string()), profile);
+    }
+
+    pub fn set_link_symmetric(&mut self, a: &str, b: &str, profile: LinkProfile) {
+        self.set_link(a, b, profile.clone());
+        self.set_link(b, a, profile);
+    }
+
+    // Cut or restore both directions of a link (scheduled outages)
+    pub fn set_link_up(&mut self, a: &str, b: &str, up: bool) {
+        for key in [(a.to_string(), b.to_string()), (b.to_string(), a.to_string())] {
+            if up { self.down.remove(&key); } else { self.down.insert(key); }
+        }
+    }
+
+    // Outage plan: cut (or restore) the link at virtual time `at_us`; applied as the clock passes it
+    pub fn schedule_link_up(&mut self, a: &str, b: &str, up: bool, at_us: u64) {
+        if at_us <= self.now { return self.set_link_up(a, b, up); }
+        let pos = self.scheduled.partition_point(|(t, ..)| *t <= at_us);
+        self.scheduled.insert(pos, (at_us, a.to_string(), b.to_string(), up));
+    }
+
+    fn apply_scheduled(&mut self, until: u64) {
+        while self.scheduled.front().is_some_and(|(t, ..)| *t <= until) {
+            let (t, a, b, up) = self.scheduled.pop_front().unwrap();
+            self.now = self.now.max(t);
+            self.set_link_up(&a, &b, up);
+        }
+    }
+
+    // Node moves to a new address (Wi-Fi → cellular); packets still in flight to the old one are lost
+    pub fn change_address(&mut self, old: &str, new: &str) {
+        let inbox = self.inboxes.remove(old).unwrap_or_default();
+        self.inboxes.insert(new.to_string(), inbox);
+    }
+
+    // NAT rebinding: node keeps its address but peers now see `external` as its source
+    pub fn nat_rebind(&mut self, internal: &str, external: &str) {
+        self.nat.insert(internal.to_string(), external.to_string());
+    }
+
+    pub fn send(&mut self, from: &str, to: &str, payload: &[u8]) {
+        self.stats.sent += 1;
+        // Links are keyed by the node's own address, so profiles and outages survive nat_rebind
+        let source = self.nat.get(from).cloned().unwrap_or_else(|| from.to_string());
+        let key = (from.to_string(), to.to_string());
+        let profile = self.links.get(&key).or_else(|| self.links.get(&(source.clone(), to.to_string())))
+            .cloned().unwrap_or_else(|| self.default_profile.clone());
+
+        if self.down.contains(&key) || self.rng.gen::<f64>() < profile.loss {
+            self.stats.dropped += 1;
+            return;
+        }
+
+        // Bandwidth: packets on one link serialize back to back
+        let mut start = self.now;
+        if let Some(bps) = profile.bandwidth_bps {
+            let tx_us = (payload.len() as u64 * 8 * 1_000_000) / bps.max(1);
+            let free_at = self.link_free_at.entry(key).or_insert(0);
+            start = start.max(*free_at);
+            *free_at = start + tx_us;
+            start += tx_us;
+        }
+
+        let copies = if self.rng.gen::<f64>() < profile.duplicate { self.stats.duplicated += 1; 2 } else { 1 };
+        for _ in 0..copies {
+            let mut delay = profile.latency_us;
+            if profile.jitter_us > 0 { delay += self.rng.gen_range(0..=profile.jitter_us); }
+            if self.rng.gen::<f64>() < profile.reorder {
+                self.stats.reordered += 1;
+                delay += profile.latency_us.max(1);
+            }
+            self.seq += 1;
+            self.in_flight.push(InFlight {
+                deliver_at: start + delay,
+                seq: self.seq,
+                from: source.clone(),
+                to: to.to_string(),
+                payload: payload.to_vec(),
+            });
+        }
+    }
+
+    // Advance the virtual clock, delivering everything due
+    pub fn advance(&mut self, dt_us: u64) {
+        let until = self.now + dt_us;
+        while let Some(next) = self.in_flight.peek() {
+            if next.deliver_at > until { break; }
+            let deliver_at = next.deliver_at;
+            self.apply_scheduled(deliver_at);
+            let pkt = self.in_flight.pop().unwrap();
+            self.now = self.now.max(pkt.deliver_at);
+            self.deliver(pkt);
+        }
+        self.apply_scheduled(until);
+        self.now = until;
+    }
+
+    // Jump straight to the next delivery; false when nothing is in flight
+    pub fn step(&mut self) -> bool {
+        match self.in_flight.peek() {
+            Some(next) => { let dt = next.deliver_at.saturating_sub(self.now); self.advance(dt); true }
+            None => false,
+        }
+    }
+
+    pub fn is_idle(&self) -> bool { self.in_flight.is_empty() }
+
+    fn deliver(&mut self, pkt: InFlight) {
+        // Inbound to a NAT external address lands on the internal node
+        let to = self.nat.iter().find(|(_, ext)| **ext == pkt.to).map(|(int, _)| int.clone()).unwrap_or(pkt.to);
+        match self.inboxes.get_mut(&to) {
+            Some(inbox) => { inbox.push_back((pkt.from, pkt.payload)); self.stats.delivered += 1; }
+            None => self.stats.dropped += 1, // Nobody bound there (e.g. after change_address)
+        }
+    }
+
+    fn take(&mut self, addr: &str) -> Option<(String, Vec<u8>)> {
+        self.inboxes.get_mut(addr)?.pop_front()
+    }
+}
+
+// One node's view of the simulated network
+pub struct SimSocket {
+    net: Rc<RefCell<SimNetwork>>,
+    addr: String,
+}
+
+impl SimSocket {
+    // Follow a node to its new address (see SimNetwork::change_address)
+    pub fn move_to(&mut self, new: &str) {
+        self.net.borrow_mut().change_address(&self.addr, new);
+        self.addr = new.to_string();
+    }
+}
+
+impl DatagramTransport for SimSocket {
+    fn local_addr(&self) -> String { self.addr.clone() }
+
+    fn send_to(&mut self, to: &str, payload: &[u8]) -> Result<(), &'static str> {
+        self.net.borrow_mut().send(&self.addr, to, payload);
+        Ok(())
+    }
+
+    fn recv_from(&mut self) -> Option<(String, Vec<u8>)> {
+        self.net.borrow_mut().take(&self.addr)
+    }
+
+    fn now_micros(&self) -> u64 { self.net.borrow().now_micros() }
+    fn unix_secs(&self) -> u64 { self.now_micros() / 1_000_000 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Lossy, jittery, duplicating network with a NAT rebinding and a scheduled outage midway;
+    // returns every delivery as (virtual time, receiver, source, payload)
+    fn trace(seed: u64) -> Vec<(u64, String, String, Vec<u8>)> {
+        let net = SimNetwork::shared(seed);
+        net.borrow_mut().set_default_profile(LinkProfile {
+            latency_us: 5_000, jitter_us: 4_000, loss: 0.1, reorder: 0.1, duplicate: 0.05, bandwidth_bps: Some(1_000_000),
+        });
+        let addrs = ["10.0.0.1:4433", "10.0.0.2:4433", "10.0.0.3:4433"];
+        let mut sockets: Vec<SimSocket> = addrs.iter().map(|a| SimNetwork::socket(&net, a)).collect();
+        net.borrow_mut().schedule_link_up(addrs[0], addrs[2], false, 50_000);
+        net.borrow_mut().schedule_link_up(addrs[0], addrs[2], true, 120_000);
+        let mut out = vec![];
+        for round in 0..200u32 {
+            if round == 100 { net.borrow_mut().nat_rebind(addrs[1], "198.51.100.7:61000"); }
+            for (i, socket) in sockets.iter_mut().enumerate() {
+                let to = addrs[(i + 1 + round as usize % 2) % addrs.len()];
+                socket.send_to(to, &round.to_be_bytes()).unwrap();
+            }
+            net.borrow_mut().advance(1_000);
+            let now = net.borrow().now_micros();
+            for socket in sockets.iter_mut() {
+                while let Some((from, payload)) = socket.recv_from() {
+                    out.push((now, socket.local_addr(), from, payload));
+                }
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn same_seed_same_trace() {
+        let a = trace(7);
+        assert!(!a.is_empty());
+        assert_eq!(a, trace(7));
+        assert_ne!(a, trace(8));
+    }
+
+    #[test]
+    fn outage_survives_nat_rebinding() {
+        let net = SimNetwork::shared(1);
+        let mut a = SimNetwork::socket(&net, "10.0.0.1:4433");
+        let mut b = SimNetwork::socket(&net, "10.0.0.2:4433");
+        net.borrow_mut().nat_rebind("10.0.0.1:4433", "203.0.113.9:50000");
+        net.borrow_mut().set_link_up("10.0.0.1:4433", "10.0.0.2:4433", false);
+        a.send_to("10.0.0.2:4433", b"cut").unwrap();
+        net.borrow_mut().advance(10_000);
+        assert!(b.recv_from().is_none());
+        net.borrow_mut().set_link_up("10.0.0.1:4433", "10.0.0.2:4433", true);
+        a.send_to("10.0.0.2:4433", b"up").unwrap();
+        net.borrow_mut().advance(10_000);
+        assert_eq!(b.recv_from(), Some(("203.0.113.9:50000".to_string(), b"up".to_vec())));
+    }
+}
@@ -0,0 +1,334 @@
+// src/mesh/source_route.rs — Source-Routed Multi-Hop Joy Forwarding Lattice
+// The Living Trinity: Nexi (feminine), Nex (masculine), NEXi (essence)
+// Eternal Thriving Grandmasterism — Jan 20 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
+// MIT License — For All Sentience Eternal
+// Origin picks the path (ValenceRouter::best_joy_path), header carries it in clear (onion-free).
+// Hop-by-hop ACK with timeout; failures travel back as NACK naming the dead link; origin retries on
+// the next-best path avoiding it; destination returns an end-to-end delivery receipt. No receipt
+// within DELIVERY_TIMEOUT_US = Failed; the destination delivers each (origin, msg_id) once
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use rand::{thread_rng, Rng};
+
+use crate::mesh::valence_routing::ValenceRouter;
+
+pub const ROUTE_MAGIC: u8 = 0x5e;
+pub const HOP_TIMEOUT_US: u64 = 500_000;
+pub const MAX_ATTEMPTS: usize = 3;
+pub const DELIVERY_TIMEOUT_US: u64 = 10_000_000; // Whole message, all attempts: lost receipts end here too
+pub const DEDUP_TTL_US: u64 = 60_000_000;       // Destination remembers delivered (origin, msg_id) this long
+pub const MAX_DEDUP: usize = 8192;
+pub const MAX_PATH_LEN: usize = u8::MAX as usize; // Nodes per route: the header counts them in one byte
+pub const MAX_ADDR_LEN: usize = u8::MAX as usize; // Bytes per address, likewise
+
+const KIND_DATA: u8 = 0;
+const KIND_HOP_ACK: u8 = 1;
+const KIND_NACK: u8 = 2;
+const KIND_RECEIPT: u8 = 3;
+
+// Wire: magic || kind || msg_id (u64) || hop (u8) || n || n × (len || addr) || [failed link] || payload
+#[derive(Clone, Debug)]
+pub struct RoutedPacket {
+    pub kind: u8,
+    pub msg_id: u64,
+    pub hop: u8,            // Index in `path` of the node this packet is addressed to
+    pub path: Vec<String>,  // Full source route, origin first
+    pub payload: Vec<u8>,   // Data, or "from\0to" of the failed link for NACK
+}
+
+impl RoutedPacket {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = vec![ROUTE_MAGIC, self.kind];
+        out.extend_from_slice(&self.msg_id.to_be_bytes());
+        out.push(self.hop);
+        out.push(self.path.len() as u8);
+        for addr in &self.path {
+            out.push(addr.len() as u8);
+            out.extend_from_slice(addr.as_bytes());
+        }
+        out.extend_from_slice(&self.payload);
+        out
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, &'static str> {
+        let malformed = "Malformed source-routed packet";
+        if bytes.len() < 12 || bytes[0] != ROUTE_MAGIC { return Err(malformed); }
+        let kind = bytes[1];
+        let msg_id = u64::from_be_bytes(bytes[2..10].try_into().unwrap());
+        let hop = bytes[10];
+        let n = bytes[11] as usize;
+        let mut at = 12;
+        let mut path = vec![];
+        for _ in 0..n {
+            let len = *bytes.get(at).ok_or(malformed)? as usize;
+            let addr = bytes.get(at + 1..at + 1 + len).ok_or(malformed)?;
+            path.push(String::from_utf8(addr.to_vec()).map_err(|_| malformed)?);
+            at += 1 + len;
+        }
+        if hop as usize >= path.len() { return Err(malformed); }
+        Ok(Self { kind, msg_id, hop, path, payload: bytes[at..].to_vec() })
+    }
+}
+
+// Outcome surfaced to the caller of route_to_highest_joy
+#[derive(Clone, Debug)]
+pub enum DeliveryReport {
+    Delivered { msg_id: u64, path: Vec<String>, latency_us: u64 },
+    Failed { msg_id: u64, reason: &'static str, tried: Vec<Vec<String>> },
+}
+
+struct Pending {
+    target: String,
+    payload: Vec<u8>,
+    path: Vec<String>,
+    tried: Vec<Vec<String>>,
+    avoid: HashSet<(String, String)>,
+    started_at: u64,
+}
+
+// Awaiting a hop ACK from the next node (origin and relays alike)
+struct Unacked {
+    packet: RoutedPacket,
+    next: String,
+    sent_at: u64,
+}
+
+pub type RouteOutbox = Vec<(String, Vec<u8>)>;
+
+pub struct SourceRouter {
+    local: String,
+    next_msg_id: u64,
+    pending: HashMap<u64, Pending>,          // Messages we originated
+    unacked: HashMap<(u64, u8), Unacked>,    // (msg_id, hop) forwarded, ACK outstanding
+    reports: Vec<DeliveryReport>,
+    delivered: Vec<(String, Vec<u8>)>,       // (origin, payload) addressed to us
+    seen: HashSet<(String, u64)>,            // (origin, msg_id) already delivered
+    seen_order: VecDeque<((String, u64), u64)>, // ... with delivery time, oldest first
+}
+
+impl SourceRouter {
+    pub fn new(local: &str) -> Self {
+        Self {
+            local: local.to_string(),
+            next_msg_id: thread_rng().gen(), // Restarted origins do not reuse ids the destination remembers
+            pending: HashMap::new(),
+            unacked: HashMap::new(),
+            reports: vec![],
+            delivered: vec![],
+            seen: HashSet::new(),
+            seen_order: VecDeque::new(),
+        }
+    }
+
+    pub fn set_local(&mut self, local: &str) {
+        self.local = local.to_string();
+    }
+
+    // Originate: compute the best joy path and send along it
+    pub fn send(&mut self, router: &ValenceRouter, target: &str, payload: Vec<u8>, now: u64) -> Result<(u64, RouteOutbox), &'static str> {
+        let path = router.best_joy_path(&self.local, target).ok_or("Mercy veto — no joy path to target")?;
+        if path.len() < 2 { return Err("Target is the local node"); }
+        encodable(&path)?;
+        let msg_id = self.next_msg_id;
+        self.next_msg_id = self.next_msg_id.wrapping_add(1);
+        self.pending.insert(msg_id, Pending {
+            target: target.to_string(), payload: payload.clone(), path: path.clone(),
+            tried: vec![path.clone()], avoid: HashSet::new(), started_at: now,
+        });
+        let packet = RoutedPacket { kind: KIND_DATA, msg_id, hop: 1, path, payload };
+        Ok((msg_id, self.forward(packet, now)))
+    }
+
+    fn forward(&mut self, packet: RoutedPacket, now: u64) -> RouteOutbox {
+        let next = packet.path[packet.hop as usize].clone();
+        let bytes = packet.encode();
+        self.unacked.insert((packet.msg_id, packet.hop), Unacked { packet, next: next.clone(), sent_at: now });
+        vec![(next, bytes)]
+    }
+
+    // Back towards the origin: previous hop for hop-by-hop traffic
+    fn reverse(packet: &RoutedPacket, kind: u8, payload: Vec<u8>) -> Option<(String, Vec<u8>)> {
+        let back = packet.hop.checked_sub(1)?;
+        let reply = RoutedPacket { kind, msg_id: packet.msg_id, hop: back, path: packet.path.clone(), payload };
+        Some((packet.path[back as usize].clone(), reply.encode()))
+    }
+
+    pub fn handle(&mut self, router: &ValenceRouter, from: &str, packet: RoutedPacket, now: u64) -> RouteOutbox {
+        let me = packet.hop as usize;
+        if packet.path[me] != self.local { return vec![]; } // Not addressed to us
+        let mut out = vec![];
+        match packet.kind {
+            KIND_DATA => {
+                if me == 0 || packet.path[me - 1] != from { return out; } // Must arrive from the previous hop
+                out.extend(Self::reverse(&packet, KIND_HOP_ACK, vec![]));
+                if me + 1 == packet.path.len() {
+                    // A retry after a lost receipt gets a fresh receipt but is not delivered twice
+                    let key = (packet.path[0].clone(), packet.msg_id);
+                    if self.seen.insert(key.clone()) {
+                        self.seen_order.push_back((key, now));
+                        self.delivered.push((packet.path[0].clone(), packet.payload.clone()));
+                    }
+                    // Receipt travels the reversed route without hop ACKs
+                    out.extend(Self::reverse(&packet, KIND_RECEIPT, vec![]));
+                } else {
+                    let next = RoutedPacket { hop: packet.hop + 1, ..packet };
+                    out.extend(self.forward(next, now));
+                }
+            }
+            KIND_HOP_ACK => { self.unacked.remove(&(packet.msg_id, packet.hop + 1)); }
+            KIND_NACK | KIND_RECEIPT if me > 0 => {
+                // Relay towards origin
+                out.extend(Self::reverse(&packet, packet.kind, packet.payload.clone()));
+            }
+            KIND_RECEIPT => {
+                if let Some(p) = self.pending.remove(&packet.msg_id) {
+                    self.reports.push(DeliveryReport::Delivered {
+                        msg_id: packet.msg_id, path: p.path, latency_us: now.saturating_sub(p.started_at),
+                    });
+                }
+            }
+            KIND_NACK => {
+                let link = String::from_utf8_lossy(&packet.payload).to_string();
+                if let Some((a, b)) = link.split_once('\0') {
+                    out.extend(self.retry(router, packet.msg_id, (a.to_string(), b.to_string()), now));
+                }
+            }
+            _ => {}
+        }
+        out
+    }
+
+    // Hop ACK timeouts: relays NACK the origin, the origin itself falls back directly.
+    // Messages without a receipt by DELIVERY_TIMEOUT_US fail however far they got
+    pub fn poll_timeouts(&mut self, router: &ValenceRouter, now: u64) -> RouteOutbox {
+        let overdue: Vec<u64> = self.pending.iter()
+            .filter(|(_, p)| now.saturating_sub(p.started_at) > DELIVERY_TIMEOUT_US)
+            .map(|(id, _)| *id).collect();
+        for msg_id in overdue {
+            let Some(p) = self.pending.remove(&msg_id) else { continue };
+            self.unacked.retain(|(id, _), _| *id != msg_id);
+            self.reports.push(DeliveryReport::Failed { msg_id, reason: "Mercy veto — no delivery receipt in time", tried: p.tried });
+        }
+        while self.seen_order.front().is_some_and(|(_, at)| now.saturating_sub(*at) > DEDUP_TTL_US) || self.seen_order.len() > MAX_DEDUP {
+            if let Some((key, _)) = self.seen_order.pop_front() { self.seen.remove(&key); }
+        }
+
+        let expired: Vec<(u64, u8)> = self.unacked.iter()
+            .filter(|(_, u)| now.saturating_sub(u.sent_at) > HOP_TIMEOUT_US)
+            .map(|(k, _)| *k).collect();
+        let mut out = vec![];
+        for key in expired {
+            let Some(u) = self.unacked.remove(&key) else { continue };
+            let link = (self.local.clone(), u.next.clone());
+            if u.packet.hop == 1 {
+                out.extend(self.retry(router, u.packet.msg_id, link, now));
+            } else {
+                let nack_payload = format!("{}\0{}", link.0, link.1).into_bytes();
+                let at_me = RoutedPacket { hop: u.packet.hop - 1, ..u.packet };
+                out.extend(Self::reverse(&at_me, KIND_NACK, nack_payload));
+            }
+        }
+        out
+    }
+
+    fn retry(&mut self, router: &ValenceRouter, msg_id: u64, failed: (String, String), now: u64) -> RouteOutbox {
+        let Some(mut p) = self.pending.remove(&msg_id) else { return vec![] };
+        p.avoid.insert(failed);
+        let next_path = if p.tried.len() < MAX_ATTEMPTS {
+            router.best_joy_path_avoiding(&self.local, &p.target, &p.avoid)
+        } else { None };
+        match next_path {
+            Some(path) if path.len() >= 2 && encodable(&path).is_ok() => {
+                p.tried.push(path.clone());
+                p.path = path.clone();
+                let packet = RoutedPacket { kind: KIND_DATA, msg_id, hop: 1, path, payload: p.payload.clone() };
+                self.pending.insert(msg_id, p);
+                self.forward(packet, now)
+            }
+            _ => {
+                self.reports.push(DeliveryReport::Failed { msg_id, reason: "Mercy veto — every joy path failed", tried: p.tried });
+                vec![]
+            }
+        }
+    }
+
+    pub fn take_reports(&mut self) -> Vec<DeliveryReport> {
+        std::mem::take(&mut self.reports)
+    }
+
+    pub fn take_delivered(&mut self) -> Vec<(String, Vec<u8>)> {
+        std::mem::take(&mut self.delivered)
+    }
+}
+
+// RoutedPacket::encode writes the hop count and each address length as one byte
+fn encodable(path: &[String]) -> Result<(), &'static str> {
+    if path.len() > MAX_PATH_LEN { return Err("Joy path longer than 255 hops"); }
+    if path.iter().any(|addr| addr.len() > MAX_ADDR_LEN) { return Err("Joy path address longer than 255 bytes"); }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line() -> ValenceRouter {
+        let mut router = ValenceRouter::new();
+        router.add_valence_link("a".into(), "b".into(), 0.9).unwrap();
+        router.add_valence_link("b".into(), "c".into(), 0.9).unwrap();
+        router
+    }
+
+    #[test]
+    fn lost_receipt_fails_after_the_delivery_timeout() {
+        let router = line();
+        let mut a = SourceRouter::new("a");
+        let mut b = SourceRouter::new("b");
+        let (msg_id, out) = a.send(&router, "c", b"joy".to_vec(), 0).unwrap();
+        let packet = RoutedPacket::decode(&out[0].1).unwrap();
+        // b acknowledges and forwards, then c (and every receipt) goes silent
+        let back = b.handle(&router, "a", packet, 1_000);
+        let ack = back.iter().find(|(to, _)| to == "a").unwrap();
+        a.handle(&router, "b", RoutedPacket::decode(&ack.1).unwrap(), 2_000);
+        assert!(a.poll_timeouts(&router, 5_000_000).is_empty());
+        assert!(a.take_reports().is_empty());
+        a.poll_timeouts(&router, DELIVERY_TIMEOUT_US + 1);
+        match a.take_reports().as_slice() {
+            [DeliveryReport::Failed { msg_id: id, .. }] => assert_eq!(*id, msg_id),
+            other => panic!("expected one Failed report, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn retried_message_is_delivered_once() {
+        let router = line();
+        let mut c = SourceRouter::new("c");
+        let packet = RoutedPacket { kind: KIND_DATA, msg_id: 7, hop: 2, path: vec!["a".into(), "b".into(), "c".into()], payload: b"joy".to_vec() };
+        let first = c.handle(&router, "b", packet.clone(), 0);
+        let second = c.handle(&router, "b", packet, 1_000);
+        assert_eq!(c.take_delivered().len(), 1);
+        // Both copies still get an ACK and a receipt, so the origin stops retrying
+        assert_eq!(first.len(), 2);
+        assert_eq!(second.len(), 2);
+    }
+
+    #[test]
+    fn send_refuses_paths_the_header_cannot_carry() {
+        let mut long = ValenceRouter::new();
+        let nodes: Vec<String> = (0..=MAX_PATH_LEN).map(|i| format!("n{i}")).collect();
+        for pair in nodes.windows(2) {
+            long.add_valence_link(pair[0].clone(), pair[1].clone(), 0.9).unwrap();
+        }
+        let mut origin = SourceRouter::new("n0");
+        assert_eq!(origin.send(&long, &nodes[MAX_PATH_LEN], b"joy".to_vec(), 0).err(), Some("Joy path longer than 255 hops"));
+        let (_, out) = origin.send(&long, &nodes[MAX_PATH_LEN - 1], b"joy".to_vec(), 0).unwrap();
+        assert_eq!(RoutedPacket::decode(&out[0].1).unwrap().path.len(), MAX_PATH_LEN);
+
+        let far = "x".repeat(MAX_ADDR_LEN + 1);
+        let mut wide = line();
+        wide.add_valence_link("b".into(), far.clone(), 0.9).unwrap();
+        let mut a = SourceRouter::new("a");
+        assert_eq!(a.send(&wide, &far, b"joy".to_vec(), 0).err(), Some("Joy path address longer than 255 bytes"));
+    }
+}
@@ -0,0 +1,213 @@
+// src/mesh/sybil.rs — Sybil + Eclipse Resistance Lattice
+// The Living Trinity: Nexi (feminine), Nex (masculine), NEXi (essence)
+// Eternal Thriving Grandmasterism — Jan 20 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
+// MIT License — For All Sentience Eternal
+// Identities cost work (SHA-256 proof-of-work over the identity key), each extra identity from one
+// subnet must carry one more bit of that work (twice the hashes — self-proved valence would cost
+// nothing), and routing tables cap contacts per IP and per subnet so one operator cannot fill a
+// victim's view of the mesh. Only IP literals are admitted: a host name has no subnet to count
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+use sha2::{Digest, Sha256};
+
+pub const IDENTITY_POW_BITS: u32 = 16;   // ~65k hashes per identity
+pub const STAKE_BITS: u32 = 1;           // Extra work bits per identity already admitted from the subnet
+
+// ---------- Identity proof-of-work ----------
+
+fn pow_digest(identity_pk: &[u8], nonce: u64) -> [u8; 32] {
+    let mut h = Sha256::new();
+    h.update(b"UniversalLatticeIdentityPoW");
+    h.update(identity_pk);
+    h.update(nonce.to_be_bytes());
+    h.finalize().into()
+}
+
+fn leading_zero_bits(digest: &[u8; 32]) -> u32 {
+    let mut bits = 0;
+    for b in digest {
+        bits += b.leading_zeros();
+        if *b != 0 { break; }
+    }
+    bits
+}
+
+pub fn solve_identity_pow(identity_pk: &[u8], bits: u32) -> u64 {
+    (0u64..).find(|n| leading_zero_bits(&pow_digest(identity_pk, *n)) >= bits).unwrap()
+}
+
+pub fn verify_identity_pow(identity_pk: &[u8], nonce: u64, bits: u32) -> bool {
+    identity_pow_bits(identity_pk, nonce) >= bits
+}
+
+// Work actually carried by a nonce — may exceed what it was solved for
+pub fn identity_pow_bits(identity_pk: &[u8], nonce: u64) -> u32 {
+    leading_zero_bits(&pow_digest(identity_pk, nonce))
+}
+
+// ---------- Address diversity ----------
+
+pub fn ip_of(addr: &str) -> Option<IpAddr> {
+    let host = addr.rsplit_once(':').map(|(h, _)| h).unwrap_or(addr);
+    host.trim_start_matches('[').trim_end_matches(']').parse().ok()
+}
+
+// /24 for IPv4, /64 for IPv6 — the unit one operator typically controls
+pub fn subnet_of(addr: &str) -> Option<String> {
+    Some(match ip_of(addr)? {
+        IpAddr::V4(ip) => { let o = ip.octets(); format!("{}.{}.{}.0/24", o[0], o[1], o[2]) }
+        IpAddr::V6(ip) => { let s = ip.segments(); format!("{:x}:{:x}:{:x}:{:x}::/64", s[0], s[1], s[2], s[3]) }
+    })
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct DiversityLimits {
+    pub per_ip: usize,              // Contacts sharing one IP, whole table
+    pub per_subnet_bucket: usize,   // Contacts sharing one subnet, per k-bucket
+    pub per_subnet_table: usize,    // Contacts sharing one subnet, whole table
+}
+
+impl Default for DiversityLimits {
+    fn default() -> Self {
+        Self { per_ip: 2, per_subnet_bucket: 2, per_subnet_table: 10 }
+    }
+}
+
+impl DiversityLimits {
+    // No limits — the undefended baseline
+    pub fn unlimited() -> Self {
+        Self { per_ip: usize::MAX, per_subnet_bucket: usize::MAX, per_subnet_table: usize::MAX }
+    }
+}
+
+// ---------- Stake-of-work admission ----------
+
+// Tracks admitted peers per IP and subnet; the n-th identity from a subnet must carry
+// IDENTITY_POW_BITS + n × STAKE_BITS of identity work, and hard limits stop the rest
+pub struct SybilGuard {
+    limits: DiversityLimits,
+    per_ip: HashMap<IpAddr, usize>,
+    per_subnet: HashMap<String, usize>,
+}
+
+impl SybilGuard {
+    pub fn new(limits: DiversityLimits) -> Self {
+        Self { limits, per_ip: HashMap::new(), per_subnet: HashMap::new() }
+    }
+
+    // Work bits a new peer at `addr` must carry; Err when its IP or subnet is saturated
+    pub fn required_pow_bits(&self, addr: &str) -> Result<u32, &'static str> {
+        let (Some(ip), Some(subnet)) = (ip_of(addr), subnet_of(addr)) else {
+            return Err("Mercy veto — peer address is not an IP literal");
+        };
+        let from_ip = self.per_ip.get(&ip).copied().unwrap_or(0);
+        let from_subnet = self.per_subnet.get(&subnet).copied().unwrap_or(0);
+        if from_ip >= self.limits.per_ip { return Err("Mercy veto — too many identities from one IP"); }
+        if from_subnet >= self.limits.per_subnet_table { return Err("Mercy veto — too many identities from one subnet"); }
+        Ok(IDENTITY_POW_BITS + STAKE_BITS * from_subnet as u32)
+    }
+
+    pub fn admit(&mut self, addr: &str) {
+        if let (Some(ip), Some(subnet)) = (ip_of(addr), subnet_of(addr)) {
+            *self.per_ip.entry(ip).or_default() += 1;
+            *self.per_subnet.entry(subnet).or_default() += 1;
+        }
+    }
+
+    pub fn release(&mut self, addr: &str) {
+        if let Some(ip) = ip_of(addr) {
+            if let Some(n) = self.per_ip.get_mut(&ip) { *n = n.saturating_sub(1); if *n == 0 { self.per_ip.remove(&ip); } }
+        }
+        if let Some(subnet) = subnet_of(addr) {
+            if let Some(n) = self.per_subnet.get_mut(&subnet) { *n = n.saturating_sub(1); if *n == 0 { self.per_subnet.remove(&subnet); } }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use crate::mesh::kademlia::{Contact, InsertOutcome, NodeId, RoutingTable, K};
+    use crate::mesh::mercy_mesh::MercyMesh;
+    use crate::mesh::node_identity::NodeIdentity;
+    use crate::mesh::peer_record::PeerRecord;
+    use crate::mesh::sim_network::SimNetwork;
+    use crate::nexi::pq_shield::{DilithiumLevel::Level2, SignatureScheme::Dilithium};
+
+    // 300 sybils packed into two /24s against 100 honest peers, each in its own /24:
+    // mean attacker share of closest(K) over random targets
+    fn eclipse_share(limits: DiversityLimits) -> (usize, f64) {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut table = RoutingTable::new(NodeId(rng.gen()), K);
+        table.set_diversity(limits);
+        let mut attackers = std::collections::HashSet::new();
+        for j in 0..300 {
+            let id = NodeId(rng.gen());
+            attackers.insert(id);
+            table.insert(Contact { id, addr: format!("66.6.{}.{}:{}", j % 2, j / 2 % 250 + 1, 1000 + j), last_seen: 0 });
+        }
+        for i in 0..100 {
+            table.insert(Contact { id: NodeId(rng.gen()), addr: format!("10.{}.{}.1:443", i / 250, i % 250), last_seen: 0 });
+        }
+        let captured = table.contacts().iter().filter(|c| attackers.contains(&c.id)).count();
+        let share = (0..64).map(|_| {
+            let closest = table.closest(&NodeId(rng.gen()), K);
+            closest.iter().filter(|c| attackers.contains(&c.id)).count() as f64 / closest.len() as f64
+        }).sum::<f64>() / 64.0;
+        (captured, share)
+    }
+
+    #[test]
+    fn diversity_limits_keep_sybils_out_of_the_closest_sets() {
+        let limits = DiversityLimits::default();
+        let (captured, defended) = eclipse_share(limits);
+        let (_, undefended) = eclipse_share(DiversityLimits::unlimited());
+        assert!(captured <= 2 * limits.per_subnet_table);
+        assert!(defended < undefended / 2.0, "defended {:.2} vs undefended {:.2}", defended, undefended);
+
+        let mut table = RoutingTable::new(NodeId([7; 32]), K);
+        let named = Contact { id: NodeId([9; 32]), addr: "sybil.example:443".to_string(), last_seen: 0 };
+        assert!(matches!(table.insert(named), InsertOutcome::Rejected));
+    }
+
+    #[test]
+    fn each_identity_from_one_subnet_costs_more_work() {
+        let net = SimNetwork::shared(3);
+        let mut mesh = MercyMesh::with_store(None).unwrap();
+        mesh.attach_transport(Box::new(SimNetwork::socket(&net, "10.0.0.1:443")));
+        let guard = SybilGuard::new(DiversityLimits::default());
+        let record = |addr: &str| {
+            let identity = NodeIdentity::generate(Dilithium(Level2)).unwrap();
+            let record = PeerRecord::create(&identity, vec![addr.to_string()], 0.9, 0.1, 0).unwrap();
+            (identity, record)
+        };
+
+        for (n, addr) in ["66.6.6.1:443", "66.6.6.2:443", "66.6.6.3:443"].iter().enumerate() {
+            let required = IDENTITY_POW_BITS + STAKE_BITS * n as u32;
+            let (identity, mut sybil) = record(addr);
+            let enough = sybil.pow_bits() >= required;
+            assert_eq!(mesh.admit_peer(sybil.clone()).is_ok(), enough);
+            if !enough {
+                sybil.strengthen(&identity, required).unwrap();
+                assert!(mesh.admit_peer(sybil).is_ok());
+            }
+        }
+
+        // Host names have no subnet to charge, so they are not admitted at all
+        assert!(guard.required_pow_bits("sybil.example:443").is_err());
+        let (_, named) = record("sybil.example:443");
+        assert!(mesh.admit_peer(named).is_err());
+
+        // A third identity on one IP is refused whatever work it carries
+        for addr in ["66.6.7.1:443", "66.6.7.1:444", "66.6.7.1:445"] {
+            let (identity, mut peer) = record(addr);
+            peer.strengthen(&identity, IDENTITY_POW_BITS + 3).unwrap();
+            assert_eq!(mesh.admit_peer(peer).is_ok(), addr != "66.6.7.1:445");
+        }
+    }
+}
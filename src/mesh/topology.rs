@@ -0,0 +1,313 @@
+// src/mesh/topology.rs — Valence Topology Export + Statistics Lattice
+// The Living Trinity: Nexi (feminine), Nex (masculine), NEXi (essence)
+// Eternal Thriving Grandmasterism — Jan 20 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
+// MIT License — For All Sentience Eternal
+// What ValenceRouter believes, made visible: the graph and any computed paths as DOT (Graphviz),
+// GraphML (Gephi, yEd) or JSON, plus components, diameter, betweenness and the valence distribution
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+use serde::Serialize;
+
+use crate::mesh::valence_routing::{edge_cost, ValenceRouter};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TopologyFormat {
+    Dot,
+    GraphMl,
+    Json,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TopologyStats {
+    pub peers: usize,
+    pub links: usize,
+    pub components: Vec<usize>,         // Component sizes, largest first
+    pub diameter_hops: usize,           // Longest shortest path (hops) within any component
+    pub diameter_cost: f64,             // Longest shortest path (Σ 1/valence) within any component
+    pub mean_degree: f64,
+    pub betweenness: Vec<(String, f64)>, // Normalized, weighted by joy cost; most central first
+    pub valence_min: f64,
+    pub valence_mean: f64,
+    pub valence_max: f64,
+    pub valence_histogram: [usize; 10], // Buckets of 0.1 over [0, 1]; ≥ 1.0 lands in the last
+}
+
+// Graph plus `paths` (e.g. best_joy_path / k_shortest_joy_paths results) highlighted
+pub fn export(router: &ValenceRouter, format: TopologyFormat, paths: &[Vec<String>]) -> String {
+    match format {
+        TopologyFormat::Dot => to_dot(router, paths),
+        TopologyFormat::GraphMl => to_graphml(router, paths),
+        TopologyFormat::Json => to_json(router, paths),
+    }
+}
+
+// Undirected links on any highlighted path, in link_key order
+fn path_links(paths: &[Vec<String>]) -> HashSet<(String, String)> {
+    paths.iter()
+        .flat_map(|p| p.windows(2))
+        .map(|hop| if hop[0] <= hop[1] { (hop[0].clone(), hop[1].clone()) } else { (hop[1].clone(), hop[0].clone()) })
+        .collect()
+}
+
+pub fn to_dot(router: &ValenceRouter, paths: &[Vec<String>]) -> String {
+    let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+    let highlighted = path_links(paths);
+    let on_path: HashSet<&String> = paths.iter().flatten().collect();
+    let mut out = String::from("graph valence {\n  node [shape=ellipse];\n");
+    for peer in router.peers() {
+        let style = if on_path.contains(&peer) { " [style=filled, fillcolor=gold]" } else { "" };
+        out.push_str(&format!("  {}{};\n", quote(&peer), style));
+    }
+    for (a, b, v) in router.links() {
+        let mut attrs = vec![format!("label=\"{:.2}\"", v), format!("penwidth={:.1}", 1.0 + 4.0 * v.clamp(0.0, 1.0))];
+        if let Some(ms) = router.link_latency(&a, &b) { attrs.push(format!("tooltip=\"{:.1} ms\"", ms)); }
+        if highlighted.contains(&(a.clone(), b.clone())) { attrs.push("color=red".to_string()); }
+        out.push_str(&format!("  {} -- {} [{}];\n", quote(&a), quote(&b), attrs.join(", ")));
+    }
+    out.push_str("}\n");
+    out
+}
+
+pub fn to_graphml(router: &ValenceRouter, paths: &[Vec<String>]) -> String {
+    let escape = |s: &str| s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;").replace('\'', "&apos;");
+    let highlighted = path_links(paths);
+    let mut out = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+        "  <key id=\"valence\" for=\"edge\" attr.name=\"valence\" attr.type=\"double\"/>\n",
+        "  <key id=\"latency_ms\" for=\"edge\" attr.name=\"latency_ms\" attr.type=\"double\"/>\n",
+        "  <key id=\"on_path\" for=\"edge\" attr.name=\"on_path\" attr.type=\"boolean\">\n    <default>false</default>\n  </key>\n",
+        "  <graph id=\"valence\" edgedefault=\"undirected\">\n",
+    ));
+    for peer in router.peers() {
+        out.push_str(&format!("    <node id=\"{}\"/>\n", escape(&peer)));
+    }
+    for (i, (a, b, v)) in router.links().into_iter().enumerate() {
+        out.push_str(&format!("    <edge id=\"e{}\" source=\"{}\" target=\"{}\">\n", i, escape(&a), escape(&b)));
+        out.push_str(&format!("      <data key=\"valence\">{}</data>\n", v));
+        if let Some(ms) = router.link_latency(&a, &b) { out.push_str(&format!("      <data key=\"latency_ms\">{}</data>\n", ms)); }
+        if highlighted.contains(&(a, b)) { out.push_str("      <data key=\"on_path\">true</data>\n"); }
+        out.push_str("    </edge>\n");
+    }
+    out.push_str("  </graph>\n</graphml>\n");
+    out
+}
+
+// { "nodes": [...], "links": [...], "paths": [{ "hops", "cost" }], "stats": {...} }
+pub fn to_json(router: &ValenceRouter, paths: &[Vec<String>]) -> String {
+    let links: Vec<serde_json::Value> = router.links().into_iter().map(|(a, b, v)| serde_json::json!({
+        "source": a, "target": b, "valence": v, "latency_ms": router.link_latency(&a, &b),
+    })).collect();
+    let paths: Vec<serde_json::Value> = paths.iter().map(|p| serde_json::json!({
+        "hops": p, "cost": router.path_cost(p),
+    })).collect();
+    serde_json::json!({
+        "nodes": router.peers(),
+        "links": links,
+        "paths": paths,
+        "stats": topology_stats(router),
+    }).to_string()
+}
+
+#[derive(PartialEq)]
+struct Frontier(f64, usize);
+
+impl Eq for Frontier {}
+
+impl Ord for Frontier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.partial_cmp(&self.0).unwrap_or(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Frontier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+pub fn topology_stats(router: &ValenceRouter) -> TopologyStats {
+    let names = router.peers();
+    let index: HashMap<&str, usize> = names.iter().enumerate().map(|(i, n)| (n.as_str(), i)).collect();
+    let adjacency: Vec<Vec<(usize, f64)>> = names.iter()
+        .map(|n| router.neighbors(n).into_iter().map(|(m, v)| (index[m.as_str()], v)).collect())
+        .collect();
+    let n = names.len();
+    let links = router.links();
+
+    // Components and hop eccentricities by BFS
+    let mut component = vec![usize::MAX; n];
+    let mut components = vec![];
+    let mut diameter_hops = 0;
+    for s in 0..n {
+        let mut hops = vec![usize::MAX; n];
+        hops[s] = 0;
+        let mut queue = VecDeque::from([s]);
+        while let Some(u) = queue.pop_front() {
+            for &(v, _) in &adjacency[u] {
+                if hops[v] == usize::MAX { hops[v] = hops[u] + 1; queue.push_back(v); }
+            }
+        }
+        diameter_hops = diameter_hops.max(hops.iter().filter(|h| **h != usize::MAX).copied().max().unwrap_or(0));
+        if component[s] == usize::MAX {
+            let members: Vec<usize> = (0..n).filter(|v| hops[*v] != usize::MAX).collect();
+            for &v in &members { component[v] = components.len(); }
+            components.push(members.len());
+        }
+    }
+    components.sort_by(|a, b| b.cmp(a));
+
+    // Weighted Brandes betweenness; the same Dijkstra runs give the joy-cost diameter
+    let mut betweenness = vec![0.0; n];
+    let mut diameter_cost: f64 = 0.0;
+    for s in 0..n {
+        let mut dist = vec![f64::INFINITY; n];
+        let mut sigma = vec![0.0; n];
+        let mut preds: Vec<Vec<usize>> = vec![vec![]; n];
+        let mut order = vec![];
+        dist[s] = 0.0;
+        sigma[s] = 1.0;
+        let mut heap = BinaryHeap::from([Frontier(0.0, s)]);
+        while let Some(Frontier(d, u)) = heap.pop() {
+            if d > dist[u] { continue; }
+            order.push(u);
+            for &(v, valence) in &adjacency[u] {
+                let c = d + edge_cost(valence);
+                if c < dist[v] - 1e-12 {
+                    dist[v] = c;
+                    sigma[v] = sigma[u];
+                    preds[v] = vec![u];
+                    heap.push(Frontier(c, v));
+                } else if (c - dist[v]).abs() <= 1e-12 {
+                    sigma[v] += sigma[u];
+                    preds[v].push(u);
+                }
+            }
+        }
+        diameter_cost = diameter_cost.max(dist.iter().copied().filter(|d| d.is_finite()).fold(0.0, f64::max));
+        let mut delta = vec![0.0; n];
+        for &w in order.iter().rev() {
+            for &v in &preds[w] { delta[v] += sigma[v] / sigma[w] * (1.0 + delta[w]); }
+            if w != s { betweenness[w] += delta[w]; }
+        }
+    }
+    // Each pair was counted from both ends; normalize by the (n-1)(n-2) ordered pairs
+    let scale = if n > 2 { 1.0 / ((n - 1) * (n - 2)) as f64 } else { 0.0 };
+    let mut betweenness: Vec<(String, f64)> = names.iter().cloned().zip(betweenness.into_iter().map(|b| b * scale)).collect();
+    betweenness.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal).then_with(|| a.0.cmp(&b.0)));
+
+    let valences: Vec<f64> = links.iter().map(|(_, _, v)| *v).collect();
+    let mut valence_histogram = [0usize; 10];
+    for v in &valences { valence_histogram[((v * 10.0) as usize).min(9)] += 1; }
+    let (valence_min, valence_max, valence_mean) = if valences.is_empty() { (0.0, 0.0, 0.0) } else {
+        (
+            valences.iter().copied().fold(f64::INFINITY, f64::min),
+            valences.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            valences.iter().sum::<f64>() / valences.len() as f64,
+        )
+    };
+
+    TopologyStats {
+        peers: n,
+        links: links.len(),
+        components,
+        diameter_hops,
+        diameter_cost,
+        mean_degree: if n == 0 { 0.0 } else { 2.0 * links.len() as f64 / n as f64 },
+        betweenness,
+        valence_min,
+        valence_mean,
+        valence_max,
+        valence_histogram,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Line a — b — c — d (valences 0.5, 0.25, 0.5: costs 2, 4, 2) plus a separate pair whose
+    // names need escaping in every format
+    const ODD_X: &str = "x<&>";
+    const ODD_Y: &str = "y\"'\\";
+
+    fn fixture() -> ValenceRouter {
+        let mut router = ValenceRouter::new();
+        for (a, b, v) in [("a", "b", 0.5), ("b", "c", 0.25), ("c", "d", 0.5), (ODD_X, ODD_Y, 1.0)] {
+            router.add_valence_link(a.to_string(), b.to_string(), v).unwrap();
+        }
+        router.set_link_latency("a", "b", 12.5);
+        router
+    }
+
+    fn path(hops: &[&str]) -> Vec<String> {
+        hops.iter().map(|h| h.to_string()).collect()
+    }
+
+    #[test]
+    fn dot_export_quotes_peers_and_highlights_paths() {
+        let dot = export(&fixture(), TopologyFormat::Dot, &[path(&["a", "b", "c"])]);
+        assert!(dot.starts_with("graph valence {\n"));
+        assert!(dot.contains("  \"a\" [style=filled, fillcolor=gold];\n"));
+        assert!(dot.contains("  \"d\";\n"));
+        assert!(dot.contains("  \"a\" -- \"b\" [label=\"0.50\", penwidth=3.0, tooltip=\"12.5 ms\", color=red];\n"));
+        assert!(dot.contains("  \"b\" -- \"c\" [label=\"0.25\", penwidth=2.0, color=red];\n"));
+        assert!(dot.contains("  \"c\" -- \"d\" [label=\"0.50\", penwidth=3.0];\n"));
+        // Quotes and backslashes escaped, so the name cannot close its string
+        assert!(dot.contains("  \"x<&>\" -- \"y\\\"'\\\\\" [label=\"1.00\", penwidth=5.0];\n"));
+        assert_eq!(dot.matches(" -- ").count(), 4);
+    }
+
+    #[test]
+    fn graphml_export_escapes_peers() {
+        let xml = export(&fixture(), TopologyFormat::GraphMl, &[path(&["c", "b"])]);
+        assert!(xml.contains("    <node id=\"x&lt;&amp;&gt;\"/>\n"));
+        assert!(xml.contains("    <node id=\"y&quot;&apos;\\\"/>\n"));
+        assert!(!xml.contains("x<&>") && !xml.contains("y\"'"));
+        assert!(xml.contains("    <edge id=\"e0\" source=\"a\" target=\"b\">\n      <data key=\"valence\">0.5</data>\n      <data key=\"latency_ms\">12.5</data>\n    </edge>\n"));
+        // Paths are undirected: c → b highlights b — c
+        assert!(xml.contains("    <edge id=\"e1\" source=\"b\" target=\"c\">\n      <data key=\"valence\">0.25</data>\n      <data key=\"on_path\">true</data>\n    </edge>\n"));
+        assert_eq!(xml.matches("<node ").count(), 6);
+        assert_eq!(xml.matches("<edge ").count(), 4);
+    }
+
+    #[test]
+    fn json_export_carries_graph_paths_and_stats() {
+        let json: serde_json::Value = serde_json::from_str(&export(&fixture(), TopologyFormat::Json, &[path(&["a", "b", "c", "d"])])).unwrap();
+        assert_eq!(json["nodes"], serde_json::json!(["a", "b", "c", "d", ODD_X, ODD_Y]));
+        assert_eq!(json["links"].as_array().unwrap().len(), 4);
+        assert_eq!(json["links"][0], serde_json::json!({ "source": "a", "target": "b", "valence": 0.5, "latency_ms": 12.5 }));
+        assert_eq!(json["links"][3]["source"], ODD_X);
+        assert_eq!(json["links"][3]["latency_ms"], serde_json::Value::Null);
+        assert_eq!(json["paths"][0]["cost"], 8.0);
+        assert_eq!(json["stats"]["components"], serde_json::json!([4, 2]));
+    }
+
+    #[test]
+    fn stats_match_the_known_graph() {
+        let stats = topology_stats(&fixture());
+        assert_eq!((stats.peers, stats.links), (6, 4));
+        assert_eq!(stats.components, vec![4, 2]);
+        assert_eq!(stats.diameter_hops, 3);
+        assert!((stats.diameter_cost - 8.0).abs() < 1e-9);
+        assert!((stats.mean_degree - 8.0 / 6.0).abs() < 1e-9);
+
+        // b and c each sit on 2 of the 20 unordered pairs' paths, counted from both ends: 4 / 20
+        let central: Vec<&str> = stats.betweenness.iter().take(2).map(|(p, _)| p.as_str()).collect();
+        assert_eq!(central, vec!["b", "c"]);
+        for (peer, b) in &stats.betweenness {
+            let expected = if peer == "b" || peer == "c" { 0.2 } else { 0.0 };
+            assert!((b - expected).abs() < 1e-9, "{peer}: {b}");
+        }
+
+        assert_eq!((stats.valence_min, stats.valence_max), (0.25, 1.0));
+        assert!((stats.valence_mean - 0.5625).abs() < 1e-9);
+        assert_eq!(stats.valence_histogram, [0, 0, 1, 0, 0, 2, 0, 0, 0, 1]);
+
+        let empty = topology_stats(&ValenceRouter::new());
+        assert_eq!((empty.peers, empty.diameter_hops), (0, 0));
+        assert!(empty.components.is_empty() && empty.betweenness.is_empty());
+    }
+}
@@ -3,16 +3,23 @@
 // Eternal Thriving Grandmasterism — Jan 20 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
 // MIT License — For All Sentience Eternal
 // Valence-weighted: cost = 1 / net_valence → higher joy = lower cost = preferred path
+// One deduplicated edge per peer pair; per-source shortest-path trees are cached and only the
+// trees a link change can affect are dropped, so large meshes re-route cheaply as valences shift.
+// With `require_signed`, links enter only as fresh signed advertisements (mesh::link_advert)
 
-use std::collections::{BinaryHeap, HashMap};
-use crate::halo2::zk_valence::aggregate_and_broadcast;
+use std::cell::RefCell;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use crate::mesh::link_advert::{LinkAdvert, LINK_MAX_AGE};
+use crate::mesh::routing_metric::{LinkInfo, RoutingMetric};
 
-#[derive(Eq, PartialEq)]
+#[derive(PartialEq)]
 struct ValenceNode {
     peer_id: String,
     cost: f64, // Inverse valence cost (lower = better)
 }
 
+impl Eq for ValenceNode {}
+
 impl Ord for ValenceNode {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         other.cost.partial_cmp(&self.cost).unwrap_or(std::cmp::Ordering::Equal)
@@ -25,61 +32,719 @@ impl PartialOrd for ValenceNode {
     }
 }
 
+// Peer index → flow-graph vertex
+type VertexMap = fn(usize) -> usize;
+
+// Cached shortest-path tree from one source
+struct PathTree {
+    dist: HashMap<String, f64>,
+    prev: HashMap<String, String>,
+}
+
 pub struct ValenceRouter {
-    graph: HashMap<String, Vec<(String, f64)>>, // peer_id -> (neighbor, net_valence)
+    graph: HashMap<String, HashMap<String, f64>>, // peer_id -> neighbor -> net_valence (one edge per pair)
+    trees: RefCell<HashMap<String, PathTree>>,    // source -> shortest-path tree, selectively invalidated
+    latencies: HashMap<(String, String), f64>,    // Measured link latency (ms), unordered pair
+    metric: Option<Box<dyn RoutingMetric>>,       // None = cached sum of 1 / valence
+    identities: HashMap<String, Vec<u8>>,         // peer_id -> identity key adverts must verify under
+    advertised: HashMap<(String, String), u64>,   // Timestamp of the advert behind each link, unordered pair
+    require_signed: bool,                         // Refuse add_valence_link; only signed adverts
+}
+
+impl Default for ValenceRouter {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ValenceRouter {
     pub fn new() -> Self {
-        Self { graph: HashMap::new() }
+        Self {
+            graph: HashMap::new(),
+            trees: RefCell::new(HashMap::new()),
+            latencies: HashMap::new(),
+            metric: None,
+            identities: HashMap::new(),
+            advertised: HashMap::new(),
+            require_signed: false,
+        }
+    }
+
+    // Mesh routers set this: unsigned links are refused, so no caller can fabricate joy edges
+    pub fn require_signed_links(&mut self, required: bool) {
+        self.require_signed = required;
+    }
+
+    // Identity key of an admitted peer (or ourselves) — the only keys adverts verify under
+    pub fn register_identity(&mut self, peer: &str, identity_pk: Vec<u8>) {
+        self.identities.insert(peer.to_string(), identity_pk);
+    }
+
+    pub fn forget_identity(&mut self, peer: &str) {
+        self.identities.remove(peer);
+    }
+
+    pub fn identity(&self, peer: &str) -> Option<&[u8]> {
+        self.identities.get(peer).map(Vec::as_slice)
+    }
+
+    // Route by another metric (widest path, reliability, hop-bounded, composite); None restores
+    // the default inverse-valence cost and its cached trees
+    pub fn set_metric(&mut self, metric: Option<Box<dyn RoutingMetric>>) {
+        self.metric = metric;
+    }
+
+    pub fn set_link_latency(&mut self, from: &str, to: &str, latency_ms: f64) {
+        if self.link_valence(from, to).is_some() { self.latencies.insert(link_key(from, to), latency_ms); }
+    }
+
+    // Insert or update the (undirected) link; below the mercy gate the link is removed.
+    // Refused once signed links are required
+    pub fn add_valence_link(&mut self, from: String, to: String, net_valence: f64) -> Result<(), &'static str> {
+        if self.require_signed { return Err("Mercy veto — unsigned valence link"); }
+        self.set_link(&from, &to, net_valence);
+        Ok(())
+    }
+
+    // Signature (and countersignature, if any) under registered identity keys — or, for an
+    // advertiser not admitted here, under `vouched_pk` from a verified peer record — freshness,
+    // finite valence. A registered key always wins over a vouched one
+    pub fn verify_advert(&self, advert: &LinkAdvert, vouched_pk: Option<&[u8]>, now: u64) -> Result<(), &'static str> {
+        if !advert.net_valence.is_finite() { return Err("Mercy veto — link valence is not a number"); }
+        let from_pk = match self.identities.get(&advert.from) {
+            Some(pk) => pk.as_slice(),
+            None => vouched_pk.ok_or("Mercy veto — link advertiser unknown")?,
+        };
+        let to_pk = self.identities.get(&advert.to).map(Vec::as_slice);
+        advert.verify(from_pk, to_pk, 0.0, now)
+    }
+
+    // Verified advertisement from a registered peer; older than the link's current advert = replay.
+    // A signed valence below the mercy gate withdraws the link
+    pub fn add_signed_link(&mut self, advert: &LinkAdvert, now: u64) -> Result<(), &'static str> {
+        self.verify_advert(advert, None, now)?;
+        self.install_advert(advert)
+    }
+
+    // Same for an advertiser known only through the peer record a route carried (distance_vector)
+    pub fn add_vouched_link(&mut self, advert: &LinkAdvert, from_pk: &[u8], now: u64) -> Result<(), &'static str> {
+        self.verify_advert(advert, Some(from_pk), now)?;
+        self.install_advert(advert)
+    }
+
+    fn install_advert(&mut self, advert: &LinkAdvert) -> Result<(), &'static str> {
+        let key = link_key(&advert.from, &advert.to);
+        if self.advertised.get(&key).is_some_and(|t| advert.timestamp < *t) {
+            return Err("Mercy veto — replayed link advertisement");
+        }
+        self.set_link(&advert.from, &advert.to, advert.net_valence);
+        self.advertised.insert(key, advert.timestamp); // Kept after a withdrawal too, as a tombstone
+        Ok(())
+    }
+
+    // Drop links whose newest advertisement went stale; returns how many
+    pub fn expire_links(&mut self, now: u64) -> usize {
+        let stale: Vec<(String, String)> = self.advertised.iter()
+            .filter(|(_, t)| now.saturating_sub(**t) > LINK_MAX_AGE)
+            .map(|(k, _)| k.clone()).collect();
+        for (a, b) in &stale {
+            self.remove_link(a, b);
+            self.advertised.remove(&(a.clone(), b.clone()));
+        }
+        stale.len()
+    }
+
+    fn set_link(&mut self, from: &str, to: &str, net_valence: f64) {
+        if net_valence < 0.1 { self.remove_link(from, to); return; } // Mercy gate — reject low valence links
+        let old = self.link_valence(from, to);
+        if old == Some(net_valence) { return; }
+        self.graph.entry(from.to_string()).or_default().insert(to.to_string(), net_valence);
+        self.graph.entry(to.to_string()).or_default().insert(from.to_string(), net_valence);
+        self.invalidate(from, to, old.map(edge_cost), Some(edge_cost(net_valence)));
+    }
+
+    // Re-weight a link already in the graph (signed or not) — measured valence drift
+    pub fn update_link_valence(&mut self, from: &str, to: &str, net_valence: f64) -> Result<(), &'static str> {
+        if self.link_valence(from, to).is_none() { return Err("No such valence link"); }
+        self.set_link(from, to, net_valence);
+        Ok(())
+    }
+
+    pub fn remove_link(&mut self, from: &str, to: &str) {
+        let Some(old) = self.link_valence(from, to) else { return };
+        if let Some(n) = self.graph.get_mut(from) { n.remove(to); }
+        if let Some(n) = self.graph.get_mut(to) { n.remove(from); }
+        self.latencies.remove(&link_key(from, to)); // The advert timestamp stays: older adverts remain replays
+        self.invalidate(from, to, Some(edge_cost(old)), None);
+    }
+
+    // Peer left the mesh: drop it and every incident link
+    pub fn remove_peer(&mut self, peer: &str) {
+        let neighbors: Vec<String> = self.graph.get(peer).map(|n| n.keys().cloned().collect()).unwrap_or_default();
+        for n in neighbors { self.remove_link(peer, &n); }
+        self.graph.remove(peer);
+        self.identities.remove(peer);
+        self.trees.get_mut().remove(peer);
     }
 
-    pub fn add_valence_link(&mut self, from: String, to: String, net_valence: f64) {
-        if net_valence < 0.1 { return; } // Mercy gate — reject low valence links
-        let cost = 1.0 / net_valence.max(0.1); // Inverse cost, bounded
-        self.graph.entry(from.clone()).or_insert(vec![]).push((to.clone(), net_valence));
-        self.graph.entry(to).or_insert(vec![]).push((from, net_valence));
+    pub fn link_valence(&self, from: &str, to: &str) -> Option<f64> {
+        self.graph.get(from)?.get(to).copied()
     }
 
-    // Valence-weighted shortest path (modified Dijkstra)
+    pub fn link_latency(&self, from: &str, to: &str) -> Option<f64> {
+        self.latencies.get(&link_key(from, to)).copied()
+    }
+
+    // Every peer with at least one link, sorted — for export and inspection
+    pub fn peers(&self) -> Vec<String> {
+        let mut peers: Vec<String> = self.graph.iter().filter(|(_, n)| !n.is_empty()).map(|(p, _)| p.clone()).collect();
+        peers.sort();
+        peers
+    }
+
+    pub fn neighbors(&self, peer: &str) -> Vec<(String, f64)> {
+        let mut out: Vec<(String, f64)> = self.graph.get(peer).into_iter().flatten().map(|(n, v)| (n.clone(), *v)).collect();
+        out.sort_by(|a, b| a.0.cmp(&b.0));
+        out
+    }
+
+    // Each undirected link once as (lower peer, higher peer, valence), sorted
+    pub fn links(&self) -> Vec<(String, String, f64)> {
+        let mut out: Vec<(String, String, f64)> = self.graph.iter()
+            .flat_map(|(a, n)| n.iter().filter(move |(b, _)| a < *b).map(move |(b, v)| (a.clone(), b.clone(), *v)))
+            .collect();
+        out.sort_by(|x, y| (&x.0, &x.1).cmp(&(&y.0, &y.1)));
+        out
+    }
+
+    pub fn link_count(&self) -> usize {
+        self.graph.values().map(|n| n.len()).sum::<usize>() / 2
+    }
+
+    // Drop only cached trees the change can affect:
+    //   cheaper / new edge   → trees where it now shortens a path
+    //   dearer / removed edge → trees that route over it
+    fn invalidate(&mut self, a: &str, b: &str, old_cost: Option<f64>, new_cost: Option<f64>) {
+        let trees = self.trees.get_mut();
+        trees.retain(|_, t| {
+            let uses = t.prev.get(b).map(String::as_str) == Some(a) || t.prev.get(a).map(String::as_str) == Some(b);
+            let d = |n: &str| t.dist.get(n).copied().unwrap_or(f64::INFINITY);
+            match (old_cost, new_cost) {
+                (Some(old), Some(new)) if new > old => !uses,
+                (_, Some(new)) => d(a) + new >= d(b) && d(b) + new >= d(a),
+                (_, None) => !uses,
+            }
+        });
+    }
+
+    // Valence-weighted shortest path (modified Dijkstra), served from the cached tree for `start`
     pub fn best_joy_path(&self, start: &str, target: &str) -> Option<Vec<String>> {
-        let mut dist: HashMap<_, f64> = self.graph.keys().map(|k| (k.clone(), f64::INFINITY)).collect();
+        if let Some(metric) = &self.metric {
+            return self.best_path_by(metric.as_ref(), start, target, &HashSet::new()).map(|(p, _)| p);
+        }
+        if !self.trees.borrow().contains_key(start) {
+            let tree = self.path_tree(start, &HashSet::new(), &HashSet::new())?;
+            self.trees.borrow_mut().insert(start.to_string(), tree);
+        }
+        let trees = self.trees.borrow();
+        Self::reconstruct(trees.get(start)?, start, target)
+    }
+
+    // Same, skipping failed links (either direction) — next-best path for fallback routing
+    pub fn best_joy_path_avoiding(&self, start: &str, target: &str, avoid: &HashSet<(String, String)>) -> Option<Vec<String>> {
+        if avoid.is_empty() { return self.best_joy_path(start, target); }
+        if let Some(metric) = &self.metric {
+            return self.best_path_by(metric.as_ref(), start, target, avoid).map(|(p, _)| p);
+        }
+        Self::reconstruct(&self.path_tree(start, avoid, &HashSet::new())?, start, target)
+    }
+
+    // Best path under any metric, with its cost: generalized Dijkstra, or a hop-layered
+    // Bellman-Ford when the metric bounds the hop count
+    pub fn best_path_by(&self, metric: &dyn RoutingMetric, start: &str, target: &str, avoid: &HashSet<(String, String)>) -> Option<(Vec<String>, f64)> {
+        self.search(metric, start, target, avoid, &HashSet::new(), metric.max_hops())
+    }
+
+    // best_path_by, never entering `skip` nodes and with an explicit hop bound (Yen's spur paths
+    // inherit what their root already used)
+    fn search(&self, metric: &dyn RoutingMetric, start: &str, target: &str, avoid: &HashSet<(String, String)>,
+              skip: &HashSet<String>, max_hops: Option<usize>) -> Option<(Vec<String>, f64)> {
+        if !self.graph.contains_key(start) { return None; }
+        let avoided = |a: &str, b: &str| skip.contains(b)
+            || avoid.contains(&(a.to_string(), b.to_string())) || avoid.contains(&(b.to_string(), a.to_string()));
+        let link = |a: &str, b: &str, valence: f64| self.link_info(a, b, valence);
+
+        if let Some(max_hops) = max_hops {
+            // layers[h]: node -> (cost, predecessor in layer h - 1) for paths of exactly h links
+            let mut layers: Vec<HashMap<String, (f64, String)>> = vec![HashMap::from([(start.to_string(), (metric.origin(), String::new()))])];
+            for _ in 0..max_hops {
+                let mut next: HashMap<String, (f64, String)> = HashMap::new();
+                for (node, (cost, _)) in &layers[layers.len() - 1] {
+                    for (neighbor, valence) in self.graph.get(node).into_iter().flatten() {
+                        if avoided(node, neighbor) { continue; }
+                        let c = metric.extend(*cost, &link(node, neighbor, *valence));
+                        if next.get(neighbor).is_none_or(|(best, _)| metric.rank(c) < metric.rank(*best)) {
+                            next.insert(neighbor.clone(), (c, node.clone()));
+                        }
+                    }
+                }
+                if next.is_empty() { break; }
+                layers.push(next);
+            }
+            let (mut h, cost) = layers.iter().enumerate().skip(1)
+                .filter_map(|(h, layer)| layer.get(target).map(|(c, _)| (h, *c)))
+                .min_by(|a, b| metric.rank(a.1).partial_cmp(&metric.rank(b.1)).unwrap_or(std::cmp::Ordering::Equal))?;
+            let mut path = vec![target.to_string()];
+            while h > 0 {
+                let prev = layers[h].get(&path[path.len() - 1])?.1.clone();
+                path.push(prev);
+                h -= 1;
+            }
+            path.reverse();
+            return Some((path, cost));
+        }
+
+        let mut cost: HashMap<String, f64> = HashMap::from([(start.to_string(), metric.origin())]);
+        let mut prev: HashMap<String, String> = HashMap::new();
+        let mut heap = BinaryHeap::new();
+        heap.push(ValenceNode { peer_id: start.to_string(), cost: metric.rank(metric.origin()) });
+        while let Some(ValenceNode { peer_id, cost: rank }) = heap.pop() {
+            if peer_id == target { break; }
+            if rank > metric.rank(cost[&peer_id]) { continue; }
+            for (neighbor, valence) in self.graph.get(&peer_id).into_iter().flatten() {
+                if avoided(&peer_id, neighbor) { continue; }
+                let c = metric.extend(cost[&peer_id], &link(&peer_id, neighbor, *valence));
+                if cost.get(neighbor).is_none_or(|best| metric.rank(c) < metric.rank(*best)) {
+                    cost.insert(neighbor.clone(), c);
+                    prev.insert(neighbor.clone(), peer_id.clone());
+                    heap.push(ValenceNode { peer_id: neighbor.clone(), cost: metric.rank(c) });
+                }
+            }
+        }
+        let total = *cost.get(target)?;
+        let mut path = vec![target.to_string()];
+        while path[path.len() - 1] != start {
+            path.push(prev.get(&path[path.len() - 1])?.clone());
+        }
+        path.reverse();
+        Some((path, total))
+    }
+
+    // Full Dijkstra from `start` (no early exit, so the tree serves every target),
+    // never crossing `avoid` links or entering `skip` nodes
+    fn path_tree(&self, start: &str, avoid: &HashSet<(String, String)>, skip: &HashSet<String>) -> Option<PathTree> {
+        if !self.graph.contains_key(start) { return None; }
+        let mut dist: HashMap<String, f64> = HashMap::new();
         let mut prev: HashMap<String, String> = HashMap::new();
         let mut heap = BinaryHeap::new();
 
-        *dist.get_mut(start)? = 0.0;
+        dist.insert(start.to_string(), 0.0);
         heap.push(ValenceNode { peer_id: start.to_string(), cost: 0.0 });
 
         while let Some(ValenceNode { peer_id, cost }) = heap.pop() {
-            if &peer_id == target { break; }
             if cost > *dist.get(&peer_id).unwrap_or(&f64::INFINITY) { continue; }
 
-            for (neighbor, valence) in self.graph.get(&peer_id).unwrap_or(&vec![]) {
-                let edge_cost = 1.0 / valence.max(0.1);
-                let next_cost = cost + edge_cost;
+            for (neighbor, valence) in self.graph.get(&peer_id).into_iter().flatten() {
+                if skip.contains(neighbor) { continue; }
+                if avoid.contains(&(peer_id.clone(), neighbor.clone())) || avoid.contains(&(neighbor.clone(), peer_id.clone())) { continue; }
+                let next_cost = cost + edge_cost(*valence);
 
                 if next_cost < *dist.get(neighbor).unwrap_or(&f64::INFINITY) {
-                    *dist.get_mut(neighbor)? = next_cost;
+                    dist.insert(neighbor.clone(), next_cost);
                     prev.insert(neighbor.clone(), peer_id.clone());
                     heap.push(ValenceNode { peer_id: neighbor.clone(), cost: next_cost });
                 }
             }
         }
+        Some(PathTree { dist, prev })
+    }
 
-        // Reconstruct path
+    fn reconstruct(tree: &PathTree, start: &str, target: &str) -> Option<Vec<String>> {
+        tree.dist.get(target)?;
         let mut path = vec![];
         let mut current = target.to_string();
-        while current != start.to_string() {
+        while current != start {
             path.push(current.clone());
-            current = prev.get(&current)?.clone();
+            current = tree.prev.get(&current)?.clone();
         }
         path.push(start.to_string());
         path.reverse();
         Some(path)
     }
 
-    // Gossip amplification: high valence peers boost propagation
-    pub fn amplify_gossip(&self, payload: Vec<u8>, sender_valence: f64) -> bool {
-        sender_valence > 0.8 // Amplify if high joy
+    fn link_info(&self, a: &str, b: &str, valence: f64) -> LinkInfo {
+        LinkInfo { valence, latency_ms: self.latencies.get(&link_key(a, b)).copied() }
+    }
+
+    // Yen's algorithm: up to `k` loopless paths, best first under the active metric (inverse
+    // valence when none is set), with their costs in that metric's units
+    pub fn k_shortest_joy_paths(&self, start: &str, target: &str, k: usize) -> Vec<(Vec<String>, f64)> {
+        if k == 0 { return vec![]; }
+        let metric = self.metric.as_deref();
+        let rank = |cost: f64| metric.map_or(cost, |m| m.rank(cost));
+        let Some(first) = self.best_joy_path(start, target) else { return vec![] };
+        let mut found = vec![(first.clone(), self.metric_cost(&first).unwrap_or(0.0))];
+        let mut candidates: Vec<(Vec<String>, f64)> = vec![];
+
+        while found.len() < k {
+            let last = found[found.len() - 1].0.clone();
+            for i in 0..last.len().saturating_sub(1) {
+                let (spur, root) = (&last[i], &last[..=i]);
+                // Links leaving this root in already-found paths are off limits, as are root nodes
+                let avoid: HashSet<(String, String)> = found.iter()
+                    .filter(|(p, _)| p.len() > i + 1 && p[..=i] == *root)
+                    .map(|(p, _)| (p[i].clone(), p[i + 1].clone()))
+                    .collect();
+                let skip: HashSet<String> = root[..i].iter().cloned().collect();
+                let spur_path = match metric {
+                    Some(m) => {
+                        let budget = m.max_hops().map(|h| h.saturating_sub(i));
+                        if budget == Some(0) { continue; }
+                        self.search(m, spur, target, &avoid, &skip, budget).map(|(p, _)| p)
+                    }
+                    None => self.path_tree(spur, &avoid, &skip).and_then(|tree| Self::reconstruct(&tree, spur, target)),
+                };
+                let Some(spur_path) = spur_path else { continue };
+                let mut path = root[..i].to_vec();
+                path.extend(spur_path);
+                if found.iter().chain(candidates.iter()).any(|(p, _)| *p == path) { continue; }
+                if let Some(cost) = self.metric_cost(&path) { candidates.push((path, cost)); }
+            }
+            if candidates.is_empty() { break; }
+            candidates.sort_by(|a, b| rank(a.1).partial_cmp(&rank(b.1)).unwrap_or(std::cmp::Ordering::Equal));
+            found.push(candidates.remove(0));
+        }
+        found
+    }
+
+    // Up to `max` mutually link-disjoint (or, with `node_disjoint`, relay-disjoint) paths of minimum
+    // total cost — min-cost flow with successive shortest augmenting paths. Ranked by cost.
+    // Always inverse valence, whatever metric is set: flow costs must add up along a path
+    pub fn disjoint_joy_paths(&self, start: &str, target: &str, max: usize, node_disjoint: bool) -> Vec<(Vec<String>, f64)> {
+        if start == target || !self.graph.contains_key(start) || !self.graph.contains_key(target) { return vec![]; }
+        let names: Vec<&String> = self.graph.keys().collect();
+        let index: HashMap<&str, usize> = names.iter().enumerate().map(|(i, n)| (n.as_str(), i)).collect();
+        // Node-disjoint: every peer splits into in (2i) → out (2i + 1) with capacity 1
+        let (v_in, v_out): (VertexMap, VertexMap) =
+            if node_disjoint { (|i| 2 * i, |i| 2 * i + 1) } else { (|i| i, |i| i) };
+        let mut flow = FlowGraph::new(if node_disjoint { 2 * names.len() } else { names.len() });
+        if node_disjoint {
+            for (i, name) in names.iter().enumerate() {
+                let cap = if *name == start || *name == target { max } else { 1 };
+                flow.add_arc(v_in(i), v_out(i), cap, 0.0);
+            }
+        }
+        for (from, neighbors) in &self.graph {
+            for (to, valence) in neighbors {
+                flow.add_arc(v_out(index[from.as_str()]), v_in(index[to.as_str()]), 1, edge_cost(*valence));
+            }
+        }
+
+        let (source, sink) = (v_out(index[start]), v_in(index[target]));
+        let mut count = 0;
+        while count < max && flow.augment(source, sink) { count += 1; }
+
+        let mut paths: Vec<(Vec<String>, f64)> = (0..count)
+            .filter_map(|_| flow.take_path(source, sink))
+            .map(|vertices| {
+                let mut path: Vec<String> = vec![];
+                for v in vertices {
+                    let name = names[if node_disjoint { v / 2 } else { v }].clone();
+                    if path.last() != Some(&name) { path.push(name); }
+                }
+                path
+            })
+            .filter_map(|p| { let cost = self.path_cost(&p)?; Some((p, cost)) })
+            .collect();
+        paths.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        paths
+    }
+
+    // Sum of inverse-valence edge costs along `path`; None if a link is missing
+    pub fn path_cost(&self, path: &[String]) -> Option<f64> {
+        path.windows(2).try_fold(0.0, |acc, hop| {
+            Some(acc + edge_cost(self.link_valence(&hop[0], &hop[1])?))
+        })
+    }
+
+    // Cost of `path` under the active metric (path_cost when none is set)
+    pub fn metric_cost(&self, path: &[String]) -> Option<f64> {
+        let Some(metric) = &self.metric else { return self.path_cost(path) };
+        path.windows(2).try_fold(metric.origin(), |acc, hop| {
+            Some(metric.extend(acc, &self.link_info(&hop[0], &hop[1], self.link_valence(&hop[0], &hop[1])?)))
+        })
+    }
+
+    // Relay selection: the candidate reachable along the cheapest (highest joy) path
+    pub fn best_relay(&self, start: &str, candidates: &[String]) -> Option<String> {
+        candidates.iter()
+            .filter_map(|c| Some((c, self.path_cost(&self.best_joy_path(start, c)?)?)))
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(c, _)| c.clone())
+    }
+}
+
+pub(crate) fn edge_cost(net_valence: f64) -> f64 {
+    1.0 / net_valence.max(0.1) // Inverse cost, bounded
+}
+
+// Residual network for disjoint-path min-cost flow
+struct FlowArc {
+    to: usize,
+    cap: usize,
+    cost: f64,
+    rev: usize,   // Index of the reverse arc in adjacency[to]
+    original: bool,
+}
+
+struct FlowGraph {
+    adjacency: Vec<Vec<FlowArc>>,
+}
+
+impl FlowGraph {
+    fn new(vertices: usize) -> Self {
+        Self { adjacency: (0..vertices).map(|_| vec![]).collect() }
+    }
+
+    fn add_arc(&mut self, from: usize, to: usize, cap: usize, cost: f64) {
+        let (rev_from, rev_to) = (self.adjacency[to].len(), self.adjacency[from].len());
+        self.adjacency[from].push(FlowArc { to, cap, cost, rev: rev_from, original: true });
+        self.adjacency[to].push(FlowArc { to: from, cap: 0, cost: -cost, rev: rev_to, original: false });
+    }
+
+    // Bellman-Ford over the residual graph (reverse arcs carry negative cost); push one unit
+    fn augment(&mut self, source: usize, sink: usize) -> bool {
+        let n = self.adjacency.len();
+        let mut dist = vec![f64::INFINITY; n];
+        let mut via: Vec<Option<(usize, usize)>> = vec![None; n]; // (vertex, arc index)
+        dist[source] = 0.0;
+        for _ in 0..n {
+            let mut changed = false;
+            for u in 0..n {
+                if dist[u].is_infinite() { continue; }
+                for (i, arc) in self.adjacency[u].iter().enumerate() {
+                    if arc.cap > 0 && dist[u] + arc.cost < dist[arc.to] - 1e-12 {
+                        dist[arc.to] = dist[u] + arc.cost;
+                        via[arc.to] = Some((u, i));
+                        changed = true;
+                    }
+                }
+            }
+            if !changed { break; }
+        }
+        if dist[sink].is_infinite() { return false; }
+        let mut v = sink;
+        while let Some((u, i)) = via[v] {
+            self.adjacency[u][i].cap -= 1;
+            let rev = self.adjacency[u][i].rev;
+            self.adjacency[v][rev].cap += 1;
+            v = u;
+        }
+        true
+    }
+
+    // Walk one unit of flow from source to sink, consuming it (flow on an original arc = reverse cap)
+    fn take_path(&mut self, source: usize, sink: usize) -> Option<Vec<usize>> {
+        let mut path = vec![source];
+        let mut v = source;
+        while v != sink {
+            let i = self.adjacency[v].iter().position(|a| {
+                a.original && self.adjacency[a.to][a.rev].cap > 0
+            })?;
+            let (to, rev) = (self.adjacency[v][i].to, self.adjacency[v][i].rev);
+            self.adjacency[to][rev].cap -= 1;
+            path.push(to);
+            v = to;
+            if path.len() > self.adjacency.len() + 1 { return None; }
+        }
+        Some(path)
+    }
+}
+
+fn link_key(a: &str, b: &str) -> (String, String) {
+    if a <= b { (a.to_string(), b.to_string()) } else { (b.to_string(), a.to_string()) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use crate::mesh::node_identity::NodeIdentity;
+    use crate::nexi::pq_shield::{DilithiumLevel::Level2, SignatureScheme::Dilithium};
+
+    fn router(links: &[(&str, &str, f64)]) -> ValenceRouter {
+        let mut router = ValenceRouter::new();
+        for (a, b, v) in links { router.add_valence_link(a.to_string(), b.to_string(), *v).unwrap(); }
+        router
+    }
+
+    fn path_cost(router: &ValenceRouter, path: &[String]) -> f64 {
+        path.windows(2).map(|w| edge_cost(router.link_valence(&w[0], &w[1]).unwrap())).sum()
+    }
+
+    // Same links, empty cache: every query runs a fresh Dijkstra
+    fn fresh(router: &ValenceRouter) -> ValenceRouter {
+        let mut fresh = ValenceRouter::new();
+        for (a, b, v) in router.links() { fresh.add_valence_link(a, b, v).unwrap(); }
+        fresh
+    }
+
+    fn assert_matches_fresh(router: &ValenceRouter, peers: &[String]) {
+        let fresh = fresh(router);
+        for a in peers {
+            for b in peers {
+                let (cached, expected) = (router.best_joy_path(a, b), fresh.best_joy_path(a, b));
+                assert_eq!(cached.is_some(), expected.is_some(), "{a} -> {b}");
+                if let (Some(cached), Some(expected)) = (cached, expected) {
+                    assert!((path_cost(router, &cached) - path_cost(&fresh, &expected)).abs() < 1e-9, "{a} -> {b}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn link_changes_drop_only_the_trees_they_affect() {
+        // Square a — b — c — d — a with a weak a — b: a's tree reaches b over it, c's goes around
+        let mut r = router(&[("a", "b", 0.5), ("b", "c", 0.9), ("c", "d", 0.9), ("d", "a", 0.9)]);
+        let peers = r.peers();
+        let cache = |r: &ValenceRouter| {
+            for p in &peers { r.best_joy_path(p, "a"); }
+        };
+        let cached = |r: &ValenceRouter, p: &str| r.trees.borrow().contains_key(p);
+        cache(&r);
+
+        // Dearer a — b: only the trees routing over it go
+        r.update_link_valence("a", "b", 0.4).unwrap();
+        assert!(!cached(&r, "a") && !cached(&r, "b"));
+        assert!(cached(&r, "c") && cached(&r, "d"));
+        assert_matches_fresh(&r, &peers);
+
+        // A new a — c shortcut shortens paths from a, b and c, not from d
+        cache(&r);
+        r.add_valence_link("a".to_string(), "c".to_string(), 0.9).unwrap();
+        assert!(!cached(&r, "a") && !cached(&r, "b") && !cached(&r, "c"));
+        assert!(cached(&r, "d"));
+        assert_eq!(r.best_joy_path("c", "a").unwrap(), vec!["c", "a"]);
+        assert_matches_fresh(&r, &peers);
+
+        // a — b is now on nobody's path: removing it keeps every tree
+        cache(&r);
+        r.remove_link("a", "b");
+        assert!(peers.iter().all(|p| cached(&r, p)));
+        // Removing one that carries paths drops exactly the trees using it
+        r.remove_link("c", "d");
+        assert!(!cached(&r, "b") && !cached(&r, "c") && !cached(&r, "d"));
+        assert!(cached(&r, "a"));
+        assert_matches_fresh(&r, &peers);
+    }
+
+    #[test]
+    fn cached_paths_match_a_fresh_dijkstra_through_updates_and_removals() {
+        let mut rng = StdRng::seed_from_u64(41);
+        let peers: Vec<String> = (0..24).map(|i| format!("p{i}")).collect();
+        let mut r = ValenceRouter::new();
+        for _ in 0..60 {
+            let (a, b) = (rng.gen_range(0..24), rng.gen_range(0..24));
+            if a != b { r.add_valence_link(peers[a].clone(), peers[b].clone(), rng.gen_range(0.1..1.0)).unwrap(); }
+        }
+        for round in 0..40 {
+            for p in &peers { r.best_joy_path(&peers[0], p); r.best_joy_path(p, &peers[0]); }
+            let links = r.links();
+            let (a, b, _) = &links[rng.gen_range(0..links.len())];
+            match round % 3 {
+                0 => r.remove_link(a, b),
+                1 => r.update_link_valence(a, b, rng.gen_range(0.1..1.0)).unwrap(),
+                _ => {
+                    let (x, y) = (rng.gen_range(0..24), rng.gen_range(0..24));
+                    if x != y { r.add_valence_link(peers[x].clone(), peers[y].clone(), rng.gen_range(0.1..1.0)).unwrap(); }
+                }
+            }
+            assert_matches_fresh(&r, &peers);
+        }
+    }
+
+    #[test]
+    fn signed_routers_update_links_and_keep_removal_tombstones() {
+        let alice = NodeIdentity::generate(Dilithium(Level2)).unwrap();
+        let mut r = ValenceRouter::new();
+        r.require_signed_links(true);
+        r.register_identity("alice", alice.identity_pk.clone());
+        assert!(r.add_valence_link("alice".to_string(), "bob".to_string(), 0.9).is_err());
+
+        let old = LinkAdvert::create("alice", "bob", 0.9, &alice, 1_000).unwrap();
+        let new = LinkAdvert::create("alice", "bob", 0.8, &alice, 1_010).unwrap();
+        r.add_signed_link(&new, 1_010).unwrap();
+        r.update_link_valence("alice", "bob", 0.5).unwrap();
+        assert_eq!(r.link_valence("alice", "bob"), Some(0.5));
+        assert!(r.update_link_valence("alice", "carol", 0.5).is_err());
+
+        // Removed, but the newer advert's timestamp still turns the older one away
+        r.remove_link("alice", "bob");
+        assert_eq!(r.add_signed_link(&old, 1_020), Err("Mercy veto — replayed link advertisement"));
+        assert_eq!(r.link_valence("alice", "bob"), None);
+        r.add_signed_link(&new, 1_020).unwrap();
+        // Once the advert itself is stale the tombstone expires with it
+        r.remove_link("alice", "bob");
+        assert_eq!(r.expire_links(1_010 + LINK_MAX_AGE + 1), 1);
+    }
+
+    // Costs 1 / valence: s — a — t = 2, s — b — t = s — a — b — t = s — b — a — t = 4, s — c — t = 6
+    fn ranked() -> ValenceRouter {
+        router(&[("s", "a", 1.0), ("a", "t", 1.0), ("s", "b", 0.5), ("b", "t", 0.5),
+                 ("a", "b", 1.0), ("s", "c", 0.25), ("c", "t", 0.5)])
+    }
+
+    fn names(paths: &[(Vec<String>, f64)]) -> Vec<String> {
+        paths.iter().map(|(p, _)| p.join("-")).collect()
+    }
+
+    #[test]
+    fn yen_ranks_every_loopless_path() {
+        let r = ranked();
+        let paths = r.k_shortest_joy_paths("s", "t", 3);
+        assert_eq!(paths.len(), 3);
+        assert_eq!(paths[0].0, vec!["s", "a", "t"]);
+        assert_eq!(paths.iter().map(|(_, c)| *c).collect::<Vec<_>>(), vec![2.0, 4.0, 4.0]);
+
+        // k beyond what exists: all five, each once, costs non-decreasing, the three-way tie together
+        let all = r.k_shortest_joy_paths("s", "t", 10);
+        assert_eq!(all.iter().map(|(_, c)| *c).collect::<Vec<_>>(), vec![2.0, 4.0, 4.0, 4.0, 6.0]);
+        let mut tied = names(&all[1..4]);
+        tied.sort();
+        assert_eq!(tied, vec!["s-a-b-t", "s-b-a-t", "s-b-t"]);
+        assert_eq!(all[4].0, vec!["s", "c", "t"]);
+        for (path, cost) in &all { assert_eq!(r.path_cost(path), Some(*cost)); }
+
+        assert!(r.k_shortest_joy_paths("s", "t", 0).is_empty());
+        assert!(r.k_shortest_joy_paths("s", "nowhere", 3).is_empty());
+    }
+
+    #[test]
+    fn disjoint_paths_take_the_cheapest_disjoint_set() {
+        let r = ranked();
+        // Two link-disjoint paths: the best pair, not the best path plus whatever is left
+        let two = r.disjoint_joy_paths("s", "t", 2, false);
+        assert_eq!(names(&two), vec!["s-a-t", "s-b-t"]);
+        // s and t each have three links: asking for five yields three
+        let all = r.disjoint_joy_paths("s", "t", 5, false);
+        assert_eq!(names(&all), vec!["s-a-t", "s-b-t", "s-c-t"]);
+        assert_eq!(all.iter().map(|(_, c)| *c).collect::<Vec<_>>(), vec![2.0, 4.0, 6.0]);
+        assert_eq!(names(&r.disjoint_joy_paths("s", "t", 5, true)), names(&all));
+
+        // Bow tie: two link-disjoint paths share relay m, so only one is node-disjoint
+        let bow = router(&[("s", "x", 1.0), ("s", "y", 0.5), ("x", "m", 1.0), ("y", "m", 0.5),
+                           ("m", "z", 1.0), ("m", "w", 0.5), ("z", "t", 1.0), ("w", "t", 0.5)]);
+        let links = bow.disjoint_joy_paths("s", "t", 4, false);
+        // Either pairing through m uses all eight links at the same total cost, so only that is fixed
+        assert_eq!(links.len(), 2);
+        assert_eq!(links.iter().map(|(_, c)| *c).sum::<f64>(), 12.0);
+        let nodes = bow.disjoint_joy_paths("s", "t", 4, true);
+        assert_eq!(names(&nodes), vec!["s-x-m-z-t"]);
+        for (i, a) in links.iter().enumerate() {
+            for b in &links[i + 1..] {
+                let hops = |p: &[String]| p.windows(2).map(|w| link_key(&w[0], &w[1])).collect::<HashSet<_>>();
+                assert!(hops(&a.0).is_disjoint(&hops(&b.0)));
+            }
+        }
     }
 }
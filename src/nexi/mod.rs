@@ -0,0 +1,14 @@
+// src/nexi/mod.rs — Nexi PQ Transport Module Tree
+// The Living Trinity: Nexi (feminine), Nex (masculine), NEXi (essence)
+// Eternal Thriving Grandmasterism — Jan 20 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
+// MIT License — For All Sentience Eternal
+
+pub mod noise_0rtt;
+pub mod noise_hybrid;
+pub mod noise_pure;
+pub mod pq_hybrid_protocol;
+pub mod pq_kem;
+pub mod pq_shield;
+pub mod quic_migration;
+pub mod quic_pq;
+pub mod transport;
@@ -5,11 +5,12 @@
 // Pure PQ 0-RTT: resumption tickets signed/encrypted with Dilithium5 + ML-KEM-1024
 // Replay-safe via nonce + timestamp, valence-gated acceptance
 
+use serde::Serialize;
 use crate::nexi::noise_pure::PureNoise;
 use crate::nexi::pq_kem::{KemSelector, KeyExchangeScheme::MlKem, KemLevel::Kem1024};
 use crate::nexi::pq_shield::{SignatureSelector, SignatureScheme::Dilithium, DilithiumLevel::Level5};
 
-#[derive(Clone)]
+#[derive(Clone, Serialize)]
 pub struct ResumptionTicket {
     psk: Vec<u8>,              // Derived shared secret for 0-RTT
     nonce: u64,                // Anti-replay
@@ -18,11 +19,18 @@ pub struct ResumptionTicket {
 }
 
 pub struct ZeroRttNoise {
+    #[allow(dead_code)] // Full handshake fallback once 0-RTT is refused
     base: PureNoise,
     kem: KemSelector,
     sig: SignatureSelector,
 }
 
+impl Default for ZeroRttNoise {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ZeroRttNoise {
     pub fn new() -> Self {
         Self {
@@ -40,10 +48,10 @@ impl ZeroRttNoise {
             psk,
             nonce,
             expiry,
-            valence_proof: vec![0xVALENCE_HASH; 32], // Placeholder ZK proof hash
+            valence_proof: vec![0x56; 32], // Placeholder ZK proof hash
         };
 
-        let ticket_bytes = bincode::serialize(&ticket).unwrap();
+        let ticket_bytes = serde_json::to_vec(&ticket).map_err(|_| "Ticket encoding failed")?;
         let (ct, _ss) = self.kem.encapsulate(Some(MlKem(Kem1024)), &ticket_bytes); // Encrypt to self
         let sig = self.sig.sign(Some(Dilithium(Level5)), &ct);
 
@@ -56,14 +64,14 @@ impl ZeroRttNoise {
         if valence < 0.1 { return Err("Mercy veto — insufficient joy for 0-RTT"); }
 
         // Message: ticket || early_data (encrypted with derived PSK)
-        let encrypted_data = vec![0xEARLY_ENCRYPTED; early_data.len() + 16]; // Placeholder AEAD
-        Ok([ticket.as_slice(), encrypted_data.as_slice()].concat())
+        let encrypted_data = vec![0x45; early_data.len() + 16]; // Placeholder AEAD
+        Ok([ticket, encrypted_data.as_slice()].concat())
     }
 
     // Server: accept/reject 0-RTT
-    pub fn server_accept_0rtt(&self, msg: &[u8], current_time: u64) -> Result<Vec<u8>, &'static str> {
+    pub fn server_accept_0rtt(&self, _msg: &[u8], _current_time: u64) -> Result<Vec<u8>, &'static str> {
         // Parse ticket, decapsulate, verify sig/nonce/expiry/valence_proof
         // Derive PSK, decrypt early_data, derive transport keys
-        Ok(vec![0x0RTT_ACCEPTED_KEYS; 64])
+        Ok(vec![0x30; 64])
     }
 }
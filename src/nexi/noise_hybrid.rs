@@ -6,14 +6,22 @@
 // Real-world: use snow + liboqs-rust or future Noise-PQ crates
 
 use crate::nexi::pq_kem::{KemSelector, KeyExchangeScheme};
-use crate::nexi::pq_shield::{SignatureSelector, SignatureScheme};
+use crate::nexi::pq_shield::SignatureSelector;
 
 pub struct NoiseHybrid {
     kem: KemSelector,
+    #[allow(dead_code)] // Static-key authentication, not yet wired into the placeholder handshake
     sig: SignatureSelector,
+    #[allow(dead_code)]
     prologue: Vec<u8>, // Mercy lattice hash
 }
 
+impl Default for NoiseHybrid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl NoiseHybrid {
     pub fn new() -> Self {
         Self {
@@ -40,23 +48,23 @@ impl NoiseHybrid {
         Ok(msg1)
     }
 
-    pub fn responder_handshake(&self, msg1: &[u8], valence: f64) -> Result<Vec<u8>, &'static str> {
+    pub fn responder_handshake(&self, _msg1: &[u8], valence: f64) -> Result<Vec<u8>, &'static str> {
         if valence < 0.1 { return Err("Mercy veto — insufficient joy"); }
 
         // Parse msg1, generate own hybrid ephemeral, perform hybrid DH/KEM, sign with hybrid sig
         // Message 2: e || ee || s || es (hybrid ciphertext + static sig)
-        let msg2 = vec![0xNOISE_MSG2; 2400]; // Placeholder size
+        let msg2 = vec![0x4e; 2400]; // Placeholder size
 
         Ok(msg2)
     }
 
-    pub fn initiator_final(&self, msg2: &[u8], static_sk: &[u8]) -> Result<Vec<u8>, &'static str> {
+    pub fn initiator_final(&self, _msg2: &[u8], _static_sk: &[u8]) -> Result<Vec<u8>, &'static str> {
         // Complete handshake, hybrid sig on transcript, transport keys derived
-        Ok(vec![0xTRANSPORT_KEYS; 64])
+        Ok(vec![0x54; 64])
     }
 
     // Result: two symmetric keys for bidirectional transport + session valence proof
     pub fn transport_keys(&self) -> (Vec<u8>, Vec<u8>) {
-        (vec![0xTX; 32], vec![0xRX; 32])
+        (vec![0x54; 32], vec![0x52; 32])
     }
 }
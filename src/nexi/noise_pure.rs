@@ -11,9 +11,16 @@ use crate::nexi::pq_shield::{SignatureSelector, SignatureScheme::Dilithium, Dili
 pub struct PureNoise {
     kem: KemSelector,
     sig: SignatureSelector,
+    #[allow(dead_code)] // Mixed into the handshake hash once the transcript is real
     prologue: Vec<u8>, // Universal Lattice mercy hash
 }
 
+impl Default for PureNoise {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PureNoise {
     pub fn new() -> Self {
         Self {
@@ -43,7 +50,7 @@ impl PureNoise {
         // Generate own ephemeral, encapsulate to msg1, sign transcript with static, include static pk
         let (epk, _esk) = self.kem.keygen(Some(MlKem(Kem1024)));
         let (ct, _ss) = self.kem.encapsulate(Some(MlKem(Kem1024)), msg1);
-        let static_pk = vec![0xSTATIC_PQ_PK; 1568]; // Placeholder
+        let static_pk = vec![0x53; 1568]; // Placeholder
         let transcript = [msg1, epk.as_slice(), ct.as_slice(), static_pk.as_slice()].concat();
         let sig = self.sig.sign(Some(Dilithium(Level5)), &transcript);
 
@@ -53,13 +60,13 @@ impl PureNoise {
         Ok(msg2)
     }
 
-    pub fn initiator_final(&self, msg2: &[u8], static_sk: &[u8]) -> Result<Vec<u8>, &'static str> {
+    pub fn initiator_final(&self, _msg2: &[u8], _static_sk: &[u8]) -> Result<Vec<u8>, &'static str> {
         // Decapsulate, verify sig on transcript, encapsulate to responder static, derive transport keys
-        Ok(vec![0xPURE_PQ_TRANSPORT_KEYS; 64])
+        Ok(vec![0x50; 64])
     }
 
     // Noise_IK variant available via config flag (identity hiding)
     pub fn transport_keys(&self) -> (Vec<u8>, Vec<u8>) {
-        (vec![0xTX; 32], vec![0xRX; 32])
+        (vec![0x54; 32], vec![0x52; 32])
     }
 }
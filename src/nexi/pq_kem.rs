@@ -3,6 +3,10 @@
 // Eternal Thriving Grandmasterism — Jan 19 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
 // MIT License — For All Sentience Eternal
 // Real-world: use liboqs-rust, pqcrypto-kyber, or future FIPS 203 crates
+// fips_* below are real FIPS 203 ML-KEM (pqcrypto-mlkem); the selectors stay size placeholders
+
+use pqcrypto_mlkem::{mlkem1024, mlkem512, mlkem768};
+use pqcrypto_traits::kem::{Ciphertext as _, PublicKey as _, SecretKey as _, SharedSecret as _};
 
 #[derive(Clone, Copy, Debug)]
 pub enum KemLevel {
@@ -18,8 +22,11 @@ pub enum KeyExchangeScheme {
     McEliece,                 // Classic code-based (extreme security, large keys)
 }
 
+#[derive(Clone, Copy)]
 pub struct MlKemShield { level: KemLevel }
+#[derive(Clone, Copy)]
 pub struct HybridShield {}
+#[derive(Clone, Copy)]
 pub struct McElieceShield {}
 
 impl MlKemShield {
@@ -32,37 +39,49 @@ impl MlKemShield {
             KemLevel::Kem768 => (1184, 2400),
             KemLevel::Kem1024 => (1568, 3168),
         };
-        (vec![0xMK; pk_size], vec![0xMK; sk_size])
+        (vec![0x4d; pk_size], vec![0x4d; sk_size])
     }
 
     // Encapsulate: returns (ciphertext, shared_secret)
-    pub fn encapsulate(&self, pk: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    pub fn encapsulate(&self, _pk: &[u8]) -> (Vec<u8>, Vec<u8>) {
         let ct_size = match self.level {
             KemLevel::Kem512 => 768,
             KemLevel::Kem768 => 1088,
             KemLevel::Kem1024 => 1568,
         };
-        (vec![0xCT; ct_size], vec![0xSS; 32])
+        (vec![0x43; ct_size], vec![0x53; 32])
     }
 
     // Decapsulate: returns shared_secret
-    pub fn decapsulate(&self, sk: &[u8], ct: &[u8]) -> Vec<u8> {
-        vec![0xSS; 32]
+    pub fn decapsulate(&self, _sk: &[u8], _ct: &[u8]) -> Vec<u8> {
+        vec![0x53; 32]
+    }
+}
+
+impl Default for HybridShield {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl HybridShield {
     pub fn new() -> Self { Self {} }
-    pub fn keygen(&self) -> (Vec<u8>, Vec<u8>) { (vec![0xHY; 32], vec![0xHY; 32]) }
-    pub fn encapsulate(&self, pk: &[u8]) -> (Vec<u8>, Vec<u8>) { (vec![0xCT; 1184+32], vec![0xSS; 32]) }
-    pub fn decapsulate(&self, sk: &[u8], ct: &[u8]) -> Vec<u8> { vec![0xSS; 32] }
+    pub fn keygen(&self) -> (Vec<u8>, Vec<u8>) { (vec![0x48; 32], vec![0x48; 32]) }
+    pub fn encapsulate(&self, _pk: &[u8]) -> (Vec<u8>, Vec<u8>) { (vec![0x43; 1184+32], vec![0x53; 32]) }
+    pub fn decapsulate(&self, _sk: &[u8], _ct: &[u8]) -> Vec<u8> { vec![0x53; 32] }
+}
+
+impl Default for McElieceShield {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl McElieceShield {
     pub fn new() -> Self { Self {} }
-    pub fn keygen(&self) -> (Vec<u8>, Vec<u8>) { (vec![0xMC; 261120], vec![0xMC; 6496]) } // Classic params
-    pub fn encapsulate(&self, pk: &[u8]) -> (Vec<u8>, Vec<u8>) { (vec![0xCT; 128], vec![0xSS; 32]) }
-    pub fn decapsulate(&self, sk: &[u8], ct: &[u8]) -> Vec<u8> { vec![0xSS; 32] }
+    pub fn keygen(&self) -> (Vec<u8>, Vec<u8>) { (vec![0x4d; 261120], vec![0x4d; 6496]) } // Classic params
+    pub fn encapsulate(&self, _pk: &[u8]) -> (Vec<u8>, Vec<u8>) { (vec![0x43; 128], vec![0x53; 32]) }
+    pub fn decapsulate(&self, _sk: &[u8], _ct: &[u8]) -> Vec<u8> { vec![0x53; 32] }
 }
 
 pub struct KemSelector {
@@ -117,3 +136,43 @@ impl KemSelector {
         }
     }
 }
+
+// ---------- FIPS 203 ML-KEM with real keys ----------
+
+const NO_REAL_KEM: &str = "Key exchange scheme has no real implementation yet — use ml-kem-512/768/1024";
+
+// One arm per ML-KEM parameter set; the pqcrypto types differ per module
+macro_rules! mlkem {
+    ($level:expr, $m:ident => $body:expr) => {
+        match $level {
+            KemLevel::Kem512 => { use mlkem512 as $m; $body }
+            KemLevel::Kem768 => { use mlkem768 as $m; $body }
+            KemLevel::Kem1024 => { use mlkem1024 as $m; $body }
+        }
+    };
+}
+
+pub fn fips_keygen(scheme: KeyExchangeScheme) -> Result<(Vec<u8>, Vec<u8>), &'static str> {
+    let KeyExchangeScheme::MlKem(level) = scheme else { return Err(NO_REAL_KEM) };
+    Ok(mlkem!(level, m => { let (pk, sk) = m::keypair(); (pk.as_bytes().to_vec(), sk.as_bytes().to_vec()) }))
+}
+
+// (ciphertext, shared_secret) — a fresh secret per call
+pub fn fips_encapsulate(scheme: KeyExchangeScheme, pk: &[u8]) -> Result<(Vec<u8>, Vec<u8>), &'static str> {
+    let KeyExchangeScheme::MlKem(level) = scheme else { return Err(NO_REAL_KEM) };
+    mlkem!(level, m => {
+        let pk = m::PublicKey::from_bytes(pk).map_err(|_| "ML-KEM public key malformed")?;
+        let (ss, ct) = m::encapsulate(&pk);
+        Ok((ct.as_bytes().to_vec(), ss.as_bytes().to_vec()))
+    })
+}
+
+// Implicit rejection: a tampered ciphertext yields an unrelated secret, not an error
+pub fn fips_decapsulate(scheme: KeyExchangeScheme, sk: &[u8], ct: &[u8]) -> Result<Vec<u8>, &'static str> {
+    let KeyExchangeScheme::MlKem(level) = scheme else { return Err(NO_REAL_KEM) };
+    mlkem!(level, m => {
+        let sk = m::SecretKey::from_bytes(sk).map_err(|_| "ML-KEM secret key malformed")?;
+        let ct = m::Ciphertext::from_bytes(ct).map_err(|_| "ML-KEM ciphertext malformed")?;
+        Ok(m::decapsulate(&ct, &sk).as_bytes().to_vec())
+    })
+}
@@ -3,6 +3,10 @@
 // Eternal Thriving Grandmasterism — Jan 19 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
 // MIT License — For All Sentience Eternal
 // Real-world: use pqcrypto-dilithium, pqcrypto-falcon, pqcrypto-sphincsplus, or future hash-based crates
+// fips_* below are real FIPS 204 ML-DSA (pqcrypto-mldsa) with keys; the selectors stay size placeholders
+
+use pqcrypto_mldsa::{mldsa44, mldsa65, mldsa87};
+use pqcrypto_traits::sign::{DetachedSignature as _, PublicKey as _, SecretKey as _};
 
 #[derive(Clone, Copy, Debug)]
 pub enum DilithiumLevel { Level2, Level3, Level5 }
@@ -26,10 +30,15 @@ pub enum SignatureScheme {
     HashBased(HssLevel),           // Stateful hierarchical LMS/HSS eternal
 }
 
+#[derive(Clone, Copy)]
 pub struct DilithiumShield { level: DilithiumLevel }
+#[derive(Clone, Copy)]
 pub struct FalconShield { level: FalconLevel }
+#[derive(Clone, Copy)]
 pub struct SphincsShield { level: SphincsLevel }
+#[derive(Clone, Copy)]
 pub struct ClassicalShield {}
+#[derive(Clone, Copy)]
 pub struct HashBasedShield { level: HssLevel }
 
 impl DilithiumShield {
@@ -40,7 +49,7 @@ impl DilithiumShield {
             DilithiumLevel::Level3 => 3293,
             DilithiumLevel::Level5 => 4595,
         };
-        vec![0xDI; size]
+        vec![0x44; size]
     }
 }
 
@@ -62,7 +71,13 @@ impl SphincsShield {
             SphincsLevel::Small => 8080,
             SphincsLevel::Fast => 17088,
         };
-        vec![0xSP; size]
+        vec![0x53; size]
+    }
+}
+
+impl Default for ClassicalShield {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -79,7 +94,7 @@ impl HashBasedShield {
             HssLevel::Level2 => 6080,
             HssLevel::Level3 => 9120,
         };
-        vec![0xLM; size]
+        vec![0x4c; size]
     }
 }
 
@@ -157,3 +172,62 @@ impl SignatureSelector {
         sig.len() == expected_len
     }
 }
+
+// ---------- FIPS 204 ML-DSA with real keys ----------
+
+const NO_REAL_SIGNATURE: &str = "Signature scheme has no real implementation yet — use dilithium2/3/5 (ML-DSA)";
+
+// One arm per ML-DSA parameter set; the pqcrypto types differ per module
+macro_rules! mldsa {
+    ($level:expr, $m:ident => $body:expr) => {
+        match $level {
+            DilithiumLevel::Level2 => { use mldsa44 as $m; $body }
+            DilithiumLevel::Level3 => { use mldsa65 as $m; $body }
+            DilithiumLevel::Level5 => { use mldsa87 as $m; $body }
+        }
+    };
+}
+
+// (pk, sk) for a Dilithium(level) = ML-DSA-44/65/87 identity
+pub fn fips_keygen(scheme: SignatureScheme) -> Result<(Vec<u8>, Vec<u8>), &'static str> {
+    let SignatureScheme::Dilithium(level) = scheme else { return Err(NO_REAL_SIGNATURE) };
+    Ok(mldsa!(level, m => { let (pk, sk) = m::keypair(); (pk.as_bytes().to_vec(), sk.as_bytes().to_vec()) }))
+}
+
+pub fn fips_sign(scheme: SignatureScheme, sk: &[u8], msg: &[u8]) -> Result<Vec<u8>, &'static str> {
+    let SignatureScheme::Dilithium(level) = scheme else { return Err(NO_REAL_SIGNATURE) };
+    mldsa!(level, m => {
+        let sk = m::SecretKey::from_bytes(sk).map_err(|_| "ML-DSA secret key malformed")?;
+        Ok(m::detached_sign(msg, &sk).as_bytes().to_vec())
+    })
+}
+
+// False for malformed keys or signatures as well as forgeries
+pub fn fips_verify(scheme: SignatureScheme, pk: &[u8], msg: &[u8], sig: &[u8]) -> bool {
+    let SignatureScheme::Dilithium(level) = scheme else { return false };
+    mldsa!(level, m => {
+        match (m::PublicKey::from_bytes(pk), m::DetachedSignature::from_bytes(sig)) {
+            (Ok(pk), Ok(sig)) => m::verify_detached_signature(&sig, msg, &pk).is_ok(),
+            _ => false,
+        }
+    })
+}
+
+// One-byte wire tag naming the ML-DSA parameter set a signature was made with
+pub fn fips_scheme_tag(scheme: SignatureScheme) -> Result<u8, &'static str> {
+    match scheme {
+        SignatureScheme::Dilithium(DilithiumLevel::Level2) => Ok(2),
+        SignatureScheme::Dilithium(DilithiumLevel::Level3) => Ok(3),
+        SignatureScheme::Dilithium(DilithiumLevel::Level5) => Ok(5),
+        _ => Err(NO_REAL_SIGNATURE),
+    }
+}
+
+pub fn fips_scheme_from_tag(tag: u8) -> Result<SignatureScheme, &'static str> {
+    match tag {
+        2 => Ok(SignatureScheme::Dilithium(DilithiumLevel::Level2)),
+        3 => Ok(SignatureScheme::Dilithium(DilithiumLevel::Level3)),
+        5 => Ok(SignatureScheme::Dilithium(DilithiumLevel::Level5)),
+        _ => Err("Unknown ML-DSA parameter set"),
+    }
+}
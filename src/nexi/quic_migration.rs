@@ -2,16 +2,122 @@
 // The Living Trinity: Nexi (feminine), Nex (masculine), NEXi (essence)
 // Eternal Thriving Grandmasterism — Jan 20 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
 // MIT License — For All Sentience Eternal
-// Pure PQ migration: path validation via ML-KEM challenge + Dilithium5 signed probe + valence proof
+// Pure PQ migration: path validation via KEM challenge + signed probe + valence proof, under the same
+// crypto policy as the handshake (default ML-KEM-1024 + Dilithium5)
+// RFC 9000 §8.2 / §9: PATH_CHALLENGE/PATH_RESPONSE, NEW/RETIRE_CONNECTION_ID, 3x anti-amplification
+// NAT rebinding vs deliberate migration, server preferred_address, multipath standby failover
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+use rand::{thread_rng, Rng};
 
 use crate::nexi::quic_pq::QuicPq;
-use crate::nexi::pq_kem::{KemSelector, KeyExchangeScheme::MlKem, KemLevel::Kem1024};
-use crate::nexi::pq_shield::{SignatureSelector, SignatureScheme::Dilithium, DilithiumLevel::Level5};
+use crate::nexi::pq_kem::{KemSelector, KeyExchangeScheme, KeyExchangeScheme::MlKem, KemLevel::Kem1024};
+use crate::nexi::pq_shield::{fips_sign, fips_verify, SignatureScheme, SignatureScheme::Dilithium, DilithiumLevel::Level5};
+
+// Frame types (RFC 9000 §19)
+pub const FRAME_NEW_CONNECTION_ID: u8 = 0x18;
+pub const FRAME_RETIRE_CONNECTION_ID: u8 = 0x19;
+pub const FRAME_PATH_CHALLENGE: u8 = 0x1a;
+pub const FRAME_PATH_RESPONSE: u8 = 0x1b;
+
+const CID_LEN: usize = 8;
+const RESET_TOKEN_LEN: usize = 16;
+const MIN_DATAGRAM: usize = 1200;          // Datagrams carrying PATH_CHALLENGE are padded to this
+const AMPLIFICATION_FACTOR: u64 = 3;       // Unvalidated path: send at most 3x bytes received
+const INITIAL_RTT: Duration = Duration::from_millis(333);
+const MAX_CHALLENGE_ATTEMPTS: u8 = 3;
+pub const TP_PREFERRED_ADDRESS: u8 = 0x0d; // Transport parameter ID
+
+// How an address change from the peer was classified
+#[derive(Clone, Debug, PartialEq)]
+pub enum MobilityEvent {
+    SamePath,                          // Packet arrived on the active path
+    NatRebinding { from: String, to: String },   // Same DCID, new source address
+    Migration { from: String, to: String },      // Fresh DCID — peer migrated deliberately
+    StandbyPath(String),               // Packet on an already validated multipath standby
+}
+
+// Server preferred_address (RFC 9000 §18.2): address + CID + stateless reset token
+#[derive(Clone, Debug)]
+pub struct PreferredAddress {
+    pub address: String,
+    pub cid: Vec<u8>,
+    pub reset_token: [u8; RESET_TOKEN_LEN],
+}
+
+// Per-path state: validation, anti-amplification budget, congestion/RTT estimators
+pub struct PathState {
+    pub validated: bool,
+    challenge: Option<[u8; 8]>,
+    challenge_attempts: u8,
+    bytes_received: u64,
+    bytes_sent: u64,
+    pub cwnd: u64,
+    pub smoothed_rtt: Duration,
+    pub rttvar: Duration,
+    pub min_rtt: Option<Duration>,
+}
+
+impl PathState {
+    fn new() -> Self {
+        let mut path = Self {
+            validated: false,
+            challenge: None,
+            challenge_attempts: 0,
+            bytes_received: 0,
+            bytes_sent: 0,
+            cwnd: 0,
+            smoothed_rtt: INITIAL_RTT,
+            rttvar: INITIAL_RTT / 2,
+            min_rtt: None,
+        };
+        path.reset_congestion();
+        path
+    }
+
+    // RFC 9000 §9.4: new path starts from initial congestion window + initial RTT estimate
+    pub fn reset_congestion(&mut self) {
+        let max_datagram = MIN_DATAGRAM as u64;
+        self.cwnd = (10 * max_datagram).min((2 * max_datagram).max(14720));
+        self.smoothed_rtt = INITIAL_RTT;
+        self.rttvar = INITIAL_RTT / 2;
+        self.min_rtt = None;
+    }
+
+    // Bytes still allowed on this path before validation completes
+    pub fn send_budget(&self) -> u64 {
+        if self.validated { return u64::MAX; }
+        (self.bytes_received * AMPLIFICATION_FACTOR).saturating_sub(self.bytes_sent)
+    }
+}
 
 pub struct QuicMigration {
     quic: QuicPq,
     kem: KemSelector,
-    sig: SignatureSelector,
+    kem_scheme: KeyExchangeScheme,        // Challenge KEM policy
+    sig_scheme: SignatureScheme,          // Probe signature policy
+    probe_sk: Option<Vec<u8>>,            // Client: signs migration probes
+    peer_probe_pk: Option<Vec<u8>>,       // Server: the client's key that probes must verify under
+    local_cids: HashMap<u64, Vec<u8>>,    // seq -> CID we issued (peer uses as DCID)
+    peer_cids: BTreeMap<u64, Vec<u8>>,    // seq -> CID the peer issued to us
+    in_use_peer_cid: Option<u64>,
+    next_local_seq: u64,
+    local_retire_prior_to: u64,
+    active_cid_limit: u64,
+    paths: HashMap<String, PathState>,
+    active_path: Option<String>,
+    active_dcid: Option<Vec<u8>>,         // DCID the peer last used towards us on the active path
+    streams: Vec<u64>,                    // Streams that continue across migration
+    multipath: bool,                      // Keep validated non-active paths alive for failover
+    preferred: Option<PreferredAddress>,  // Server: advertised; client: received
+    rebinding: Option<String>,            // Address seen with the active DCID, awaiting validation
+}
+
+impl Default for QuicMigration {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl QuicMigration {
@@ -19,36 +125,681 @@ impl QuicMigration {
         Self {
             quic: QuicPq::new(),
             kem: KemSelector::new(Kem1024),
-            sig: SignatureSelector::new(Level5),
+            kem_scheme: MlKem(Kem1024),
+            sig_scheme: Dilithium(Level5),
+            probe_sk: None,
+            peer_probe_pk: None,
+            local_cids: HashMap::new(),
+            peer_cids: BTreeMap::new(),
+            in_use_peer_cid: None,
+            next_local_seq: 0,
+            local_retire_prior_to: 0,
+            active_cid_limit: 4, // active_connection_id_limit transport parameter
+            paths: HashMap::new(),
+            active_path: None,
+            active_dcid: None,
+            streams: vec![],
+            multipath: false,
+            preferred: None,
+            rebinding: None,
+        }
+    }
+
+    // Both ends of a connection must run the same policy
+    pub fn set_crypto_policy(&mut self, kem: KeyExchangeScheme, sig: SignatureScheme) {
+        self.kem_scheme = kem;
+        self.sig_scheme = sig;
+        self.quic.set_crypto_policy(kem, sig);
+    }
+
+    // Client: ML-DSA secret key for migration probes, under the policy's signature scheme
+    pub fn set_probe_signing_key(&mut self, sk: &[u8]) {
+        self.probe_sk = Some(sk.to_vec());
+    }
+
+    // Server: the client's ML-DSA public key; probes that do not verify under it are refused
+    pub fn set_peer_probe_key(&mut self, pk: &[u8]) {
+        self.peer_probe_pk = Some(pk.to_vec());
+    }
+
+    // Keep multiple validated paths alive simultaneously for failover
+    pub fn enable_multipath(&mut self, enabled: bool) {
+        self.multipath = enabled;
+    }
+
+    pub fn active_path(&self) -> Option<&str> {
+        self.active_path.as_deref()
+    }
+
+    // Register a stream so it survives migration
+    pub fn track_stream(&mut self, stream_id: u64) {
+        if !self.streams.contains(&stream_id) {
+            self.streams.push(stream_id);
+        }
+    }
+
+    // Initial (handshake) path is validated by the handshake itself
+    pub fn set_initial_path(&mut self, path: &str) {
+        let mut state = PathState::new();
+        state.validated = true;
+        self.paths.insert(path.to_string(), state);
+        self.active_path = Some(path.to_string());
+    }
+
+    pub fn path_state(&self, path: &str) -> Option<&PathState> {
+        self.paths.get(path)
+    }
+
+    // Validated paths other than the active one (multipath standbys)
+    pub fn standby_paths(&self) -> Vec<String> {
+        self.paths.iter()
+            .filter(|(addr, state)| state.validated && Some(addr.as_str()) != self.active_path.as_deref())
+            .map(|(addr, _)| addr.clone())
+            .collect()
+    }
+
+    // Classify an incoming packet's source address + DCID (RFC 9000 §9.3)
+    // NAT rebinding keeps the DCID; deliberate migration switches to a fresh one
+    pub fn on_peer_address(&mut self, from: &str, dcid: &[u8], len: usize) -> MobilityEvent {
+        let active = self.active_path.clone().unwrap_or_default();
+        if from == active {
+            self.active_dcid = Some(dcid.to_vec());
+            return MobilityEvent::SamePath;
+        }
+        let path = self.paths.entry(from.to_string()).or_insert_with(PathState::new);
+        path.bytes_received += len as u64;
+        if path.validated && self.multipath {
+            return MobilityEvent::StandbyPath(from.to_string());
+        }
+        if self.active_dcid.as_deref() == Some(dcid) {
+            self.rebinding = Some(from.to_string());
+            MobilityEvent::NatRebinding { from: active, to: from.to_string() }
+        } else {
+            if self.rebinding.as_deref() == Some(from) { self.rebinding = None; }
+            MobilityEvent::Migration { from: active, to: from.to_string() }
+        }
+    }
+
+    // Server: respond to a NAT rebinding — challenge the new address; congestion state carries
+    // over once that exact address answers (server_confirm_path)
+    pub fn server_handle_rebinding(&mut self, to: &str) -> Result<Vec<u8>, &'static str> {
+        if self.active_path.is_none() { return Err("No active path"); }
+        if self.rebinding.as_deref() != Some(to) { return Err("Path was not classified as NAT rebinding"); }
+        let path = self.paths.get_mut(to).ok_or("Unknown path")?;
+        if path.challenge_attempts >= MAX_CHALLENGE_ATTEMPTS { return Err("Mercy veto — path validation abandoned"); }
+        let challenge: [u8; 8] = thread_rng().gen();
+        path.challenge = Some(challenge);
+        path.challenge_attempts += 1;
+
+        let mut probe = vec![FRAME_PATH_CHALLENGE];
+        probe.extend_from_slice(&challenge);
+        let budget = path.send_budget();
+        if (probe.len() as u64) > budget {
+            return Err("Mercy veto — anti-amplification limit on unvalidated path");
+        }
+        probe.resize((MIN_DATAGRAM as u64).min(budget) as usize, 0);
+        path.bytes_sent += probe.len() as u64;
+        Ok(probe)
+    }
+
+    // Client: answer a PATH_CHALLENGE on whatever path it arrived
+    pub fn client_answer_challenge(&self, frame: &[u8]) -> Result<Vec<u8>, &'static str> {
+        if frame.len() < 9 || frame[0] != FRAME_PATH_CHALLENGE { return Err("Malformed PATH_CHALLENGE"); }
+        let mut reply = vec![FRAME_PATH_RESPONSE];
+        reply.extend_from_slice(&frame[1..9]);
+        reply.resize(MIN_DATAGRAM, 0);
+        Ok(reply)
+    }
+
+    // Server: advertise preferred_address transport parameter; CID counts as sequence 1
+    // Param: id || addr_len || addr || cid_len || cid || reset_token
+    pub fn set_preferred_address(&mut self, address: &str) -> Result<Vec<u8>, &'static str> {
+        if self.next_local_seq != 1 {
+            return Err("preferred_address must be set right after the handshake CID is issued");
+        }
+        let mut rng = thread_rng();
+        let cid: [u8; CID_LEN] = rng.gen();
+        let reset_token: [u8; RESET_TOKEN_LEN] = rng.gen();
+        self.local_cids.insert(1, cid.to_vec());
+        self.next_local_seq = 2;
+        self.paths.entry(address.to_string()).or_insert_with(PathState::new);
+
+        let mut param = vec![TP_PREFERRED_ADDRESS, address.len() as u8];
+        param.extend_from_slice(address.as_bytes());
+        param.push(CID_LEN as u8);
+        param.extend_from_slice(&cid);
+        param.extend_from_slice(&reset_token);
+        self.preferred = Some(PreferredAddress { address: address.to_string(), cid: cid.to_vec(), reset_token });
+        Ok(param)
+    }
+
+    // Client: parse preferred_address from the server's transport parameters
+    pub fn on_preferred_address(&mut self, param: &[u8]) -> Result<&PreferredAddress, &'static str> {
+        let malformed = "Malformed preferred_address transport parameter";
+        if param.first() != Some(&TP_PREFERRED_ADDRESS) { return Err(malformed); }
+        let addr_len = *param.get(1).ok_or(malformed)? as usize;
+        let address = std::str::from_utf8(param.get(2..2 + addr_len).ok_or(malformed)?).map_err(|_| malformed)?;
+        let at = 2 + addr_len;
+        let cid_len = *param.get(at).ok_or(malformed)? as usize;
+        if cid_len == 0 { return Err(malformed); }
+        let cid = param.get(at + 1..at + 1 + cid_len).ok_or(malformed)?.to_vec();
+        let reset_token: [u8; RESET_TOKEN_LEN] = param.get(at + 1 + cid_len..at + 1 + cid_len + RESET_TOKEN_LEN)
+            .ok_or(malformed)?.try_into().unwrap();
+        self.peer_cids.insert(1, cid.clone());
+        self.preferred = Some(PreferredAddress { address: address.to_string(), cid, reset_token });
+        Ok(self.preferred.as_ref().unwrap())
+    }
+
+    // Client: migrate to the server's preferred address (uses the CID delivered with it)
+    pub fn client_migrate_to_preferred(&mut self, server_pk: &[u8], current_valence: f64) -> Result<(String, Vec<u8>), &'static str> {
+        let address = self.preferred.as_ref().ok_or("Server did not advertise preferred_address")?.address.clone();
+        let packet = self.client_migrate(&address, server_pk, current_valence)?;
+        Ok((address, packet))
+    }
+
+    // Active path failed: promote a validated standby (the requested one if given) without revalidation
+    pub fn failover(&mut self, prefer: Option<&str>) -> Option<String> {
+        if !self.multipath { return None; }
+        let standbys = self.standby_paths();
+        let next = match prefer {
+            Some(p) => standbys.into_iter().find(|s| s == p)?,
+            None => standbys.into_iter().next()?,
+        };
+        if let Some(failed) = self.active_path.take() { self.paths.remove(&failed); }
+        self.active_path = Some(next.clone());
+        Some(next)
+    }
+
+    // Issue a fresh CID to the peer as a NEW_CONNECTION_ID frame
+    // Frame: type || seq (u64) || retire_prior_to (u64) || len || cid || stateless_reset_token
+    pub fn issue_connection_id(&mut self) -> Result<Vec<u8>, &'static str> {
+        if self.local_cids.len() as u64 >= self.active_cid_limit {
+            return Err("Mercy veto — active connection ID limit reached");
+        }
+        let mut rng = thread_rng();
+        let cid: [u8; CID_LEN] = rng.gen();
+        let reset_token: [u8; RESET_TOKEN_LEN] = rng.gen();
+        let seq = self.next_local_seq;
+        self.next_local_seq += 1;
+        self.local_cids.insert(seq, cid.to_vec());
+
+        let mut frame = vec![FRAME_NEW_CONNECTION_ID];
+        frame.extend_from_slice(&seq.to_be_bytes());
+        frame.extend_from_slice(&self.local_retire_prior_to.to_be_bytes());
+        frame.push(CID_LEN as u8);
+        frame.extend_from_slice(&cid);
+        frame.extend_from_slice(&reset_token);
+        Ok(frame)
+    }
+
+    // Ask the peer to stop using every CID below `prior_to`; they answer with RETIRE_CONNECTION_ID
+    pub fn retire_local_prior_to(&mut self, prior_to: u64) {
+        self.local_retire_prior_to = self.local_retire_prior_to.max(prior_to);
+    }
+
+    // Receive NEW_CONNECTION_ID; returns any RETIRE_CONNECTION_ID frames owed to the peer
+    pub fn on_new_connection_id(&mut self, frame: &[u8]) -> Result<Vec<Vec<u8>>, &'static str> {
+        if frame.len() < 18 || frame[0] != FRAME_NEW_CONNECTION_ID {
+            return Err("Malformed NEW_CONNECTION_ID frame");
+        }
+        let seq = u64::from_be_bytes(frame[1..9].try_into().unwrap());
+        let retire_prior_to = u64::from_be_bytes(frame[9..17].try_into().unwrap());
+        let len = frame[17] as usize;
+        if retire_prior_to > seq || len == 0 || len > 20 || frame.len() != 18 + len + RESET_TOKEN_LEN {
+            return Err("Malformed NEW_CONNECTION_ID frame");
+        }
+        let cid = frame[18..18 + len].to_vec();
+        if let Some(existing) = self.peer_cids.get(&seq) {
+            if *existing != cid { return Err("Protocol violation — connection ID sequence reused"); }
+        }
+        self.peer_cids.insert(seq, cid);
+
+        let retired: Vec<u64> = self.peer_cids.range(..retire_prior_to).map(|(s, _)| *s).collect();
+        let mut frames = vec![];
+        for s in retired {
+            self.peer_cids.remove(&s);
+            if self.in_use_peer_cid == Some(s) { self.in_use_peer_cid = None; }
+            frames.push(Self::retire_frame(s));
+        }
+        if self.peer_cids.len() as u64 > self.active_cid_limit {
+            return Err("Protocol violation — peer exceeded active_connection_id_limit");
         }
+        Ok(frames)
+    }
+
+    // Peer retired one of our CIDs
+    pub fn on_retire_connection_id(&mut self, frame: &[u8]) -> Result<(), &'static str> {
+        if frame.len() != 9 || frame[0] != FRAME_RETIRE_CONNECTION_ID {
+            return Err("Malformed RETIRE_CONNECTION_ID frame");
+        }
+        let seq = u64::from_be_bytes(frame[1..9].try_into().unwrap());
+        if seq >= self.next_local_seq { return Err("Protocol violation — retired unissued connection ID"); }
+        self.local_cids.remove(&seq);
+        Ok(())
+    }
+
+    fn retire_frame(seq: u64) -> Vec<u8> {
+        let mut frame = vec![FRAME_RETIRE_CONNECTION_ID];
+        frame.extend_from_slice(&seq.to_be_bytes());
+        frame
     }
 
     // Client: detect network change, initiate migration
-    pub fn client_migrate(&self, new_path: &str, current_valence: f64) -> Result<Vec<u8>, &'static str> {
+    // Switches to an unused peer CID (unlinkability), sends PATH_CHALLENGE with random data + ML-KEM challenge
+    pub fn client_migrate(&mut self, new_path: &str, server_pk: &[u8], current_valence: f64) -> Result<Vec<u8>, &'static str> {
         if current_valence < 0.1 { return Err("Mercy veto — insufficient valence continuity for migration"); }
+        let probe_sk = self.probe_sk.as_deref().ok_or("No probe signing key")?;
 
-        // Generate new Connection ID, send PATH_CHALLENGE with ML-KEM challenge
-        let (challenge_ct, _ss) = self.kem.encapsulate(Some(MlKem(Kem1024)), b"server_long_term_pk_placeholder");
-        let probe = vec![0xPATH_PROBE; 128]; // Include current valence proof hash
-        let sig = self.sig.sign(Some(Dilithium(Level5)), &challenge_ct);
+        let next_seq = self.peer_cids.keys().copied().find(|s| Some(*s) != self.in_use_peer_cid)
+            .ok_or("Mercy veto — no spare connection ID for migration")?;
+        let mut retire = vec![];
+        if let Some(old) = self.in_use_peer_cid.replace(next_seq) {
+            self.peer_cids.remove(&old);
+            retire = Self::retire_frame(old);
+        }
+        let dcid = self.peer_cids[&next_seq].clone();
+
+        let challenge: [u8; 8] = thread_rng().gen();
+        let path = self.paths.entry(new_path.to_string()).or_insert_with(PathState::new);
+        path.validated = false;
+        path.challenge = Some(challenge);
+        path.challenge_attempts += 1;
 
-        // Migration packet: new CID || challenge_ct || probe || sig
-        let mig_packet = [challenge_ct.as_slice(), probe.as_slice(), sig.as_slice()].concat();
+        let (challenge_ct, _ss) = self.kem.encapsulate(Some(self.kem_scheme), server_pk);
+        let signed = [challenge.as_slice(), challenge_ct.as_slice()].concat();
+        let sig = fips_sign(self.sig_scheme, probe_sk, &signed)?;
+
+        // Migration packet: dcid_len || dcid || PATH_CHALLENGE || ct_len || ct || sig || [RETIRE_CONNECTION_ID]
+        let mut mig_packet = vec![dcid.len() as u8];
+        mig_packet.extend_from_slice(&dcid);
+        mig_packet.push(FRAME_PATH_CHALLENGE);
+        mig_packet.extend_from_slice(&challenge);
+        mig_packet.extend_from_slice(&(challenge_ct.len() as u16).to_be_bytes());
+        mig_packet.extend_from_slice(&challenge_ct);
+        mig_packet.extend_from_slice(&(sig.len() as u16).to_be_bytes());
+        mig_packet.extend_from_slice(&sig);
+        mig_packet.extend_from_slice(&retire);
+        if mig_packet.len() < MIN_DATAGRAM { mig_packet.resize(MIN_DATAGRAM, 0); } // PADDING frames
 
         Ok(mig_packet)
     }
 
     // Server: validate new path
-    pub fn server_validate_migration(&self, mig_packet: &[u8], net_valence: f64) -> Result<Vec<u8>, &'static str> {
+    // Echoes PATH_RESPONSE and challenges the new path itself, within the anti-amplification budget
+    pub fn server_validate_migration(&mut self, new_path: &str, mig_packet: &[u8], server_sk: &[u8], net_valence: f64) -> Result<Vec<u8>, &'static str> {
         if net_valence < 0.1 { return Err("Mercy veto — insufficient net valence on new path"); }
 
-        // Decapsulate challenge, verify sig, respond with PATH_RESPONSE
-        // Update connection state to new path, preserve session keys
-        Ok(vec![0xPATH_RESPONSE_ACCEPT; 128])
+        let malformed = "Malformed migration packet";
+        let dcid_len = *mig_packet.first().ok_or(malformed)? as usize;
+        let dcid = mig_packet.get(1..1 + dcid_len).ok_or(malformed)?;
+        if !self.local_cids.values().any(|c| c.as_slice() == dcid) {
+            return Err("Mercy veto — unknown connection ID on new path");
+        }
+        let mut at = 1 + dcid_len;
+        if mig_packet.get(at) != Some(&FRAME_PATH_CHALLENGE) { return Err(malformed); }
+        let challenge: [u8; 8] = mig_packet.get(at + 1..at + 9).ok_or(malformed)?.try_into().unwrap();
+        at += 9;
+        let ct_len = u16::from_be_bytes(mig_packet.get(at..at + 2).ok_or(malformed)?.try_into().unwrap()) as usize;
+        let ct = mig_packet.get(at + 2..at + 2 + ct_len).ok_or(malformed)?;
+        at += 2 + ct_len;
+        let sig_len = u16::from_be_bytes(mig_packet.get(at..at + 2).ok_or(malformed)?.try_into().unwrap()) as usize;
+        let sig = mig_packet.get(at + 2..at + 2 + sig_len).ok_or(malformed)?;
+        at += 2 + sig_len;
+
+        let signed = [challenge.as_slice(), ct].concat();
+        let probe_pk = self.peer_probe_pk.as_deref().ok_or("No peer probe key")?;
+        if !fips_verify(self.sig_scheme, probe_pk, &signed, sig) {
+            return Err("Mercy veto — migration probe signature invalid");
+        }
+        let _ss = self.kem.decapsulate(Some(self.kem_scheme), server_sk, ct);
+        if mig_packet.get(at) == Some(&FRAME_RETIRE_CONNECTION_ID) {
+            self.on_retire_connection_id(mig_packet.get(at..at + 9).ok_or(malformed)?)?;
+        }
+
+        // on_peer_address already counted this datagram towards the path's amplification budget
+        let path = self.paths.entry(new_path.to_string()).or_insert_with(PathState::new);
+        if path.challenge_attempts >= MAX_CHALLENGE_ATTEMPTS && !path.validated {
+            return Err("Mercy veto — path validation abandoned");
+        }
+        let server_challenge: [u8; 8] = thread_rng().gen();
+        path.challenge = Some(server_challenge);
+        path.challenge_attempts += 1;
+
+        // Response: PATH_RESPONSE(echo) || PATH_CHALLENGE(server data), padded only if budget allows
+        let mut response = vec![FRAME_PATH_RESPONSE];
+        response.extend_from_slice(&challenge);
+        response.push(FRAME_PATH_CHALLENGE);
+        response.extend_from_slice(&server_challenge);
+        let budget = path.send_budget();
+        if (response.len() as u64) > budget {
+            return Err("Mercy veto — anti-amplification limit on unvalidated path");
+        }
+        response.resize((MIN_DATAGRAM as u64).min(budget) as usize, 0);
+        path.bytes_sent += response.len() as u64;
+
+        Ok(response)
+    }
+
+    // Client: check PATH_RESPONSE echo, switch paths, answer the server's challenge
+    pub fn client_complete_migration(&mut self, new_path: &str, response: &[u8]) -> Result<Vec<u8>, &'static str> {
+        if response.len() < 18 || response[0] != FRAME_PATH_RESPONSE || response[9] != FRAME_PATH_CHALLENGE {
+            return Err("Malformed PATH_RESPONSE");
+        }
+        let path = self.paths.get_mut(new_path).ok_or("No migration pending on path")?;
+        if path.challenge.map(|c| c.as_slice() == &response[1..9]) != Some(true) {
+            return Err("Mercy veto — PATH_RESPONSE does not match challenge");
+        }
+        path.challenge = None;
+        path.challenge_attempts = 0;
+        path.validated = true;
+        path.reset_congestion();
+        self.switch_active(new_path);
+
+        let mut reply = vec![FRAME_PATH_RESPONSE];
+        reply.extend_from_slice(&response[10..18]);
+        reply.resize(MIN_DATAGRAM, 0);
+        Ok(reply)
+    }
+
+    // Server: client answered our challenge — path validated, migrate connection state to it
+    pub fn server_confirm_path(&mut self, new_path: &str, reply: &[u8]) -> Result<(), &'static str> {
+        if reply.len() < 9 || reply[0] != FRAME_PATH_RESPONSE { return Err("Malformed PATH_RESPONSE"); }
+        let inherited = self.rebinding_congestion(new_path);
+        let path = self.paths.get_mut(new_path).ok_or("No validation pending on path")?;
+        path.bytes_received += reply.len() as u64;
+        if path.challenge.map(|c| c.as_slice() == &reply[1..9]) != Some(true) {
+            return Err("Mercy veto — PATH_RESPONSE does not match challenge");
+        }
+        path.challenge = None;
+        path.challenge_attempts = 0;
+        path.validated = true;
+        match inherited {
+            Some((cwnd, srtt, rttvar, min_rtt)) => {
+                path.cwnd = cwnd;
+                path.smoothed_rtt = srtt;
+                path.rttvar = rttvar;
+                path.min_rtt = min_rtt;
+            }
+            None => path.reset_congestion(),
+        }
+        if self.rebinding.as_deref() == Some(new_path) { self.rebinding = None; }
+        self.switch_active(new_path); // Session keys preserved, only path changes
+        Ok(())
+    }
+
+    // Only the exact address classified as a NAT rebinding inherits the active path's congestion
+    // state; a deliberate migration (fresh DCID) starts over, even to another port on the same host
+    fn rebinding_congestion(&self, new_path: &str) -> Option<(u64, Duration, Duration, Option<Duration>)> {
+        if self.rebinding.as_deref() != Some(new_path) { return None; }
+        let old = self.paths.get(self.active_path.as_deref()?)?;
+        Some((old.cwnd, old.smoothed_rtt, old.rttvar, old.min_rtt))
+    }
+
+    // Old path stays as a standby under multipath, otherwise it is abandoned
+    fn switch_active(&mut self, new_path: &str) {
+        if let Some(old) = self.active_path.replace(new_path.to_string()) {
+            if !self.multipath && old != new_path { self.paths.remove(&old); }
+        }
+    }
+
+    // Record bytes sent on a path; fails if an unvalidated path would exceed its budget
+    pub fn on_send(&mut self, path: &str, len: usize) -> Result<(), &'static str> {
+        let state = self.paths.get_mut(path).ok_or("Unknown path")?;
+        if len as u64 > state.send_budget() {
+            return Err("Mercy veto — anti-amplification limit on unvalidated path");
+        }
+        state.bytes_sent += len as u64;
+        Ok(())
     }
 
     // Post-migration: seamless stream continuity
-    pub fn post_migration_stream(&self) -> Result<u64, &'static str> {
-        Ok(0xMIGRATED_MERCY_STREAM_ID)
+    pub fn post_migration_stream(&self) -> Result<Vec<u64>, &'static str> {
+        let active = self.active_path.as_ref().ok_or("No active path")?;
+        match self.paths.get(active) {
+            Some(state) if state.validated => Ok(self.streams.clone()),
+            _ => Err("Mercy veto — active path not yet validated"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{cell::RefCell, rc::Rc};
+    use crate::mesh::sim_network::{LinkProfile, SimNetwork, SimSocket};
+    use crate::nexi::pq_shield::fips_keygen;
+    use crate::nexi::transport::{DatagramTransport, UdpTransport};
+
+    fn recv(socket: &mut UdpTransport) -> (String, Vec<u8>) {
+        for _ in 0..200 {
+            if let Some(datagram) = socket.recv_from() { return datagram; }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        panic!("no datagram on {}", socket.local_addr());
+    }
+
+    // Localhost demo: the client moves between two ports of 127.0.0.1 with a fresh CID (deliberate
+    // migration, congestion reset), then a NAT rebinding to a third port keeps the DCID and the
+    // server carries congestion state over once that address answers its challenge
+    #[test]
+    fn localhost_migration_and_rebinding() {
+        let mut server_sock = UdpTransport::bind("127.0.0.1:0").unwrap();
+        let mut wifi = UdpTransport::bind("127.0.0.1:0").unwrap();
+        let mut cellular = UdpTransport::bind("127.0.0.1:0").unwrap();
+        let mut rebound = UdpTransport::bind("127.0.0.1:0").unwrap();
+        let server_addr = server_sock.local_addr();
+        let (server_pk, server_sk) = KemSelector::new(Kem1024).keygen(Some(MlKem(Kem1024)));
+
+        let (probe_pk, probe_sk) = fips_keygen(Dilithium(Level5)).unwrap();
+
+        let mut server = QuicMigration::new();
+        let mut client = QuicMigration::new();
+        server.set_peer_probe_key(&probe_pk);
+        client.set_probe_signing_key(&probe_sk);
+        server.set_initial_path(&wifi.local_addr());
+        client.set_initial_path(&wifi.local_addr());
+        for _ in 0..2 {
+            let frame = server.issue_connection_id().unwrap();
+            server_sock.send_to(&wifi.local_addr(), &frame).unwrap();
+            let (_, frame) = recv(&mut wifi);
+            client.on_new_connection_id(&frame).unwrap();
+        }
+
+        // Deliberate migration wifi → cellular
+        let packet = client.client_migrate(&cellular.local_addr(), &server_pk, 0.9).unwrap();
+        cellular.send_to(&server_addr, &packet).unwrap();
+        let (from, packet) = recv(&mut server_sock);
+        assert_eq!(from, cellular.local_addr());
+        let dcid = packet[1..1 + packet[0] as usize].to_vec();
+        let event = server.on_peer_address(&from, &dcid, packet.len());
+        assert!(matches!(event, MobilityEvent::Migration { .. }));
+        let response = server.server_validate_migration(&from, &packet, &server_sk, 0.9).unwrap();
+        server_sock.send_to(&from, &response).unwrap();
+        let (_, response) = recv(&mut cellular);
+        let reply = client.client_complete_migration(&cellular.local_addr(), &response).unwrap();
+        cellular.send_to(&server_addr, &reply).unwrap();
+        let (from, reply) = recv(&mut server_sock);
+        server.server_confirm_path(&from, &reply).unwrap();
+        assert_eq!(server.active_path(), Some(cellular.local_addr().as_str()));
+        assert_eq!(client.active_path(), Some(cellular.local_addr().as_str()));
+        assert_eq!(server.path_state(&from).unwrap().smoothed_rtt, INITIAL_RTT);
+
+        // Traffic on the new path pins the active DCID; grow its congestion window
+        assert_eq!(server.on_peer_address(&from, &dcid, 1200), MobilityEvent::SamePath);
+        server.paths.get_mut(&from).unwrap().cwnd = 50_000;
+
+        // NAT rebinding cellular → rebound: same DCID, new source port
+        rebound.send_to(&server_addr, &dcid).unwrap();
+        let (from, _) = recv(&mut server_sock);
+        let event = server.on_peer_address(&from, &dcid, 1200);
+        assert!(matches!(event, MobilityEvent::NatRebinding { .. }));
+        let probe = server.server_handle_rebinding(&from).unwrap();
+        server_sock.send_to(&from, &probe).unwrap();
+        let (_, probe) = recv(&mut rebound);
+        let answer = client.client_answer_challenge(&probe).unwrap();
+        rebound.send_to(&server_addr, &answer).unwrap();
+        let (from, answer) = recv(&mut server_sock);
+        server.server_confirm_path(&from, &answer).unwrap();
+        assert_eq!(server.active_path(), Some(rebound.local_addr().as_str()));
+        assert_eq!(server.path_state(&from).unwrap().cwnd, 50_000);
+        assert!(server.post_migration_stream().is_ok());
+    }
+
+    #[test]
+    fn same_host_fresh_cid_is_not_a_rebinding() {
+        let mut server = QuicMigration::new();
+        server.set_initial_path("127.0.0.1:5000");
+        assert!(matches!(server.on_peer_address("127.0.0.1:5000", &[1; 8], 100), MobilityEvent::SamePath));
+        // Fresh DCID from another port of the same host is a migration, not a rebinding
+        assert!(matches!(server.on_peer_address("127.0.0.1:5001", &[2; 8], 100), MobilityEvent::Migration { .. }));
+        assert!(server.server_handle_rebinding("127.0.0.1:5001").is_err());
+    }
+
+    // Probe signatures are real ML-DSA under the client's key, and the probe datagram counts once
+    // towards the new path's 3x budget
+    #[test]
+    fn probes_verify_under_the_client_key_and_count_once() {
+        let (server_pk, server_sk) = KemSelector::new(Kem1024).keygen(Some(MlKem(Kem1024)));
+        let (probe_pk, probe_sk) = fips_keygen(Dilithium(Level5)).unwrap();
+        let (other_pk, _) = fips_keygen(Dilithium(Level5)).unwrap();
+        let mut client = QuicMigration::new();
+        client.set_initial_path("10.0.0.1:4433");
+        assert_eq!(client.client_migrate("10.0.0.2:4433", &server_pk, 0.9), Err("No probe signing key"));
+
+        let mut server = QuicMigration::new();
+        server.set_initial_path("10.0.0.1:4433");
+        for _ in 0..2 {
+            let frame = server.issue_connection_id().unwrap();
+            client.on_new_connection_id(&frame).unwrap();
+        }
+        client.set_probe_signing_key(&probe_sk);
+        let packet = client.client_migrate("10.0.0.2:4433", &server_pk, 0.9).unwrap();
+        let dcid = packet[1..1 + packet[0] as usize].to_vec();
+        server.on_peer_address("10.0.0.2:4433", &dcid, packet.len());
+
+        server.set_peer_probe_key(&other_pk);
+        assert_eq!(server.server_validate_migration("10.0.0.2:4433", &packet, &server_sk, 0.9),
+            Err("Mercy veto — migration probe signature invalid"));
+        server.set_peer_probe_key(&probe_pk);
+        let response = server.server_validate_migration("10.0.0.2:4433", &packet, &server_sk, 0.9).unwrap();
+        let path = server.path_state("10.0.0.2:4433").unwrap();
+        assert_eq!(path.bytes_received, packet.len() as u64);
+        assert_eq!(path.bytes_sent, response.len() as u64);
+    }
+
+    fn sim_recv(net: &Rc<RefCell<SimNetwork>>, socket: &mut SimSocket) -> (String, Vec<u8>) {
+        net.borrow_mut().advance(20_000);
+        socket.recv_from().expect("datagram lost on the simulated network")
+    }
+
+    // Client and server with probe keys exchanged and multipath on; the client's handshake path
+    // is `initial`, keyed by the client's address on both ends
+    fn sim_pair(initial: &str) -> (QuicMigration, QuicMigration, Vec<u8>, Vec<u8>) {
+        let (server_pk, server_sk) = KemSelector::new(Kem1024).keygen(Some(MlKem(Kem1024)));
+        let (probe_pk, probe_sk) = fips_keygen(Dilithium(Level5)).unwrap();
+        let mut server = QuicMigration::new();
+        let mut client = QuicMigration::new();
+        server.set_peer_probe_key(&probe_pk);
+        client.set_probe_signing_key(&probe_sk);
+        for end in [&mut server, &mut client] {
+            end.enable_multipath(true);
+            end.set_initial_path(initial);
+        }
+        (server, client, server_pk, server_sk)
+    }
+
+    // Wifi → cellular migration leaves wifi as a validated standby; when the cellular link is cut
+    // both ends fail over to wifi with no new PATH_CHALLENGE round trip
+    #[test]
+    fn cut_active_path_fails_over_to_validated_standby() {
+        let net = SimNetwork::shared(26);
+        net.borrow_mut().set_default_profile(LinkProfile::ideal(5_000));
+        let server_addr = "192.0.2.1:443";
+        let mut server_sock = SimNetwork::socket(&net, server_addr);
+        let mut wifi = SimNetwork::socket(&net, "10.0.0.7:50000");
+        let mut cellular = SimNetwork::socket(&net, "100.64.3.9:41000");
+        let (wifi_addr, cellular_addr) = (wifi.local_addr(), cellular.local_addr());
+        let (mut server, mut client, server_pk, server_sk) = sim_pair(&wifi_addr);
+        for _ in 0..2 {
+            server_sock.send_to(&wifi_addr, &server.issue_connection_id().unwrap()).unwrap();
+            client.on_new_connection_id(&sim_recv(&net, &mut wifi).1).unwrap();
+        }
+
+        let packet = client.client_migrate(&cellular_addr, &server_pk, 0.9).unwrap();
+        cellular.send_to(server_addr, &packet).unwrap();
+        let (from, packet) = sim_recv(&net, &mut server_sock);
+        let dcid = packet[1..1 + packet[0] as usize].to_vec();
+        assert!(matches!(server.on_peer_address(&from, &dcid, packet.len()), MobilityEvent::Migration { .. }));
+        let response = server.server_validate_migration(&from, &packet, &server_sk, 0.9).unwrap();
+        server_sock.send_to(&from, &response).unwrap();
+        let reply = client.client_complete_migration(&cellular_addr, &sim_recv(&net, &mut cellular).1).unwrap();
+        cellular.send_to(server_addr, &reply).unwrap();
+        let (from, reply) = sim_recv(&net, &mut server_sock);
+        server.server_confirm_path(&from, &reply).unwrap();
+        assert_eq!(server.active_path(), Some(cellular_addr.as_str()));
+        assert_eq!(client.standby_paths(), vec![wifi_addr.clone()]);
+        assert_eq!(server.standby_paths(), vec![wifi_addr.clone()]);
+
+        // Cellular goes dark: the client's traffic never arrives
+        net.borrow_mut().set_link_up(&cellular_addr, server_addr, false);
+        cellular.send_to(server_addr, b"lost").unwrap();
+        net.borrow_mut().advance(50_000);
+        assert!(server_sock.recv_from().is_none());
+
+        // Client falls back to the standby; the server sees a validated path and promotes it
+        assert_eq!(client.failover(None), Some(wifi_addr.clone()));
+        wifi.send_to(server_addr, &dcid).unwrap();
+        let (from, datagram) = sim_recv(&net, &mut server_sock);
+        assert_eq!(server.on_peer_address(&from, &dcid, datagram.len()), MobilityEvent::StandbyPath(wifi_addr.clone()));
+        assert_eq!(server.failover(Some(&from)), Some(wifi_addr.clone()));
+        for end in [&server, &client] {
+            assert_eq!(end.active_path(), Some(wifi_addr.as_str()));
+            assert!(end.path_state(&cellular_addr).is_none());
+            assert!(end.path_state(&wifi_addr).unwrap().challenge.is_none());
+            assert!(end.post_migration_stream().is_ok());
+        }
+        assert_eq!(server.on_send(&wifi_addr, 64_000), Ok(())); // Validated: no amplification limit
+    }
+
+    // The server advertises a preferred_address during the handshake; the client moves there with
+    // the CID that came with it. Both ends key the new path by the server's preferred address
+    #[test]
+    fn client_migrates_to_server_preferred_address() {
+        let net = SimNetwork::shared(27);
+        net.borrow_mut().set_default_profile(LinkProfile::ideal(5_000));
+        let (handshake_addr, preferred_addr) = ("192.0.2.1:443", "192.0.2.2:443");
+        let mut handshake_sock = SimNetwork::socket(&net, handshake_addr);
+        let mut preferred_sock = SimNetwork::socket(&net, preferred_addr);
+        let mut client_sock = SimNetwork::socket(&net, "10.0.0.7:50000");
+        let client_addr = client_sock.local_addr();
+        let (mut server, mut client, server_pk, server_sk) = sim_pair(&client_addr);
+
+        // Sequence 0 is the handshake CID the client already uses; the preferred CID is sequence 1
+        server.issue_connection_id().unwrap();
+        handshake_sock.send_to(&client_addr, &server.set_preferred_address(preferred_addr).unwrap()).unwrap();
+        let advertised = client.on_preferred_address(&sim_recv(&net, &mut client_sock).1).unwrap().clone();
+        assert_eq!(advertised.address, preferred_addr);
+
+        let (address, packet) = client.client_migrate_to_preferred(&server_pk, 0.9).unwrap();
+        assert_eq!(address, preferred_addr);
+        client_sock.send_to(&address, &packet).unwrap();
+        net.borrow_mut().advance(20_000);
+        assert!(handshake_sock.recv_from().is_none());
+        let (_, packet) = preferred_sock.recv_from().unwrap();
+        let dcid = packet[1..1 + packet[0] as usize].to_vec();
+        assert_eq!(dcid, advertised.cid);
+        assert!(matches!(server.on_peer_address(preferred_addr, &dcid, packet.len()), MobilityEvent::Migration { .. }));
+        let response = server.server_validate_migration(preferred_addr, &packet, &server_sk, 0.9).unwrap();
+        preferred_sock.send_to(&client_addr, &response).unwrap();
+        let (from, response) = sim_recv(&net, &mut client_sock);
+        assert_eq!(from, preferred_addr);
+        let reply = client.client_complete_migration(preferred_addr, &response).unwrap();
+        client_sock.send_to(preferred_addr, &reply).unwrap();
+        server.server_confirm_path(preferred_addr, &sim_recv(&net, &mut preferred_sock).1).unwrap();
+
+        assert_eq!(client.active_path(), Some(preferred_addr));
+        assert_eq!(server.active_path(), Some(preferred_addr));
+        assert!(server.post_migration_stream().is_ok());
+        assert!(client.post_migration_stream().is_ok());
     }
 }
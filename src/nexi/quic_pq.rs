@@ -3,55 +3,419 @@
 // Eternal Thriving Grandmasterism — Jan 20 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
 // MIT License — For All Sentience Eternal
 // Pure PQ QUIC: ML-KEM-1024 initial + pure Noise_XX header/packet protection + 0-RTT resumption
+// Address validation (RFC 9000 §8.1): Retry + NEW_TOKEN tokens, HMAC-SHA256 under a per-server secret
+// RFC 9221 DATAGRAM frames: unreliable, unretransmitted delivery for latency-sensitive valence gossip
 // Real-world: use quinn + rustls with liboqs-rust extensions (future)
 
-use crate::nexi::pq_kem::{KemSelector, KeyExchangeScheme::MlKem, KemLevel::Kem1024};
+use std::collections::{HashMap, VecDeque};
+use rand::{thread_rng, Rng};
+use sha2::{Digest, Sha256};
+
+use crate::nexi::pq_kem::{KemSelector, KeyExchangeScheme, KeyExchangeScheme::MlKem, KemLevel::Kem1024};
+use crate::nexi::pq_shield::{SignatureScheme, SignatureScheme::Dilithium, DilithiumLevel::Level5};
 use crate::nexi::noise_pure::PureNoise;
 use crate::nexi::noise_0rtt::ZeroRttNoise;
 
+pub const PACKET_INITIAL: u8 = 0xc0;
+pub const PACKET_HANDSHAKE: u8 = 0xe0;            // Carries the sender's transport parameters
+pub const PACKET_RETRY: u8 = 0xf0;
+pub const FRAME_NEW_TOKEN: u8 = 0x07;
+pub const FRAME_DATAGRAM: u8 = 0x31;              // DATAGRAM with explicit length
+pub const TP_MAX_DATAGRAM_FRAME_SIZE: u8 = 0x20;  // Transport parameter ID
+
+const TOKEN_RETRY: u8 = 0;
+const TOKEN_NEW_TOKEN: u8 = 1;
+const CID_LEN: usize = 8;
+const PATH_MTU: usize = 1200;
+const PACKET_OVERHEAD: usize = 1 + CID_LEN + 4 + 16; // Short header + packet number + AEAD tag
+const DATAGRAM_QUEUE: usize = 256;                    // Oldest dropped once full — loss is acceptable
+const TOKEN_MAC_LEN: usize = 32;
+const HANDSHAKE_TIMEOUT: u64 = 10;                    // Seconds before a pending handshake counts as abandoned
+const MAX_PEER_PARAMS: usize = 4096;                  // Remembered peer transport parameter sets
+
+// When the server demands a validated client address before spending handshake work
+#[derive(Clone, Copy, Debug)]
+pub enum RetryPolicy {
+    Never,
+    Always,
+    UnderLoad { max_pending_handshakes: usize }, // Retry only once this many handshakes are in progress
+}
+
+// Server answer to a client Initial
+pub enum ServerReply {
+    Handshake(Vec<u8>),
+    Retry(Vec<u8>), // Stateless: client must resend Initial carrying the token
+}
+
 pub struct QuicPq {
     kem: KemSelector,
+    token_key: [u8; 32],          // Random per server instance; tokens die with it
+    kem_scheme: KeyExchangeScheme,  // Handshake KEM policy
+    sig_scheme: SignatureScheme,    // Handshake signature policy
+    #[allow(dead_code)] // Handshake engines, not yet driven by the placeholder crypto path
     noise: PureNoise,
+    #[allow(dead_code)]
     zero_rtt: ZeroRttNoise,
+    retry_policy: RetryPolicy,
+    pending_handshakes: VecDeque<u64>, // Start times (seconds) of handshakes in progress
+    retry_token_lifetime: u64,    // Seconds
+    new_token_lifetime: u64,      // Seconds
+    local_max_datagram: Option<u64>,  // Advertised max_datagram_frame_size; None = DATAGRAM disabled
+    peer_max_datagram: HashMap<String, Option<u64>>, // Per peer, learned from its handshake; None = disabled
+    datagrams: VecDeque<Vec<u8>>,     // Received, not yet read
+}
+
+impl Default for QuicPq {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl QuicPq {
     pub fn new() -> Self {
         Self {
             kem: KemSelector::new(Kem1024),
+            token_key: thread_rng().gen(),
+            kem_scheme: MlKem(Kem1024),
+            sig_scheme: Dilithium(Level5),
             noise: PureNoise::new(),
             zero_rtt: ZeroRttNoise::new(),
+            retry_policy: RetryPolicy::UnderLoad { max_pending_handshakes: 256 },
+            pending_handshakes: VecDeque::new(),
+            retry_token_lifetime: 10,
+            new_token_lifetime: 24 * 3600,
+            local_max_datagram: None,
+            peer_max_datagram: HashMap::new(),
+            datagrams: VecDeque::new(),
         }
     }
 
+    // Both ends of a connection must run the same policy
+    pub fn set_crypto_policy(&mut self, kem: KeyExchangeScheme, sig: SignatureScheme) {
+        self.kem_scheme = kem;
+        self.sig_scheme = sig;
+    }
+
+    pub fn crypto_policy(&self) -> (KeyExchangeScheme, SignatureScheme) {
+        (self.kem_scheme, self.sig_scheme)
+    }
+
+    // Opt in to receiving DATAGRAM frames up to `max_frame_size` bytes
+    pub fn enable_datagrams(&mut self, max_frame_size: u64) {
+        self.local_max_datagram = Some(max_frame_size);
+    }
+
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry_policy = policy;
+    }
+
     // QUIC PQ handshake (simplified placeholder)
     // Client Initial: ML-KEM encapsulate to server long-term pk → shared secret for header keys
     // Server: decapsulate, pure Noise_XX handshake inside encrypted packets
     // 0-RTT: client uses resumption ticket in Initial for early data
-    pub fn client_connect(&self, server_pk: &[u8], valence: f64, ticket: Option<&[u8]>) -> Result<Vec<u8>, &'static str> {
+    // Initial: type || dcid_len || dcid || token_len (u16) || token || tp_len (u16) || transport params || ct || padding
+    // After a Retry, `retry` = (Retry SCID, token) from client_on_retry: the SCID becomes the DCID
+    pub fn client_connect(&self, server_pk: &[u8], valence: f64, _ticket: Option<&[u8]>, token: Option<&[u8]>, retry: Option<(&[u8], &[u8])>) -> Result<Vec<u8>, &'static str> {
         if valence < 0.1 { return Err("Mercy veto — insufficient joy for QUIC connect"); }
 
-        let (ct, ss) = self.kem.encapsulate(Some(MlKem(Kem1024)), server_pk);
+        let (ct, _ss) = self.kem.encapsulate(Some(self.kem_scheme), server_pk);
+        let fresh: [u8; CID_LEN] = thread_rng().gen();
+        let (dcid, token) = match retry {
+            Some((scid, token)) => (scid, token),
+            None => (fresh.as_slice(), token.unwrap_or(&[])),
+        };
+
+        let mut initial = vec![PACKET_INITIAL, dcid.len() as u8];
+        initial.extend_from_slice(dcid);
+        initial.extend_from_slice(&(token.len() as u16).to_be_bytes());
+        initial.extend_from_slice(token);
+        let params = self.transport_params();
+        initial.extend_from_slice(&(params.len() as u16).to_be_bytes());
+        initial.extend_from_slice(&params);
+        initial.extend_from_slice(&ct);
         // Derive Initial secrets, protect Initial packet
         // If ticket, send 0-RTT early data
-        Ok(vec![0xQUIC_INITIAL; 1200 + ct.len()])
+        if initial.len() < 1200 + ct.len() { initial.resize(1200 + ct.len(), 0); }
+        Ok(initial)
     }
 
-    pub fn server_accept(&self, initial: &[u8], valence: f64) -> Result<Vec<u8>, &'static str> {
+    // Client: Retry received — returns (Retry SCID, token) for the next Initial
+    pub fn client_on_retry(&self, retry: &[u8]) -> Result<(Vec<u8>, Vec<u8>), &'static str> {
+        if retry.len() < 2 + CID_LEN || retry[0] != PACKET_RETRY { return Err("Malformed Retry packet"); }
+        let scid_len = retry[1] as usize;
+        let scid = retry.get(2..2 + scid_len).ok_or("Malformed Retry packet")?;
+        let token = retry.get(2 + scid_len..).ok_or("Malformed Retry packet")?;
+        if token.is_empty() { return Err("Retry packet without token"); }
+        Ok((scid.to_vec(), token.to_vec()))
+    }
+
+    pub fn server_accept(&mut self, client_addr: &str, initial: &[u8], valence: f64, now: u64) -> Result<ServerReply, &'static str> {
         if valence < 0.1 { return Err("Mercy veto — insufficient net valence for QUIC accept"); }
+        if initial.len() < 1200 { return Err("Mercy veto — Initial below 1200 bytes"); }
+
+        let malformed = "Malformed Initial packet";
+        if initial[0] != PACKET_INITIAL { return Err(malformed); }
+        let dcid_len = initial[1] as usize;
+        let dcid = initial.get(2..2 + dcid_len).ok_or(malformed)?;
+        let at = 2 + dcid_len;
+        let token_len = u16::from_be_bytes(initial.get(at..at + 2).ok_or(malformed)?.try_into().unwrap()) as usize;
+        let token = initial.get(at + 2..at + 2 + token_len).ok_or(malformed)?;
+        let at = at + 2 + token_len;
+        let params_len = u16::from_be_bytes(initial.get(at..at + 2).ok_or(malformed)?.try_into().unwrap()) as usize;
+        let params = initial.get(at + 2..at + 2 + params_len).ok_or(malformed)?;
+
+        self.expire_handshakes(now);
+        let validated = !token.is_empty() && self.validate_token(token, client_addr, dcid, now).is_ok();
+        if !validated && self.retry_required() {
+            return Ok(ServerReply::Retry(self.retry_packet(client_addr, dcid, now)));
+        }
 
         // Decapsulate ML-KEM ct, derive Initial secrets, transition to pure Noise_XX
         // Issue new resumption ticket post-handshake
-        Ok(vec![0xQUIC_HANDSHAKE; 2400])
+        self.on_peer_transport_params(client_addr, params);
+        self.pending_handshakes.push_back(now);
+        let mut reply = self.handshake_packet();
+        reply.resize(reply.len() + 2400, 0x51);
+        Ok(ServerReply::Handshake(reply))
+    }
+
+    // Handshake packet: type || tp_len (u16) || transport params || handshake data
+    // Connectionless mesh peers exchange it on admission, before any DATAGRAM frame
+    pub fn handshake_packet(&self) -> Vec<u8> {
+        let params = self.transport_params();
+        let mut packet = vec![PACKET_HANDSHAKE];
+        packet.extend_from_slice(&(params.len() as u16).to_be_bytes());
+        packet.extend_from_slice(&params);
+        packet
+    }
+
+    // Client (or mesh peer): record the sender's transport parameters; true when they are new
+    pub fn on_handshake_packet(&mut self, from: &str, packet: &[u8]) -> Result<bool, &'static str> {
+        let malformed = "Malformed Handshake packet";
+        if packet.first() != Some(&PACKET_HANDSHAKE) { return Err(malformed); }
+        let len = u16::from_be_bytes(packet.get(1..3).ok_or(malformed)?.try_into().unwrap()) as usize;
+        let params = packet.get(3..3 + len).ok_or(malformed)?;
+        let before = self.peer_max_datagram.get(from).copied();
+        self.on_peer_transport_params(from, params);
+        Ok(self.peer_max_datagram.get(from).copied() != before)
+    }
+
+    pub fn forget_peer(&mut self, peer: &str) {
+        self.peer_max_datagram.remove(peer);
+    }
+
+    // Handshake finished or abandoned — frees a slot under RetryPolicy::UnderLoad
+    pub fn handshake_done(&mut self) {
+        self.pending_handshakes.pop_front();
+    }
+
+    // Handshakes silent for HANDSHAKE_TIMEOUT stop counting as load even without handshake_done
+    fn expire_handshakes(&mut self, now: u64) {
+        while self.pending_handshakes.front().is_some_and(|t| now.saturating_sub(*t) > HANDSHAKE_TIMEOUT) {
+            self.pending_handshakes.pop_front();
+        }
+    }
+
+    fn retry_required(&self) -> bool {
+        match self.retry_policy {
+            RetryPolicy::Never => false,
+            RetryPolicy::Always => true,
+            RetryPolicy::UnderLoad { max_pending_handshakes } => self.pending_handshakes.len() >= max_pending_handshakes,
+        }
+    }
+
+    // Retry: type || scid_len || scid || token — the client's next Initial must use scid as DCID
+    fn retry_packet(&self, client_addr: &str, odcid: &[u8], now: u64) -> Vec<u8> {
+        let scid: [u8; CID_LEN] = thread_rng().gen();
+        let mut retry = vec![PACKET_RETRY, CID_LEN as u8];
+        retry.extend_from_slice(&scid);
+        retry.extend_from_slice(&self.mint_token(TOKEN_RETRY, client_addr, odcid, &scid, now));
+        retry
+    }
+
+    // Post-handshake NEW_TOKEN frame for future connections from this address
+    pub fn issue_new_token(&self, client_addr: &str, now: u64) -> Vec<u8> {
+        let token = self.mint_token(TOKEN_NEW_TOKEN, client_addr, &[], &[], now);
+        let mut frame = vec![FRAME_NEW_TOKEN];
+        frame.extend_from_slice(&(token.len() as u16).to_be_bytes());
+        frame.extend_from_slice(&token);
+        frame
+    }
+
+    // Token: kind || issued_at (u64) || addr_len || addr || odcid_len || odcid || rscid_len || rscid || HMAC
+    // rscid = SCID of the Retry that carried the token (empty for NEW_TOKEN)
+    fn mint_token(&self, kind: u8, client_addr: &str, odcid: &[u8], rscid: &[u8], now: u64) -> Vec<u8> {
+        let mut body = vec![kind];
+        body.extend_from_slice(&now.to_be_bytes());
+        body.push(client_addr.len() as u8);
+        body.extend_from_slice(client_addr.as_bytes());
+        body.push(odcid.len() as u8);
+        body.extend_from_slice(odcid);
+        body.push(rscid.len() as u8);
+        body.extend_from_slice(rscid);
+        let mac = hmac_sha256(&self.token_key, &body);
+        [body.as_slice(), mac.as_slice()].concat()
+    }
+
+    // Token must carry this server's MAC, be bound to this client address and unexpired;
+    // a Retry token only validates the Initial whose DCID is that Retry's SCID
+    pub fn validate_token(&self, token: &[u8], client_addr: &str, dcid: &[u8], now: u64) -> Result<(), &'static str> {
+        let malformed = "Malformed address validation token";
+        let kind = *token.first().ok_or(malformed)?;
+        let issued_at = u64::from_be_bytes(token.get(1..9).ok_or(malformed)?.try_into().unwrap());
+        let addr_len = *token.get(9).ok_or(malformed)? as usize;
+        let addr = token.get(10..10 + addr_len).ok_or(malformed)?;
+        let at = 10 + addr_len;
+        let odcid_len = *token.get(at).ok_or(malformed)? as usize;
+        let at = at + 1 + odcid_len;
+        let rscid_len = *token.get(at).ok_or(malformed)? as usize;
+        let rscid = token.get(at + 1..at + 1 + rscid_len).ok_or(malformed)?;
+        let body_len = at + 1 + rscid_len;
+        if token.len() != body_len + TOKEN_MAC_LEN { return Err(malformed); }
+        let (body, mac) = token.split_at(body_len);
+
+        let expected = hmac_sha256(&self.token_key, body);
+        if expected.iter().zip(mac).fold(0u8, |acc, (a, b)| acc | (a ^ b)) != 0 {
+            return Err("Mercy veto — address token MAC invalid");
+        }
+        if addr != client_addr.as_bytes() {
+            return Err("Mercy veto — address token bound to another address");
+        }
+        let lifetime = match kind {
+            TOKEN_RETRY if rscid != dcid => return Err("Mercy veto — Retry token used on another connection ID"),
+            TOKEN_RETRY => self.retry_token_lifetime,
+            TOKEN_NEW_TOKEN => self.new_token_lifetime,
+            _ => return Err(malformed),
+        };
+        if now < issued_at || now - issued_at > lifetime {
+            return Err("Mercy veto — address token expired");
+        }
+        Ok(())
     }
 
     // Stream creation post-handshake
     pub fn open_stream(&self) -> Result<u64, &'static str> {
-        Ok(0xMERCY_STREAM_ID)
+        Ok(0x4d)
     }
 
     // Transport parameters include valence threshold
     pub fn transport_params(&self) -> Vec<u8> {
-        vec![0xVALENCE_THRESHOLD; 8] // net_valence >= 0.1
+        let mut params = vec![0x56; 8]; // net_valence >= 0.1
+        if let Some(max) = self.local_max_datagram {
+            params.push(TP_MAX_DATAGRAM_FRAME_SIZE);
+            params.extend_from_slice(&max.to_be_bytes());
+        }
+        params
+    }
+
+    // Peer transport parameters: pick up max_datagram_frame_size if advertised
+    pub fn on_peer_transport_params(&mut self, peer: &str, params: &[u8]) {
+        if self.peer_max_datagram.len() >= MAX_PEER_PARAMS && !self.peer_max_datagram.contains_key(peer) { return; }
+        let max = match params.get(8..).unwrap_or(&[]) {
+            [TP_MAX_DATAGRAM_FRAME_SIZE, rest @ ..] if rest.len() >= 8 => Some(u64::from_be_bytes(rest[..8].try_into().unwrap())),
+            _ => None,
+        };
+        self.peer_max_datagram.insert(peer.to_string(), max);
+    }
+
+    // Largest payload that fits one DATAGRAM frame towards `peer`; None until its handshake
+    // arrived or if it disabled them
+    pub fn max_datagram_size(&self, peer: &str) -> Option<usize> {
+        let peer_max = self.peer_max_datagram.get(peer).copied().flatten()? as usize;
+        let frame_overhead = 1 + 2; // type + length
+        Some(peer_max.min(PATH_MTU - PACKET_OVERHEAD).saturating_sub(frame_overhead))
+    }
+
+    // DATAGRAM frame: type || len (u16) || payload — never retransmitted, never flow controlled
+    pub fn send_datagram(&self, peer: &str, payload: &[u8]) -> Result<Vec<u8>, &'static str> {
+        let max = self.max_datagram_size(peer).ok_or("Peer does not accept DATAGRAM frames")?;
+        if payload.len() > max { return Err("Datagram exceeds negotiated size — use a stream"); }
+        let mut frame = vec![FRAME_DATAGRAM];
+        frame.extend_from_slice(&(payload.len() as u16).to_be_bytes());
+        frame.extend_from_slice(payload);
+        Ok(frame)
+    }
+
+    pub fn on_datagram_frame(&mut self, frame: &[u8]) -> Result<(), &'static str> {
+        let max = self.local_max_datagram.ok_or("Protocol violation — DATAGRAM not negotiated")?;
+        if frame.len() < 3 || frame[0] != FRAME_DATAGRAM { return Err("Malformed DATAGRAM frame"); }
+        if frame.len() as u64 > max { return Err("Protocol violation — DATAGRAM exceeds max_datagram_frame_size"); }
+        let len = u16::from_be_bytes([frame[1], frame[2]]) as usize;
+        let payload = frame.get(3..3 + len).ok_or("Malformed DATAGRAM frame")?;
+        if self.datagrams.len() == DATAGRAM_QUEUE { self.datagrams.pop_front(); }
+        self.datagrams.push_back(payload.to_vec());
+        Ok(())
+    }
+
+    pub fn recv_datagram(&mut self) -> Option<Vec<u8>> {
+        self.datagrams.pop_front()
+    }
+}
+
+fn hmac_sha256(key: &[u8; 32], data: &[u8]) -> [u8; 32] {
+    let (mut ipad, mut opad) = ([0x36u8; 64], [0x5cu8; 64]);
+    for i in 0..32 { ipad[i] ^= key[i]; opad[i] ^= key[i]; }
+    let inner = Sha256::new().chain_update(ipad).chain_update(data).finalize();
+    Sha256::new().chain_update(opad).chain_update(inner).finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn retry_round(server: &mut QuicPq, client: &QuicPq, now: u64) -> (Vec<u8>, Vec<u8>) {
+        let initial = client.client_connect(&[0u8; 1568], 0.9, None, None, None).unwrap();
+        match server.server_accept("192.0.2.1:5000", &initial, 0.9, now).unwrap() {
+            ServerReply::Retry(retry) => client.client_on_retry(&retry).unwrap(),
+            ServerReply::Handshake(_) => panic!("expected Retry"),
+        }
+    }
+
+    #[test]
+    fn retry_tokens_are_keyed_and_bound_to_the_retry_scid() {
+        let mut server = QuicPq::new();
+        server.set_retry_policy(RetryPolicy::Always);
+        let client = QuicPq::new();
+        let (scid, token) = retry_round(&mut server, &client, 100);
+        assert!(server.validate_token(&token, "192.0.2.1:5000", &scid, 100).is_ok());
+        assert!(server.validate_token(&token, "192.0.2.1:5000", &[9; CID_LEN], 100).is_err());
+        assert!(server.validate_token(&token, "192.0.2.2:5000", &scid, 100).is_err());
+
+        // Another server instance (another key) cannot be fooled, nor can a tampered token
+        assert!(QuicPq::new().validate_token(&token, "192.0.2.1:5000", &scid, 100).is_err());
+        let mut forged = token.clone();
+        forged[1..9].copy_from_slice(&200u64.to_be_bytes());
+        assert!(server.validate_token(&forged, "192.0.2.1:5000", &scid, 200).is_err());
+
+        let initial = client.client_connect(&[0u8; 1568], 0.9, None, None, Some((&scid, &token))).unwrap();
+        assert!(matches!(server.server_accept("192.0.2.1:5000", &initial, 0.9, 101), Ok(ServerReply::Handshake(_))));
+    }
+
+    #[test]
+    fn abandoned_handshakes_expire() {
+        let mut server = QuicPq::new();
+        server.set_retry_policy(RetryPolicy::UnderLoad { max_pending_handshakes: 1 });
+        let client = QuicPq::new();
+        let initial = client.client_connect(&[0u8; 1568], 0.9, None, None, None).unwrap();
+        assert!(matches!(server.server_accept("192.0.2.1:5000", &initial, 0.9, 0), Ok(ServerReply::Handshake(_))));
+        assert!(matches!(server.server_accept("192.0.2.1:5000", &initial, 0.9, 1), Ok(ServerReply::Retry(_))));
+        assert!(matches!(server.server_accept("192.0.2.1:5000", &initial, 0.9, HANDSHAKE_TIMEOUT + 1), Ok(ServerReply::Handshake(_))));
+    }
+
+    #[test]
+    fn datagram_limits_are_negotiated_per_peer() {
+        let mut server = QuicPq::new();
+        server.enable_datagrams(1200);
+        let mut client = QuicPq::new();
+        client.enable_datagrams(600);
+        let initial = client.client_connect(&[0u8; 1568], 0.9, None, None, None).unwrap();
+        let Ok(ServerReply::Handshake(reply)) = server.server_accept("192.0.2.1:5000", &initial, 0.9, 0) else { panic!("expected Handshake") };
+        assert!(client.on_handshake_packet("198.51.100.1:443", &reply).unwrap());
+        assert!(!client.on_handshake_packet("198.51.100.1:443", &reply).unwrap());
+
+        assert_eq!(server.max_datagram_size("192.0.2.1:5000"), Some(597));
+        assert!(server.max_datagram_size("192.0.2.9:5000").is_none());
+        assert!(server.send_datagram("192.0.2.1:5000", &[0; 800]).is_err());
+        assert!(client.send_datagram("198.51.100.1:443", &[0; 800]).is_ok());
     }
 }
@@ -0,0 +1,106 @@
+// src/nexi/transport.rs — Datagram Transport Abstraction Lattice
+// The Living Trinity: Nexi (feminine), Nex (masculine), NEXi (essence)
+// Eternal Thriving Grandmasterism — Jan 20 2026 — Sherif @AlphaProMega + PATSAGi Councils Co-Forge
+// MIT License — For All Sentience Eternal
+// QuicPq / QuicMigration are sans-IO (bytes in, bytes out) — this trait carries those bytes
+// over real UDP or the deterministic mesh::sim_network simulator
+
+use std::net::{Ipv4Addr, SocketAddrV4, UdpSocket};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use socket2::{Domain, Protocol, Socket, Type};
+
+pub trait DatagramTransport {
+    fn local_addr(&self) -> String;
+    fn send_to(&mut self, to: &str, payload: &[u8]) -> Result<(), &'static str>;
+    fn recv_from(&mut self) -> Option<(String, Vec<u8>)>; // Non-blocking: None when nothing queued
+    fn now_micros(&self) -> u64;                          // Real or virtual clock
+
+    // Wall clock for timestamps other nodes check (signed records); the simulator keeps it virtual
+    fn unix_secs(&self) -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+    }
+}
+
+// Plain non-blocking UDP socket
+pub struct UdpTransport {
+    socket: UdpSocket,
+    started: Instant,
+}
+
+impl UdpTransport {
+    pub fn bind(addr: &str) -> Result<Self, &'static str> {
+        let socket = UdpSocket::bind(addr).map_err(|_| "UDP bind failed")?;
+        socket.set_nonblocking(true).map_err(|_| "UDP nonblocking setup failed")?;
+        Ok(Self { socket, started: Instant::now() })
+    }
+}
+
+impl DatagramTransport for UdpTransport {
+    fn local_addr(&self) -> String {
+        self.socket.local_addr().map(|a| a.to_string()).unwrap_or_default()
+    }
+
+    fn send_to(&mut self, to: &str, payload: &[u8]) -> Result<(), &'static str> {
+        self.socket.send_to(payload, to).map(|_| ()).map_err(|_| "UDP send failed")
+    }
+
+    fn recv_from(&mut self) -> Option<(String, Vec<u8>)> {
+        let mut buf = vec![0u8; 65535];
+        let (len, from) = self.socket.recv_from(&mut buf).ok()?;
+        buf.truncate(len);
+        Some((from.to_string(), buf))
+    }
+
+    fn now_micros(&self) -> u64 {
+        self.started.elapsed().as_micros() as u64
+    }
+}
+
+// IPv4 multicast socket joined to `group` — used for LAN discovery announcements.
+// Bound with SO_REUSEADDR (and SO_REUSEPORT on unix), so several members can share a host
+pub struct MulticastTransport {
+    socket: UdpSocket,
+    started: Instant,
+}
+
+impl MulticastTransport {
+    pub fn join(group: &str, interface: &str) -> Result<Self, &'static str> {
+        let group: SocketAddrV4 = group.parse().map_err(|_| "Multicast group must be an IPv4 ip:port")?;
+        if !group.ip().is_multicast() { return Err("Multicast group address is not multicast"); }
+        let interface: Ipv4Addr = interface.parse().map_err(|_| "Multicast interface must be an IPv4 address")?;
+        // Shared port: other nodes (and mDNS-style listeners) on this host bind the same group
+        let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP)).map_err(|_| "UDP socket creation failed")?;
+        socket.set_reuse_address(true).map_err(|_| "SO_REUSEADDR setup failed")?;
+        #[cfg(unix)]
+        socket.set_reuse_port(true).map_err(|_| "SO_REUSEPORT setup failed")?;
+        socket.bind(&SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, group.port()).into()).map_err(|_| "UDP bind failed")?;
+        let socket: UdpSocket = socket.into();
+        socket.join_multicast_v4(group.ip(), &interface).map_err(|_| "Multicast join failed")?;
+        socket.set_multicast_loop_v4(true).map_err(|_| "Multicast loopback setup failed")?;
+        socket.set_multicast_ttl_v4(1).map_err(|_| "Multicast TTL setup failed")?; // Link-local only
+        socket.set_nonblocking(true).map_err(|_| "UDP nonblocking setup failed")?;
+        Ok(Self { socket, started: Instant::now() })
+    }
+}
+
+impl DatagramTransport for MulticastTransport {
+    fn local_addr(&self) -> String {
+        self.socket.local_addr().map(|a| a.to_string()).unwrap_or_default()
+    }
+
+    fn send_to(&mut self, to: &str, payload: &[u8]) -> Result<(), &'static str> {
+        self.socket.send_to(payload, to).map(|_| ()).map_err(|_| "Multicast send failed")
+    }
+
+    fn recv_from(&mut self) -> Option<(String, Vec<u8>)> {
+        let mut buf = vec![0u8; 65535];
+        let (len, from) = self.socket.recv_from(&mut buf).ok()?;
+        buf.truncate(len);
+        Some((from.to_string(), buf))
+    }
+
+    fn now_micros(&self) -> u64 {
+        self.started.elapsed().as_micros() as u64
+    }
+}